@@ -145,7 +145,7 @@ struct ContentView: View {
                     name: "Push-ups",
                     description: "Classic bodyweight exercise for chest and triceps",
                     muscleGroups: ["Chest", "Triceps", "Shoulders"],
-                    equipmentNeeded: nil,
+                    equipmentIds: [],
                     difficultyLevel: 3
                 ),
                 Exercise(
@@ -153,7 +153,7 @@ struct ContentView: View {
                     name: "Squats",
                     description: "Fundamental lower body compound movement",
                     muscleGroups: ["Quadriceps", "Glutes", "Hamstrings"],
-                    equipmentNeeded: nil,
+                    equipmentIds: [],
                     difficultyLevel: 4
                 ),
                 Exercise(
@@ -161,7 +161,7 @@ struct ContentView: View {
                     name: "Deadlift",
                     description: "King of all exercises - full body compound movement",
                     muscleGroups: ["Hamstrings", "Glutes", "Back", "Traps"],
-                    equipmentNeeded: "Barbell",
+                    equipmentIds: ["barbell"],
                     difficultyLevel: 9
                 )
             ]
@@ -239,8 +239,8 @@ struct ExerciseRowView: View {
                 .font(.caption)
                 .foregroundColor(.blue)
 
-            if let equipment = exercise.equipmentNeeded {
-                Text("Equipment: \(equipment)")
+            if !exercise.equipmentIds.isEmpty {
+                Text("Equipment: \(exercise.equipmentIds.joined(separator: ", "))")
                     .font(.caption)
                     .foregroundColor(.orange)
             }
@@ -68,7 +68,7 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Push-up",
             description: "Basic bodyweight exercise",
             muscleGroups: ["Chest", "Triceps"],
-            equipmentNeeded: nil,
+            equipmentIds: [],
             difficultyLevel: 3
         )
 
@@ -78,7 +78,7 @@ final class WeightliftingAppTests: XCTestCase {
         XCTAssertEqual(exercise.name, "Push-up")
         XCTAssertEqual(exercise.description, "Basic bodyweight exercise")
         XCTAssertEqual(exercise.muscleGroups, ["Chest", "Triceps"])
-        XCTAssertNil(exercise.equipmentNeeded)
+        XCTAssertTrue(exercise.equipmentIds.isEmpty)
         XCTAssertEqual(exercise.difficultyLevel, 3)
 
         logger.info("✅ Exercise creation test passed")
@@ -92,13 +92,13 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Bench Press",
             description: "Upper body strength exercise",
             muscleGroups: ["Chest", "Triceps", "Shoulders"],
-            equipmentNeeded: "Barbell",
+            equipmentIds: ["barbell"],
             difficultyLevel: 7
         )
 
-        logger.info("🏋️ Created exercise: \(exercise.name) requiring: \(exercise.equipmentNeeded ?? "none")")
+        logger.info("🏋️ Created exercise: \(exercise.name) requiring: \(exercise.equipmentIds.joined(separator: ", "))")
 
-        XCTAssertEqual(exercise.equipmentNeeded, "Barbell")
+        XCTAssertEqual(exercise.equipmentIds, ["barbell"])
         XCTAssertEqual(exercise.difficultyLevel, 7)
 
         logger.info("✅ Exercise with equipment test passed")
@@ -114,7 +114,7 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Squat",
             description: "Compound leg exercise",
             muscleGroups: ["Quadriceps", "Glutes"],
-            equipmentNeeded: "Barbell",
+            equipmentIds: ["barbell"],
             difficultyLevel: 6
         )
 
@@ -133,7 +133,7 @@ final class WeightliftingAppTests: XCTestCase {
         XCTAssertEqual(retrieved.name, exercise.name)
         XCTAssertEqual(retrieved.description, exercise.description)
         XCTAssertEqual(retrieved.muscleGroups, exercise.muscleGroups)
-        XCTAssertEqual(retrieved.equipmentNeeded, exercise.equipmentNeeded)
+        XCTAssertEqual(retrieved.equipmentIds, exercise.equipmentIds)
         XCTAssertEqual(retrieved.difficultyLevel, exercise.difficultyLevel)
 
         logger.info("✅ Add and get exercise test passed")
@@ -167,7 +167,7 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Bench Press",
             description: "Upper body exercise",
             muscleGroups: ["Chest", "Triceps"],
-            equipmentNeeded: "Barbell",
+            equipmentIds: ["barbell"],
             difficultyLevel: 6
         )
 
@@ -176,7 +176,7 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Deadlift",
             description: "Full body exercise",
             muscleGroups: ["Hamstrings", "Glutes", "Back"],
-            equipmentNeeded: "Barbell",
+            equipmentIds: ["barbell"],
             difficultyLevel: 9
         )
 
@@ -209,7 +209,7 @@ final class WeightliftingAppTests: XCTestCase {
             name: "Pull-up",
             description: "Upper body pulling exercise",
             muscleGroups: ["Back", "Biceps"],
-            equipmentNeeded: "Pull-up bar",
+            equipmentIds: ["pull-up-bar"],
             difficultyLevel: 5
         )
 
@@ -265,7 +265,7 @@ final class WeightliftingAppTests: XCTestCase {
                 name: "Push-ups",
                 description: "Bodyweight chest exercise",
                 muscleGroups: ["Chest", "Triceps", "Shoulders"],
-                equipmentNeeded: nil,
+                equipmentIds: [],
                 difficultyLevel: 3
             ),
             WeightliftingApp.Exercise(
@@ -273,7 +273,7 @@ final class WeightliftingAppTests: XCTestCase {
                 name: "Squats",
                 description: "Bodyweight leg exercise",
                 muscleGroups: ["Quadriceps", "Glutes"],
-                equipmentNeeded: nil,
+                equipmentIds: [],
                 difficultyLevel: 4
             ),
             WeightliftingApp.Exercise(
@@ -281,7 +281,7 @@ final class WeightliftingAppTests: XCTestCase {
                 name: "Deadlift",
                 description: "Heavy compound exercise",
                 muscleGroups: ["Hamstrings", "Glutes", "Back"],
-                equipmentNeeded: "Barbell",
+                equipmentIds: ["barbell"],
                 difficultyLevel: 9
             )
         ]
@@ -300,14 +300,14 @@ final class WeightliftingAppTests: XCTestCase {
         // 4. Test individual retrieval
         logger.info("🔍 Testing individual exercise retrieval")
         let pushups = try repository.getExercise(id: "wf1")
-        logger.info("📋 Retrieved: \(pushups.name) (Equipment: \(pushups.equipmentNeeded ?? "none"))")
+        logger.info("📋 Retrieved: \(pushups.name) (Equipment: \(pushups.equipmentIds.joined(separator: ", ")))")
         XCTAssertEqual(pushups.name, "Push-ups")
-        XCTAssertNil(pushups.equipmentNeeded)
+        XCTAssertTrue(pushups.equipmentIds.isEmpty)
 
         let deadlift = try repository.getExercise(id: "wf3")
-        logger.info("📋 Retrieved: \(deadlift.name) (Equipment: \(deadlift.equipmentNeeded ?? "none"), Difficulty: \(deadlift.difficultyLevel))")
+        logger.info("📋 Retrieved: \(deadlift.name) (Equipment: \(deadlift.equipmentIds.joined(separator: ", ")), Difficulty: \(deadlift.difficultyLevel))")
         XCTAssertEqual(deadlift.name, "Deadlift")
-        XCTAssertEqual(deadlift.equipmentNeeded, "Barbell")
+        XCTAssertEqual(deadlift.equipmentIds, ["barbell"])
         XCTAssertEqual(deadlift.difficultyLevel, 9)
 
         // 5. Delete one exercise
@@ -344,8 +344,8 @@ final class WeightliftingAppTests: XCTestCase {
             id: "duplicate-test",
             name: "Test Exercise",
             description: nil,
-            muscleGroups: ["Test"],
-            equipmentNeeded: nil,
+            muscleGroups: ["Abs"],
+            equipmentIds: [],
             difficultyLevel: 1
         )
 
@@ -376,8 +376,8 @@ final class WeightliftingAppTests: XCTestCase {
                         id: "perf-\(i)",
                         name: "Exercise \(i)",
                         description: "Performance test exercise \(i)",
-                        muscleGroups: ["Muscle\(i % 5)"],
-                        equipmentNeeded: i % 2 == 0 ? "Equipment\(i)" : nil,
+                        muscleGroups: [WeightliftingApp.muscleTaxonomy()[i % 5].name],
+                        equipmentIds: i % 2 == 0 ? ["dumbbell"] : [],
                         difficultyLevel: UInt8((i % 10) + 1)
                     )
                     try repo.addExercise(exercise: exercise)
@@ -401,8 +401,8 @@ final class WeightliftingAppTests: XCTestCase {
                 id: "getall-\(i)",
                 name: "Exercise \(i)",
                 description: "Test exercise \(i)",
-                muscleGroups: ["Muscle\(i % 3)"],
-                equipmentNeeded: nil,
+                muscleGroups: [WeightliftingApp.muscleTaxonomy()[i % 3].name],
+                equipmentIds: [],
                 difficultyLevel: UInt8((i % 10) + 1)
             )
             try repository.addExercise(exercise: exercise)
@@ -0,0 +1,658 @@
+// Achievements unlocked by rules over the training data, and weekly training streaks
+
+use crate::calendar::CalendarDate;
+use crate::error::WeightliftingError;
+use crate::models::LoggedSet;
+use crate::repository::ExerciseRepository;
+use crate::standards::CanonicalLift;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Receives achievements as soon as a write unlocks them
+pub trait AchievementListener: Send + Sync {
+    fn on_achievements_unlocked(&self, achievements: Vec<UnlockedAchievement>);
+}
+
+/// What it takes to unlock an achievement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AchievementRule {
+    /// A set of an exercise at `weight_kg` or more
+    ExerciseWeight { exercise_id: String, weight_kg: f64 },
+    /// A set at `weight_kg` or more of any exercise mapped to `lift`
+    LiftWeight { lift: CanonicalLift, weight_kg: f64 },
+    /// `count` workouts logged
+    Workouts { count: u32 },
+    /// `volume_kg` lifted across all sets
+    TotalVolume { volume_kg: f64 },
+    /// Training in `weeks` consecutive weeks
+    WeeklyStreak { weeks: u32 },
+    /// Best squat, bench and deadlift from the lifter profile adding up to `total_kg`
+    PowerliftingTotal { total_kg: f64 },
+}
+
+/// A badge and the rule that unlocks it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub rule: AchievementRule,
+}
+
+/// An achievement and when the training data first met its rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    pub unlocked_at: i64, // Timestamp of the set or activity that unlocked it
+}
+
+/// Consecutive weeks with at least one set or cardio activity
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingStreak {
+    pub current_weeks: u32, // Still running if the lifter hasn't trained yet this week
+    pub longest_weeks: u32,
+    pub current_streak_start: Option<CalendarDate>, // Monday of the streak's first week
+    pub trained_this_week: bool,
+}
+
+fn achievement(id: &str, name: &str, description: &str, rule: AchievementRule) -> Achievement {
+    Achievement {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        rule,
+    }
+}
+
+/// The achievements every new database starts with
+pub fn default_achievements() -> Vec<Achievement> {
+    use AchievementRule::*;
+    vec![
+        achievement("first-workout", "First Workout", "Log your first workout", Workouts { count: 1 }),
+        achievement("workouts-10", "Regular", "Log 10 workouts", Workouts { count: 10 }),
+        achievement("workouts-100", "Centurion", "Log 100 workouts", Workouts { count: 100 }),
+        achievement("streak-4", "Consistent", "Train 4 weeks in a row", WeeklyStreak { weeks: 4 }),
+        achievement("streak-10", "Habit Formed", "Train 10 weeks in a row", WeeklyStreak { weeks: 10 }),
+        achievement("streak-52", "Year Round", "Train 52 weeks in a row", WeeklyStreak { weeks: 52 }),
+        achievement("bench-100", "Two Plates Bench", "Bench press 100 kg", LiftWeight { lift: CanonicalLift::BenchPress, weight_kg: 100.0 }),
+        achievement("squat-140", "Three Plates Squat", "Squat 140 kg", LiftWeight { lift: CanonicalLift::Squat, weight_kg: 140.0 }),
+        achievement("deadlift-180", "Four Plates Pull", "Deadlift 180 kg", LiftWeight { lift: CanonicalLift::Deadlift, weight_kg: 180.0 }),
+        achievement("volume-100t", "Heavy Lifter", "Lift 100 tonnes in total", TotalVolume { volume_kg: 100_000.0 }),
+        achievement("total-500", "Elite Total", "Reach a 500 kg powerlifting total", PowerliftingTotal { total_kg: 500.0 }),
+    ]
+}
+
+impl AchievementRule {
+    /// Validate the rule's thresholds
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |label: &str, value: f64| {
+            if value.is_finite() && value > 0.0 {
+                Ok(())
+            } else {
+                Err(format!("{} must be positive, got {}", label, value))
+            }
+        };
+
+        match self {
+            AchievementRule::ExerciseWeight { weight_kg, .. } | AchievementRule::LiftWeight { weight_kg, .. } => positive("Weight", *weight_kg),
+            AchievementRule::TotalVolume { volume_kg } => positive("Volume", *volume_kg),
+            AchievementRule::PowerliftingTotal { total_kg } => positive("Total", *total_kg),
+            AchievementRule::Workouts { count: 0 } | AchievementRule::WeeklyStreak { weeks: 0 } => {
+                Err("Count must be at least 1".to_string())
+            }
+            AchievementRule::Workouts { .. } | AchievementRule::WeeklyStreak { .. } => Ok(()),
+        }
+    }
+}
+
+/// Monday of the week containing `timestamp`, as days since the epoch
+fn week_of(timestamp: i64) -> i64 {
+    CalendarDate::from_timestamp(timestamp).start_of_week().to_days()
+}
+
+/// A write the rules are checked against
+pub(crate) enum TrainingEvent<'a> {
+    /// Sets just added to the training log
+    Sets(&'a [LoggedSet]),
+    /// A cardio activity just logged, by its start time
+    Cardio(i64),
+}
+
+/// Lifetime volume and workout count, kept up to date as sets are logged and deleted
+///
+/// Bodyweight sets count at the bodyweight recorded when they were performed,
+/// so the totals are recounted whenever a bodyweight is added or removed.
+#[derive(Debug, Clone, Copy, Default)]
+struct TrainingTotals {
+    volume_kg: f64,
+    workouts: u32,
+}
+
+/// Everything the rules are evaluated against, loaded on first use
+#[derive(Default)]
+struct TrainingHistory {
+    sets: Option<Vec<LoggedSet>>,
+    activity_times: Option<Vec<i64>>,
+    totals: Option<TrainingTotals>,
+}
+
+impl TrainingHistory {
+    /// All logged sets, oldest first
+    fn sets(&mut self, conn: &Connection) -> Result<&[LoggedSet], WeightliftingError> {
+        if self.sets.is_none() {
+            self.sets = Some(ExerciseRepository::query_sets_between(conn, None, i64::MIN, i64::MAX)?);
+        }
+        Ok(self.sets.as_deref().unwrap_or_default())
+    }
+
+    /// Times of every set and cardio activity, oldest first
+    fn activity_times(&mut self, conn: &Connection) -> Result<&[i64], WeightliftingError> {
+        if self.activity_times.is_none() {
+            let mut stmt = conn.prepare(
+                "SELECT performed_at FROM logged_sets UNION ALL SELECT started_at FROM cardio_activities ORDER BY 1",
+            )?;
+            let times = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<i64>, _>>()?;
+            self.activity_times = Some(times);
+        }
+        Ok(self.activity_times.as_deref().unwrap_or_default())
+    }
+
+    fn totals(&mut self, conn: &Connection) -> Result<TrainingTotals, WeightliftingError> {
+        if self.totals.is_none() {
+            self.totals = Some(query_training_totals(conn)?);
+        }
+        Ok(self.totals.unwrap_or_default())
+    }
+
+    /// When the rule was first met, if it has been, scanning the whole history
+    fn achieved_at(&mut self, conn: &Connection, rule: &AchievementRule) -> Result<Option<i64>, WeightliftingError> {
+        match rule {
+            AchievementRule::ExerciseWeight { exercise_id, weight_kg } => {
+                Ok(first_set_at(self.sets(conn)?, *weight_kg, |set| set.exercise_id == *exercise_id))
+            }
+            AchievementRule::LiftWeight { lift, weight_kg } => {
+                let exercise_ids = ExerciseRepository::query_exercises_for_lift(conn, *lift)?;
+                Ok(first_set_at(self.sets(conn)?, *weight_kg, |set| exercise_ids.contains(&set.exercise_id)))
+            }
+            AchievementRule::Workouts { count } => {
+                // Sets without a session count as one workout per day, as in usage statistics
+                let mut seen = HashSet::new();
+                Ok(self.sets(conn)?
+                    .iter()
+                    .filter(|set| seen.insert(set.session_key()))
+                    .nth((*count as usize).saturating_sub(1))
+                    .map(|set| set.performed_at))
+            }
+            AchievementRule::TotalVolume { volume_kg } => {
+                let mut total = 0.0;
+                for set in self.sets(conn)? {
+                    total += ExerciseRepository::set_volume(conn, set)?;
+                    if total >= *volume_kg {
+                        return Ok(Some(set.performed_at));
+                    }
+                }
+                Ok(None)
+            }
+            AchievementRule::WeeklyStreak { weeks } => {
+                let mut run = 0;
+                let mut last_week = None;
+                for &time in self.activity_times(conn)? {
+                    let week = week_of(time);
+                    match last_week {
+                        Some(last) if last == week => continue,
+                        Some(last) if week - last == 7 => run += 1,
+                        _ => run = 1,
+                    }
+                    last_week = Some(week);
+                    if run >= *weeks {
+                        return Ok(Some(time));
+                    }
+                }
+                Ok(None)
+            }
+            AchievementRule::PowerliftingTotal { total_kg } => {
+                let Some(profile) = ExerciseRepository::query_lifter_profile(conn)? else {
+                    return Ok(None);
+                };
+                let lifts = [profile.squat_exercise_id, profile.bench_exercise_id, profile.deadlift_exercise_id];
+                let mut best: [Option<f64>; 3] = [None; 3];
+                for set in self.sets(conn)?.iter().filter(|s| s.is_strength_comparable()) {
+                    for (i, exercise_id) in lifts.iter().enumerate() {
+                        if exercise_id.as_deref() == Some(set.exercise_id.as_str()) {
+                            best[i] = Some(best[i].map_or(set.weight_kg, |b| b.max(set.weight_kg)));
+                        }
+                    }
+                    if best.iter().copied().sum::<Option<f64>>().is_some_and(|total| total >= *total_kg) {
+                        return Ok(Some(set.performed_at));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Whether a write just met the rule, looking only at what it added
+    ///
+    /// Returns the time of the newest set or activity in the write. Nothing
+    /// here scans the training history: weights come from the new sets,
+    /// volume and workouts from the running totals, streaks from one indexed
+    /// probe per week and powerlifting totals from each lift's heaviest set.
+    fn achieved_by(&mut self, conn: &Connection, rule: &AchievementRule, event: &TrainingEvent) -> Result<Option<i64>, WeightliftingError> {
+        let (sets, at) = match *event {
+            TrainingEvent::Sets(sets) => match sets.iter().map(|set| set.performed_at).max() {
+                Some(at) => (sets, at),
+                None => return Ok(None),
+            },
+            TrainingEvent::Cardio(started_at) => (&[][..], started_at),
+        };
+        let met = |met: bool| Ok(met.then_some(at));
+
+        match rule {
+            AchievementRule::ExerciseWeight { exercise_id, weight_kg } => {
+                Ok(first_set_at(sets, *weight_kg, |set| set.exercise_id == *exercise_id))
+            }
+            AchievementRule::LiftWeight { lift, weight_kg } => {
+                if sets.is_empty() {
+                    return Ok(None);
+                }
+                let exercise_ids = ExerciseRepository::query_exercises_for_lift(conn, *lift)?;
+                Ok(first_set_at(sets, *weight_kg, |set| exercise_ids.contains(&set.exercise_id)))
+            }
+            AchievementRule::Workouts { count } => met(!sets.is_empty() && self.totals(conn)?.workouts >= *count),
+            AchievementRule::TotalVolume { volume_kg } => met(!sets.is_empty() && self.totals(conn)?.volume_kg >= *volume_kg),
+            AchievementRule::WeeklyStreak { weeks } => met(streak_around(conn, at, *weeks)? >= *weeks),
+            AchievementRule::PowerliftingTotal { total_kg } => {
+                let Some(profile) = ExerciseRepository::query_lifter_profile(conn)? else {
+                    return Ok(None);
+                };
+                let lifts = [profile.squat_exercise_id, profile.bench_exercise_id, profile.deadlift_exercise_id];
+                if !sets.iter().any(|set| lifts.iter().any(|id| id.as_deref() == Some(set.exercise_id.as_str()))) {
+                    return Ok(None);
+                }
+                let mut total = 0.0;
+                for exercise_id in &lifts {
+                    match exercise_id {
+                        Some(id) => match heaviest_set_kg(conn, id)? {
+                            Some(weight_kg) => total += weight_kg,
+                            None => return Ok(None),
+                        },
+                        None => return Ok(None),
+                    }
+                }
+                met(total >= *total_kg)
+            }
+        }
+    }
+}
+
+/// Whether any set or cardio activity falls in the week starting on `monday` (days since the epoch)
+fn trained_in_week(conn: &Connection, monday: i64) -> Result<bool, WeightliftingError> {
+    let start = CalendarDate::from_days(monday).to_timestamp();
+    let end = CalendarDate::from_days(monday + 7).to_timestamp() - 1;
+    let trained = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM logged_sets WHERE performed_at BETWEEN ?1 AND ?2)
+             OR EXISTS(SELECT 1 FROM cardio_activities WHERE started_at BETWEEN ?1 AND ?2)",
+        params![start, end],
+        |row| row.get(0),
+    )?;
+    Ok(trained)
+}
+
+/// Length of the run of trained weeks through the week containing `at`, counting up to `needed`
+fn streak_around(conn: &Connection, at: i64, needed: u32) -> Result<u32, WeightliftingError> {
+    let week = week_of(at);
+    let mut run = 1;
+    for step in [-7, 7] {
+        let mut other = week + step;
+        while run < needed && trained_in_week(conn, other)? {
+            run += 1;
+            other += step;
+        }
+    }
+    Ok(run)
+}
+
+/// Heaviest strength-comparable set logged for an exercise
+fn heaviest_set_kg(conn: &Connection, exercise_id: &str) -> Result<Option<f64>, WeightliftingError> {
+    let weight_kg = conn.query_row(
+        "SELECT MAX(weight_kg) FROM logged_sets WHERE exercise_id = ?1 AND set_type IN ('standard', 'drop_set')",
+        [exercise_id],
+        |row| row.get(0),
+    )?;
+    Ok(weight_kg)
+}
+
+fn query_training_totals(conn: &Connection) -> Result<TrainingTotals, WeightliftingError> {
+    let totals = conn.query_row("SELECT volume_kg, workouts FROM training_totals WHERE id = 1", [], |row| {
+        Ok(TrainingTotals { volume_kg: row.get(0)?, workouts: row.get(1)? })
+    }).optional()?;
+    Ok(totals.unwrap_or_default())
+}
+
+/// Number of logged sets in the same workout as `set`, including it if it's stored
+fn sets_in_workout(conn: &Connection, set: &LoggedSet) -> Result<u32, WeightliftingError> {
+    let count = match set.session_id {
+        Some(ref session_id) => conn.query_row(
+            "SELECT COUNT(*) FROM logged_sets WHERE session_id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )?,
+        None => {
+            let day = set.performed_at.div_euclid(86_400) * 86_400;
+            conn.query_row(
+                "SELECT COUNT(*) FROM logged_sets WHERE session_id IS NULL AND performed_at BETWEEN ?1 AND ?2",
+                params![day, day + 86_399],
+                |row| row.get(0),
+            )?
+        }
+    };
+    Ok(count)
+}
+
+/// Time of the first strength-comparable set at `weight_kg` or more that matches
+fn first_set_at(sets: &[LoggedSet], weight_kg: f64, matches: impl Fn(&LoggedSet) -> bool) -> Option<i64> {
+    sets.iter()
+        .find(|set| set.is_strength_comparable() && set.weight_kg >= weight_kg && matches(set))
+        .map(|set| set.performed_at)
+}
+
+impl ExerciseRepository {
+    /// Save an achievement, replacing any existing achievement with the same ID
+    pub fn save_achievement(&self, achievement: Achievement) -> Result<(), WeightliftingError> {
+        info!("🏅 Saving achievement: {} (ID: {})", achievement.name, achievement.id);
+
+        if achievement.name.trim().is_empty() {
+            warn!("❌ Achievement name is empty");
+            return Err(WeightliftingError::InvalidInput {
+                message: "Achievement name cannot be empty".to_string(),
+            });
+        }
+        achievement.rule.validate().map_err(|message| {
+            warn!("❌ Invalid achievement rule: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        if let AchievementRule::ExerciseWeight { ref exercise_id, .. } = achievement.rule {
+            Self::ensure_exercise_exists(&conn, exercise_id)?;
+        }
+        Self::write_achievement(&conn, &achievement)?;
+        Ok(())
+    }
+
+    /// Get all achievements, sorted by name
+    pub fn get_all_achievements(&self) -> Result<Vec<Achievement>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_achievements(&conn)
+    }
+
+    /// Delete an achievement and its unlock
+    /// Returns true if the achievement was deleted, false if it wasn't found
+    pub fn delete_achievement(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting achievement with ID: {}", id);
+
+        let conn = self.connection()?;
+        conn.execute("DELETE FROM achievement_unlocks WHERE achievement_id = ?1", [&id])?;
+        let rows_affected = conn.execute("DELETE FROM achievements WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get every unlocked achievement, most recent first
+    pub fn get_unlocked_achievements(&self) -> Result<Vec<UnlockedAchievement>, WeightliftingError> {
+        let conn = self.connection()?;
+        let achievements: HashMap<String, Achievement> =
+            Self::query_achievements(&conn)?.into_iter().map(|a| (a.id.clone(), a)).collect();
+
+        let mut stmt = conn.prepare("SELECT achievement_id, unlocked_at FROM achievement_unlocks ORDER BY unlocked_at DESC, achievement_id")?;
+        let unlocks = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(unlocks
+            .into_iter()
+            .filter_map(|(id, unlocked_at)| {
+                achievements.get(&id).map(|achievement| UnlockedAchievement { achievement: achievement.clone(), unlocked_at })
+            })
+            .collect())
+    }
+
+    /// Check the locked achievements against the training data and unlock those whose rule is met
+    ///
+    /// Returns the newly unlocked achievements. Unlocks are kept even if the
+    /// data that earned them is later deleted. This looks at the whole
+    /// history, so it also finds rules met before they were added; logging
+    /// sets and cardio only checks what each write adds.
+    pub fn evaluate_achievements(&self) -> Result<Vec<UnlockedAchievement>, WeightliftingError> {
+        debug!("🏅 Evaluating achievements");
+
+        let mut history = TrainingHistory::default();
+        self.unlock_achievements(|conn, rule| history.achieved_at(conn, rule))
+    }
+
+    /// Unlock the locked achievements `achieved_at` finds met, in one transaction
+    ///
+    /// Overlapping evaluations can both find an achievement locked, so only
+    /// unlocks actually inserted are returned.
+    fn unlock_achievements(
+        &self,
+        mut achieved_at: impl FnMut(&Connection, &AchievementRule) -> Result<Option<i64>, WeightliftingError>,
+    ) -> Result<Vec<UnlockedAchievement>, WeightliftingError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let locked = {
+            let mut stmt = tx.prepare(
+                "SELECT id, name, description, rule FROM achievements
+                 WHERE id NOT IN (SELECT achievement_id FROM achievement_unlocks) ORDER BY name",
+            )?;
+            let locked = stmt.query_map([], achievement_from_row)?.collect::<Result<Vec<_>, _>>()?;
+            locked
+        };
+
+        let mut unlocked = Vec::new();
+        for achievement in locked {
+            let Some(unlocked_at) = achieved_at(&tx, &achievement.rule)? else {
+                continue;
+            };
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO achievement_unlocks (achievement_id, unlocked_at) VALUES (?1, ?2)",
+                params![achievement.id, unlocked_at],
+            )?;
+            if inserted > 0 {
+                info!("🏅 Unlocked achievement: {}", achievement.name);
+                unlocked.push(UnlockedAchievement { achievement, unlocked_at });
+            }
+        }
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit achievement unlocks: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to save achievement unlocks: {}", e),
+            }
+        })?;
+        Ok(unlocked)
+    }
+
+    /// Get the lifter's weekly training streaks as of `now`
+    pub fn get_training_streak(&self, now: i64) -> Result<TrainingStreak, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut history = TrainingHistory::default();
+        let mut weeks: Vec<i64> = history.activity_times(&conn)?.iter().filter(|&&t| t <= now).map(|&t| week_of(t)).collect();
+        weeks.dedup();
+
+        let mut longest_weeks = 0;
+        let mut run = 0;
+        for (i, week) in weeks.iter().enumerate() {
+            run = if i > 0 && week - weeks[i - 1] == 7 { run + 1 } else { 1 };
+            longest_weeks = longest_weeks.max(run);
+        }
+
+        // The streak survives the current week until it's over
+        let this_week = week_of(now);
+        let trained_this_week = weeks.last() == Some(&this_week);
+        let streak_alive = weeks.last().is_some_and(|&last| this_week - last <= 7);
+        let current_weeks = if streak_alive { run } else { 0 };
+        let current_streak_start = weeks
+            .last()
+            .filter(|_| streak_alive)
+            .map(|&last| CalendarDate::from_days(last - 7 * (current_weeks as i64 - 1)));
+
+        Ok(TrainingStreak { current_weeks, longest_weeks, current_streak_start, trained_this_week })
+    }
+
+    /// Set the listener told about achievements unlocked by later writes
+    pub fn set_achievement_listener(&self, listener: Box<dyn AchievementListener>) {
+        info!("🔔 Setting achievement listener");
+        *self.achievement_listener.lock().unwrap_or_else(|e| e.into_inner()) = Some(listener.into());
+    }
+
+    /// Stop notifying about unlocked achievements
+    pub fn clear_achievement_listener(&self) {
+        *self.achievement_listener.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Check achievements against a write and tell the listener about new ones
+    ///
+    /// The write already succeeded, so evaluation failures are logged rather than returned.
+    pub(crate) fn notify_achievements(&self, event: TrainingEvent) {
+        let mut history = TrainingHistory::default();
+        let unlocked = match self.unlock_achievements(|conn, rule| history.achieved_by(conn, rule, &event)) {
+            Ok(unlocked) => unlocked,
+            Err(e) => {
+                warn!("⚠️ Failed to evaluate achievements: {}", e);
+                return;
+            }
+        };
+
+        if unlocked.is_empty() {
+            return;
+        }
+        // Called without holding the lock so the listener may replace itself
+        let listener = self.achievement_listener.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some(listener) = listener {
+            debug!("🔔 Notifying listener of {} achievements", unlocked.len());
+            listener.on_achievements_unlocked(unlocked);
+        }
+    }
+
+    /// Add a set that was just inserted to the running training totals
+    pub(crate) fn add_to_training_totals(conn: &Connection, set: &LoggedSet) -> Result<(), WeightliftingError> {
+        let volume_kg = Self::set_volume(conn, set)?;
+        let starts_workout = sets_in_workout(conn, set)? == 1;
+        conn.execute(
+            "UPDATE training_totals SET volume_kg = volume_kg + ?1, workouts = workouts + ?2 WHERE id = 1",
+            params![volume_kg, starts_workout as u32],
+        )?;
+        Ok(())
+    }
+
+    /// Take a set that was just deleted out of the running training totals
+    pub(crate) fn remove_from_training_totals(conn: &Connection, set: &LoggedSet) -> Result<(), WeightliftingError> {
+        let volume_kg = Self::set_volume(conn, set)?;
+        let ends_workout = sets_in_workout(conn, set)? == 0;
+        conn.execute(
+            "UPDATE training_totals SET volume_kg = volume_kg - ?1, workouts = workouts - ?2 WHERE id = 1",
+            params![volume_kg, ends_workout as u32],
+        )?;
+        Ok(())
+    }
+
+    /// Recount the running training totals from the whole training log
+    pub(crate) fn recount_training_totals(conn: &Connection) -> Result<(), WeightliftingError> {
+        let sets = Self::query_sets_between(conn, None, i64::MIN, i64::MAX)?;
+        let volume_kg = sets.iter().map(|set| Self::set_volume(conn, set)).sum::<Result<f64, _>>()?;
+        let workouts = sets.iter().map(|set| set.session_key()).collect::<HashSet<_>>().len() as u32;
+        conn.execute(
+            "INSERT INTO training_totals (id, volume_kg, workouts) VALUES (1, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET volume_kg = excluded.volume_kg, workouts = excluded.workouts",
+            params![volume_kg, workouts],
+        )?;
+        debug!("🔢 Training totals from {} sets: {} kg over {} workouts", sets.len(), volume_kg, workouts);
+        Ok(())
+    }
+
+    fn write_achievement(conn: &Connection, achievement: &Achievement) -> Result<(), WeightliftingError> {
+        let rule_json = serde_json::to_string(&achievement.rule).map_err(|e| {
+            error!("❌ Failed to serialize achievement rule: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize achievement rule: {}", e),
+            }
+        })?;
+        conn.execute(
+            "INSERT INTO achievements (id, name, description, rule) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, description = excluded.description, rule = excluded.rule",
+            params![achievement.id, achievement.name, achievement.description, rule_json],
+        )?;
+        Ok(())
+    }
+
+    fn query_achievements(conn: &Connection) -> Result<Vec<Achievement>, WeightliftingError> {
+        let mut stmt = conn.prepare("SELECT id, name, description, rule FROM achievements ORDER BY name")?;
+        let achievements = stmt.query_map([], achievement_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(achievements)
+    }
+
+    /// Create the achievement tables if they don't exist, seeding the defaults into a new database
+    pub(crate) fn create_achievement_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating achievements, achievement_unlocks and training_totals tables if not exists");
+
+        let is_new: bool = conn.query_row(
+            "SELECT NOT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'achievements')",
+            [],
+            |row| row.get(0),
+        )?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS achievements (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                rule TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS achievement_unlocks (
+                achievement_id TEXT PRIMARY KEY,
+                unlocked_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS training_totals (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                volume_kg REAL NOT NULL,
+                workouts INTEGER NOT NULL
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create achievement tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        // Seeded once so deleted defaults stay deleted
+        if is_new {
+            for achievement in default_achievements() {
+                Self::write_achievement(conn, &achievement)?;
+            }
+            debug!("🌱 Seeded default achievements");
+        }
+
+        // Databases with sets logged before the totals were kept are counted once
+        let has_totals: bool = conn.query_row("SELECT EXISTS(SELECT 1 FROM training_totals)", [], |row| row.get(0))?;
+        if !has_totals {
+            Self::recount_training_totals(conn)?;
+        }
+
+        debug!("✅ Achievement tables ready");
+        Ok(())
+    }
+}
+
+fn achievement_from_row(row: &Row) -> rusqlite::Result<Achievement> {
+    let rule_json: String = row.get(3)?;
+    let rule: AchievementRule = serde_json::from_str(&rule_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(3, "rule".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(Achievement {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        rule,
+    })
+}
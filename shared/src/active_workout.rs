@@ -0,0 +1,821 @@
+// Workout-in-progress state machine shared by all host apps
+// Every transition is persisted as a draft so a crash never loses a workout
+
+use crate::achievements::TrainingEvent;
+use crate::error::WeightliftingError;
+use crate::models::LoggedSet;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Lifecycle of an active workout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkoutStatus {
+    Running,
+    Paused,
+    Finished,
+    Discarded,
+}
+
+/// Progress of one exercise within an active workout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkoutExerciseStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Skipped,
+}
+
+/// An exercise planned for an active workout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkoutExerciseEntry {
+    pub exercise_id: String,
+    pub status: WorkoutExerciseStatus,
+}
+
+/// Rest countdown started after a set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestTimer {
+    pub started_at: i64,
+    pub duration_seconds: u32,
+}
+
+/// How a group of exercises is performed back-to-back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExerciseGroupKind {
+    Superset, // Exactly two exercises
+    Circuit,  // Two or more exercises
+    GiantSet, // Three or more exercises
+}
+
+/// Exercises to alternate, one set of each per round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseGroupPlan {
+    pub kind: ExerciseGroupKind,
+    pub exercise_ids: Vec<String>, // Order sets are performed in within a round
+    pub rounds: u32,
+    pub rest_between_exercises_seconds: u32, // 0 to go straight to the next exercise
+    pub rest_after_round_seconds: u32,
+}
+
+/// A group of exercises in an active workout and its progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseGroup {
+    pub id: String,
+    pub plan: ExerciseGroupPlan,
+    pub completed_rounds: u32,
+    pub sets_in_round: u32,
+    pub next_position: Option<u32>, // Index into plan.exercise_ids of the exercise due next, None once finished
+}
+
+impl ExerciseGroupPlan {
+    /// Validate the group's size for its kind, its exercises and its rounds
+    pub fn validate(&self) -> Result<(), String> {
+        let count = self.exercise_ids.len();
+        let valid_size = match self.kind {
+            ExerciseGroupKind::Superset => count == 2,
+            ExerciseGroupKind::Circuit => count >= 2,
+            ExerciseGroupKind::GiantSet => count >= 3,
+        };
+        if !valid_size {
+            return Err(format!("A {:?} cannot have {} exercises", self.kind, count));
+        }
+
+        if let Some(duplicate) = self.exercise_ids.iter().enumerate().find_map(|(i, id)| self.exercise_ids[..i].contains(id).then_some(id)) {
+            return Err(format!("Exercise {} appears twice in the group", duplicate));
+        }
+
+        if self.rounds == 0 {
+            return Err("A group needs at least one round".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Snapshot of an active workout, also the persisted draft
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveWorkoutState {
+    pub id: String,
+    pub status: WorkoutStatus,
+    pub started_at: i64,
+    pub updated_at: i64,
+    pub paused_at: Option<i64>,
+    pub paused_seconds: i64, // Total time spent paused before the current pause
+    pub exercises: Vec<WorkoutExerciseEntry>,
+    pub current_index: Option<u32>,
+    pub sets: Vec<LoggedSet>,
+    pub rest_timer: Option<RestTimer>,
+    #[serde(default)]
+    pub groups: Vec<ExerciseGroup>,
+}
+
+/// Result of finishing a workout
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkoutSummary {
+    pub session_id: String,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub duration_seconds: i64,
+    pub set_count: u32,
+    pub total_volume_kg: f64,
+    pub exercise_ids: Vec<String>,
+}
+
+impl ActiveWorkoutState {
+    fn new(exercise_ids: Vec<String>, timestamp: i64) -> Self {
+        let exercises: Vec<WorkoutExerciseEntry> = exercise_ids
+            .into_iter()
+            .map(|exercise_id| WorkoutExerciseEntry { exercise_id, status: WorkoutExerciseStatus::Pending })
+            .collect();
+
+        let mut state = Self {
+            id: Uuid::new_v4().to_string(),
+            status: WorkoutStatus::Running,
+            started_at: timestamp,
+            updated_at: timestamp,
+            paused_at: None,
+            paused_seconds: 0,
+            exercises,
+            current_index: None,
+            sets: Vec::new(),
+            rest_timer: None,
+            groups: Vec::new(),
+        };
+        state.advance();
+        state
+    }
+
+    /// Workout time so far, excluding pauses
+    pub fn elapsed_seconds(&self, now: i64) -> i64 {
+        let end = self.paused_at.unwrap_or(now);
+        (end - self.started_at - self.paused_seconds).max(0)
+    }
+
+    /// Seconds left on the rest timer, None when no rest is running
+    pub fn rest_remaining_seconds(&self, now: i64) -> Option<u32> {
+        self.rest_timer.as_ref().and_then(|timer| {
+            let remaining = timer.started_at + timer.duration_seconds as i64 - now;
+            (remaining > 0).then_some(remaining as u32)
+        })
+    }
+
+    fn require_open(&self) -> Result<(), WeightliftingError> {
+        match self.status {
+            WorkoutStatus::Running | WorkoutStatus::Paused => Ok(()),
+            status => Err(invalid_state(format!("Workout is already {:?}", status))),
+        }
+    }
+
+    fn require_running(&self) -> Result<(), WeightliftingError> {
+        self.require_open()?;
+        if self.status == WorkoutStatus::Paused {
+            return Err(invalid_state("Workout is paused".to_string()));
+        }
+        Ok(())
+    }
+
+    fn current_entry_mut(&mut self) -> Result<&mut WorkoutExerciseEntry, WeightliftingError> {
+        let index = self.current_index
+            .ok_or_else(|| invalid_state("No exercise is in progress".to_string()))?;
+        Ok(&mut self.exercises[index as usize])
+    }
+
+    /// Move to the first exercise that is neither completed nor skipped
+    ///
+    /// Landing on a grouped exercise moves to whichever member of the group is due next.
+    fn advance(&mut self) {
+        self.current_index = self.exercises
+            .iter()
+            .position(|e| matches!(e.status, WorkoutExerciseStatus::Pending | WorkoutExerciseStatus::InProgress))
+            .map(|i| {
+                self.open_group_of(&self.exercises[i].exercise_id)
+                    .and_then(|g| self.group_due_index(g))
+                    .unwrap_or(i) as u32
+            });
+
+        if let Some(index) = self.current_index {
+            self.exercises[index as usize].status = WorkoutExerciseStatus::InProgress;
+        }
+    }
+
+    /// Index of the first entry for an exercise that is still pending or in progress
+    fn open_index(&self, exercise_id: &str) -> Option<usize> {
+        self.exercises.iter().position(|e| {
+            e.exercise_id == exercise_id
+                && matches!(e.status, WorkoutExerciseStatus::Pending | WorkoutExerciseStatus::InProgress)
+        })
+    }
+
+    /// Index of the unfinished group containing an exercise
+    fn open_group_of(&self, exercise_id: &str) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|g| g.next_position.is_some() && g.plan.exercise_ids.iter().any(|id| id == exercise_id))
+    }
+
+    /// Exercise due next in a group
+    fn group_due_exercise(&self, group: usize) -> Option<&String> {
+        let group = &self.groups[group];
+        group.next_position.map(|p| &group.plan.exercise_ids[p as usize])
+    }
+
+    /// Entry index of the exercise due next in a group
+    fn group_due_index(&self, group: usize) -> Option<usize> {
+        self.group_due_exercise(group).and_then(|id| self.open_index(id))
+    }
+
+    /// Point a group at its next open exercise at or after `from`, wrapping into a new round
+    ///
+    /// Closes the group and completes its remaining exercises once every round
+    /// is done or none of its exercises are open. Returns true if a round ended.
+    fn settle_group(&mut self, group: usize, from: usize) -> bool {
+        let ids = self.groups[group].plan.exercise_ids.clone();
+        let open: Vec<bool> = ids.iter().map(|id| self.open_index(id).is_some()).collect();
+        let group = &mut self.groups[group];
+
+        if let Some(position) = (from..ids.len()).find(|&p| open[p]) {
+            group.next_position = Some(position as u32);
+            return false;
+        }
+
+        let round_ended = group.sets_in_round > 0;
+        if round_ended {
+            group.completed_rounds += 1;
+            group.sets_in_round = 0;
+        }
+
+        group.next_position = open
+            .iter()
+            .position(|&o| o)
+            .filter(|_| group.completed_rounds < group.plan.rounds)
+            .map(|p| p as u32);
+
+        if group.next_position.is_none() {
+            debug!("✅ Group {} finished after {} rounds", group.id, group.completed_rounds);
+            for id in &ids {
+                if let Some(index) = self.open_index(id) {
+                    self.exercises[index].status = WorkoutExerciseStatus::Completed;
+                }
+            }
+        }
+        round_ended
+    }
+
+    /// Re-point the group of an exercise that just left the rotation
+    fn settle_group_of(&mut self, exercise_id: &str) {
+        if let Some(g) = self.open_group_of(exercise_id) {
+            let from = self.groups[g].next_position.unwrap_or(0) as usize;
+            self.settle_group(g, from);
+        }
+    }
+
+    /// Require `exercise_id` to be due next if it belongs to a group
+    fn require_due(&self, exercise_id: &str) -> Result<Option<usize>, WeightliftingError> {
+        let Some(g) = self.open_group_of(exercise_id) else {
+            return Ok(None);
+        };
+        match self.group_due_exercise(g) {
+            Some(due) if due != exercise_id => Err(invalid_state(format!(
+                "Exercise {} is next in this {:?}, not {}",
+                due, self.groups[g].plan.kind, exercise_id
+            ))),
+            _ => Ok(Some(g)),
+        }
+    }
+
+    /// Entries that move together with the entry at `index`: the run of its
+    /// unfinished group's exercises around it, or just the entry itself
+    fn move_block(&self, index: usize) -> Range<usize> {
+        let Some(g) = self.open_group_of(&self.exercises[index].exercise_id) else {
+            return index..index + 1;
+        };
+        let ids = &self.groups[g].plan.exercise_ids;
+        let is_member = |i: &usize| ids.contains(&self.exercises[*i].exercise_id);
+        let start = (0..index).rev().take_while(is_member).last().unwrap_or(index);
+        let end = (index + 1..self.exercises.len()).take_while(is_member).last().unwrap_or(index) + 1;
+        start..end
+    }
+
+    /// Make `index` the current exercise, parking the previous one
+    fn select(&mut self, index: usize) {
+        if let Some(current) = self.current_index {
+            let entry = &mut self.exercises[current as usize];
+            if entry.status == WorkoutExerciseStatus::InProgress {
+                entry.status = WorkoutExerciseStatus::Pending;
+            }
+        }
+        self.exercises[index].status = WorkoutExerciseStatus::InProgress;
+        self.current_index = Some(index as u32);
+    }
+}
+
+fn invalid_state(message: String) -> WeightliftingError {
+    warn!("❌ Invalid workout transition: {}", message);
+    WeightliftingError::InvalidState { message }
+}
+
+/// A workout in progress
+///
+/// Hosts drive the workout through these transitions, passing the time of
+/// each one as a Unix timestamp in seconds. The state is written to SQLite
+/// after every transition and can be restored with
+/// `ExerciseRepository::resume_workout` after the app is killed.
+pub struct ActiveWorkout {
+    repository: ExerciseRepository,
+    state: Mutex<ActiveWorkoutState>,
+}
+
+impl ActiveWorkout {
+    /// Get a snapshot of the current workout state
+    pub fn state(&self) -> ActiveWorkoutState {
+        self.lock().clone()
+    }
+
+    /// Workout time so far, excluding pauses
+    pub fn elapsed_seconds(&self, now: i64) -> i64 {
+        self.lock().elapsed_seconds(now)
+    }
+
+    /// Seconds left on the rest timer, None when no rest is running
+    pub fn rest_remaining_seconds(&self, now: i64) -> Option<u32> {
+        self.lock().rest_remaining_seconds(now)
+    }
+
+    /// Log a set for one of the workout's exercises and start the rest timer
+    ///
+    /// The set joins this workout's session and its exercise becomes the
+    /// current one. Pass 0 for `rest_seconds` to skip the rest timer.
+    ///
+    /// A grouped exercise must be the one due next in its group. Its rest comes
+    /// from the group's plan instead of `rest_seconds`, and the next exercise in
+    /// the group becomes the current one.
+    pub fn log_set(&self, set: LoggedSet, rest_seconds: u32) -> Result<LoggedSet, WeightliftingError> {
+        info!("📝 Logging set in active workout for exercise {}", set.exercise_id);
+
+        set.validate().map_err(|message| WeightliftingError::InvalidInput { message })?;
+
+        self.transition(set.performed_at, |state| {
+            state.require_running()?;
+            let group = state.require_due(&set.exercise_id)?;
+
+            let index = state.exercises
+                .iter()
+                .position(|e| e.exercise_id == set.exercise_id && e.status != WorkoutExerciseStatus::Completed)
+                .ok_or_else(|| invalid_state(format!("Exercise {} is not open in this workout", set.exercise_id)))?;
+            state.select(index);
+
+            let set = LoggedSet { session_id: Some(state.id.clone()), ..set };
+            state.sets.push(set.clone());
+
+            let rest_seconds = match group {
+                Some(g) => {
+                    state.groups[g].sets_in_round += 1;
+                    let from = state.groups[g].next_position.unwrap_or(0) as usize + 1;
+                    let round_ended = state.settle_group(g, from);
+                    match state.group_due_index(g) {
+                        Some(next) => state.select(next),
+                        None => state.advance(),
+                    }
+
+                    let plan = &state.groups[g].plan;
+                    if round_ended { plan.rest_after_round_seconds } else { plan.rest_between_exercises_seconds }
+                }
+                None => rest_seconds,
+            };
+            state.rest_timer = (rest_seconds > 0).then_some(RestTimer {
+                started_at: set.performed_at,
+                duration_seconds: rest_seconds,
+            });
+            Ok(set)
+        })
+    }
+
+    /// Mark the current exercise completed and move to the next one
+    pub fn complete_exercise(&self, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_running()?;
+            let entry = state.current_entry_mut()?;
+            entry.status = WorkoutExerciseStatus::Completed;
+            let exercise_id = entry.exercise_id.clone();
+            state.settle_group_of(&exercise_id);
+            state.advance();
+            Ok(state.clone())
+        })
+    }
+
+    /// Skip the current exercise and move to the next one
+    pub fn skip_exercise(&self, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_running()?;
+            let entry = state.current_entry_mut()?;
+            entry.status = WorkoutExerciseStatus::Skipped;
+            let exercise_id = entry.exercise_id.clone();
+            state.settle_group_of(&exercise_id);
+            state.rest_timer = None;
+            state.advance();
+            Ok(state.clone())
+        })
+    }
+
+    /// Jump to the exercise at `index`, reopening it if it was skipped
+    ///
+    /// Within a group only the exercise due next can be selected.
+    pub fn select_exercise(&self, index: u32, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_running()?;
+            if index as usize >= state.exercises.len() {
+                return Err(invalid_state(format!("No exercise at position {}", index)));
+            }
+            state.require_due(&state.exercises[index as usize].exercise_id)?;
+            state.select(index as usize);
+            Ok(state.clone())
+        })
+    }
+
+    /// Append an exercise to the end of the workout
+    pub fn add_exercise(&self, exercise_id: String, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        {
+            let conn = self.repository.connection()?;
+            ExerciseRepository::ensure_exercise_exists(&conn, &exercise_id)?;
+        }
+
+        self.transition(timestamp, |state| {
+            state.require_open()?;
+            state.exercises.push(WorkoutExerciseEntry {
+                exercise_id: exercise_id.clone(),
+                status: WorkoutExerciseStatus::Pending,
+            });
+            if state.current_index.is_none() {
+                state.advance();
+            }
+            Ok(state.clone())
+        })
+    }
+
+    /// Move the exercise at `from_index` to `to_index`
+    ///
+    /// A member of an unfinished group moves the whole group by the same
+    /// distance, and nothing can be moved into the middle of a group.
+    pub fn move_exercise(&self, from_index: u32, to_index: u32, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_open()?;
+            let len = state.exercises.len() as u32;
+            if from_index >= len || to_index >= len {
+                return Err(invalid_state(format!("Cannot move exercise {} to {} in a workout of {}", from_index, to_index, len)));
+            }
+
+            let current_id = state.current_index.map(|i| state.exercises[i as usize].exercise_id.clone());
+            let block = state.move_block(from_index as usize);
+            let insert_at = (block.start as i64 + to_index as i64 - from_index as i64)
+                .clamp(0, (len as usize - block.len()) as i64) as usize;
+            let moved: Vec<WorkoutExerciseEntry> = state.exercises.drain(block).collect();
+            if insert_at > 0 && insert_at < state.exercises.len() && state.move_block(insert_at).start < insert_at {
+                return Err(invalid_state(format!("Moving exercise {} to {} would split a group", from_index, to_index)));
+            }
+            state.exercises.splice(insert_at..insert_at, moved);
+            state.current_index = state.exercises
+                .iter()
+                .position(|e| Some(&e.exercise_id) == current_id.as_ref() && e.status == WorkoutExerciseStatus::InProgress)
+                .map(|i| i as u32);
+            Ok(state.clone())
+        })
+    }
+
+    /// Group open exercises into a superset, circuit or giant set
+    ///
+    /// The exercises are moved next to each other, in the plan's order, at the
+    /// position of the earliest one. Each exercise can be in one unfinished group.
+    pub fn group_exercises(&self, plan: ExerciseGroupPlan, timestamp: i64) -> Result<ExerciseGroup, WeightliftingError> {
+        info!("🔗 Grouping {} exercises into a {:?}", plan.exercise_ids.len(), plan.kind);
+
+        plan.validate().map_err(|message| {
+            warn!("❌ Invalid group: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        self.transition(timestamp, |state| {
+            state.require_open()?;
+
+            let mut positions = Vec::with_capacity(plan.exercise_ids.len());
+            for exercise_id in &plan.exercise_ids {
+                if state.open_group_of(exercise_id).is_some() {
+                    return Err(invalid_state(format!("Exercise {} is already in a group", exercise_id)));
+                }
+                let position = state.open_index(exercise_id)
+                    .ok_or_else(|| invalid_state(format!("Exercise {} is not open in this workout", exercise_id)))?;
+                positions.push(position);
+            }
+
+            // Pull the members out and reinsert them together in plan order
+            let insert_at = positions.iter().copied().min().unwrap_or(0);
+            let members: Vec<WorkoutExerciseEntry> = positions.iter().map(|&i| state.exercises[i].clone()).collect();
+            let mut exercises: Vec<WorkoutExerciseEntry> = state.exercises
+                .drain(..)
+                .enumerate()
+                .filter(|(i, _)| !positions.contains(i))
+                .map(|(_, entry)| entry)
+                .collect();
+            exercises.splice(insert_at..insert_at, members);
+            state.exercises = exercises;
+            state.current_index = state.exercises
+                .iter()
+                .position(|e| e.status == WorkoutExerciseStatus::InProgress)
+                .map(|i| i as u32);
+
+            state.groups.push(ExerciseGroup {
+                id: Uuid::new_v4().to_string(),
+                plan: plan.clone(),
+                completed_rounds: 0,
+                sets_in_round: 0,
+                next_position: Some(0),
+            });
+            let g = state.groups.len() - 1;
+
+            // Working on one of the members means starting the group from its first exercise
+            let current_is_member = state.current_index
+                .is_some_and(|i| plan.exercise_ids.contains(&state.exercises[i as usize].exercise_id));
+            if current_is_member {
+                if let Some(first) = state.group_due_index(g) {
+                    state.select(first);
+                }
+            }
+
+            Ok(state.groups[g].clone())
+        })
+    }
+
+    /// Dissolve a group, leaving its exercises in place as ordinary exercises
+    pub fn ungroup_exercises(&self, group_id: String, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        info!("✂️ Ungrouping group {}", group_id);
+
+        self.transition(timestamp, |state| {
+            state.require_open()?;
+            let index = state.groups
+                .iter()
+                .position(|g| g.id == group_id)
+                .ok_or_else(|| invalid_state(format!("No group {} in this workout", group_id)))?;
+            state.groups.remove(index);
+            Ok(state.clone())
+        })
+    }
+
+    /// Pause the workout clock
+    pub fn pause(&self, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_running()?;
+            state.status = WorkoutStatus::Paused;
+            state.paused_at = Some(timestamp);
+            Ok(state.clone())
+        })
+    }
+
+    /// Resume a paused workout
+    pub fn resume(&self, timestamp: i64) -> Result<ActiveWorkoutState, WeightliftingError> {
+        self.transition(timestamp, |state| {
+            state.require_open()?;
+            let paused_at = state.paused_at
+                .ok_or_else(|| invalid_state("Workout is not paused".to_string()))?;
+            state.paused_seconds += (timestamp - paused_at).max(0);
+            state.paused_at = None;
+            state.status = WorkoutStatus::Running;
+            Ok(state.clone())
+        })
+    }
+
+    /// Finish the workout, moving its sets into the training log
+    pub fn finish(&self, timestamp: i64) -> Result<WorkoutSummary, WeightliftingError> {
+        info!("🏁 Finishing active workout");
+
+        let mut state = self.lock();
+        state.require_open()?;
+        if state.sets.is_empty() {
+            return Err(invalid_state("Cannot finish a workout with no logged sets, discard it instead".to_string()));
+        }
+
+        let mut finished = state.clone();
+        if let Some(paused_at) = finished.paused_at.take() {
+            finished.paused_seconds += (timestamp - paused_at).max(0);
+        }
+        finished.status = WorkoutStatus::Finished;
+        finished.updated_at = timestamp;
+        finished.rest_timer = None;
+
+        let mut exercise_ids: Vec<String> = Vec::new();
+        for set in &finished.sets {
+            if !exercise_ids.contains(&set.exercise_id) {
+                exercise_ids.push(set.exercise_id.clone());
+            }
+        }
+        let mut conn = self.repository.connection()?;
+        let summary = WorkoutSummary {
+            session_id: finished.id.clone(),
+            started_at: finished.started_at,
+            finished_at: timestamp,
+            duration_seconds: finished.elapsed_seconds(timestamp),
+            set_count: finished.sets.len() as u32,
+            total_volume_kg: finished.sets
+                .iter()
+                .map(|set| ExerciseRepository::set_volume(&conn, set))
+                .sum::<Result<f64, _>>()?,
+            exercise_ids,
+        };
+
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO workout_sessions (id, started_at, finished_at, duration_seconds) VALUES (?1, ?2, ?3, ?4)",
+            params![summary.session_id, summary.started_at, summary.finished_at, summary.duration_seconds],
+        )?;
+        for set in &finished.sets {
+            ExerciseRepository::insert_set(&tx, set)?;
+        }
+        tx.execute("DELETE FROM active_workout_drafts WHERE id = ?1", [&finished.id])?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit finished workout: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to save workout: {}", e),
+            }
+        })?;
+
+        drop(conn);
+
+        *state = finished;
+        info!("✅ Workout {} finished with {} sets", summary.session_id, summary.set_count);
+        self.repository.notify_achievements(TrainingEvent::Sets(&state.sets));
+        Ok(summary)
+    }
+
+    /// Throw the workout away without logging anything
+    pub fn discard(&self) -> Result<(), WeightliftingError> {
+        info!("🗑️ Discarding active workout");
+
+        let mut state = self.lock();
+        state.require_open()?;
+
+        let conn = self.repository.connection()?;
+        conn.execute("DELETE FROM active_workout_drafts WHERE id = ?1", [&state.id])?;
+
+        state.status = WorkoutStatus::Discarded;
+        Ok(())
+    }
+
+    /// Apply a transition to a copy of the state, persist it, then publish it
+    ///
+    /// The in-memory state is only replaced once the draft is safely stored.
+    fn transition<T>(
+        &self,
+        timestamp: i64,
+        apply: impl FnOnce(&mut ActiveWorkoutState) -> Result<T, WeightliftingError>,
+    ) -> Result<T, WeightliftingError> {
+        let mut state = self.lock();
+        let mut next = state.clone();
+
+        let result = apply(&mut next)?;
+        next.updated_at = timestamp;
+
+        let conn = self.repository.connection()?;
+        update_draft(&conn, &next)?;
+
+        debug!("💾 Workout {} draft saved ({:?})", next.id, next.status);
+        *state = next;
+        Ok(result)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ActiveWorkoutState> {
+        // A panic mid-transition never publishes a partial state, so the guard is still valid
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn draft_json(state: &ActiveWorkoutState) -> Result<String, WeightliftingError> {
+    serde_json::to_string(state).map_err(|e| {
+        error!("❌ Failed to serialize workout draft: {}", e);
+        WeightliftingError::DatabaseError {
+            message: format!("Failed to serialize workout draft: {}", e),
+        }
+    })
+}
+
+/// Store the draft of a newly started workout
+fn insert_draft(conn: &Connection, state: &ActiveWorkoutState) -> Result<(), WeightliftingError> {
+    conn.execute(
+        "INSERT INTO active_workout_drafts (id, state, updated_at) VALUES (?1, ?2, ?3)",
+        params![state.id, draft_json(state)?, state.updated_at],
+    )?;
+    Ok(())
+}
+
+/// Overwrite the stored draft of a workout that is still in progress
+///
+/// A handle whose workout was finished or discarded through another handle
+/// has no draft left to update, and must not bring it back.
+fn update_draft(conn: &Connection, state: &ActiveWorkoutState) -> Result<(), WeightliftingError> {
+    let rows_affected = conn.execute(
+        "UPDATE active_workout_drafts SET state = ?2, updated_at = ?3 WHERE id = ?1",
+        params![state.id, draft_json(state)?, state.updated_at],
+    )?;
+    if rows_affected == 0 {
+        return Err(invalid_state(format!("Workout {} is no longer in progress", state.id)));
+    }
+    Ok(())
+}
+
+impl ExerciseRepository {
+    /// Start a new workout with the given exercises
+    ///
+    /// Only one workout can be active at a time; finish or discard the
+    /// existing one first.
+    pub fn start_workout(&self, exercise_ids: Vec<String>, timestamp: i64) -> Result<Arc<ActiveWorkout>, WeightliftingError> {
+        info!("🏋️ Starting workout with {} exercises", exercise_ids.len());
+
+        let mut conn = self.connection()?;
+        // Take the write lock before checking, so two starts can't both find no draft
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if Self::load_draft(&tx)?.is_some() {
+            return Err(invalid_state("A workout is already in progress".to_string()));
+        }
+        for exercise_id in &exercise_ids {
+            Self::ensure_exercise_exists(&tx, exercise_id)?;
+        }
+
+        let state = ActiveWorkoutState::new(exercise_ids, timestamp);
+        insert_draft(&tx, &state)?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit workout start: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to start workout: {}", e),
+            }
+        })?;
+
+        info!("✅ Workout {} started", state.id);
+        Ok(Arc::new(ActiveWorkout {
+            repository: self.clone(),
+            state: Mutex::new(state),
+        }))
+    }
+
+    /// Restore the workout that was in progress, if any
+    pub fn resume_workout(&self) -> Result<Option<Arc<ActiveWorkout>>, WeightliftingError> {
+        info!("🔄 Looking for a workout in progress");
+
+        let conn = self.connection()?;
+        let workout = Self::load_draft(&conn)?.map(|state| {
+            info!("✅ Restored workout {} with {} sets", state.id, state.sets.len());
+            Arc::new(ActiveWorkout {
+                repository: self.clone(),
+                state: Mutex::new(state),
+            })
+        });
+        Ok(workout)
+    }
+
+    fn load_draft(conn: &Connection) -> Result<Option<ActiveWorkoutState>, WeightliftingError> {
+        let state_json: Option<String> = conn.query_row(
+            "SELECT state FROM active_workout_drafts ORDER BY updated_at DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+
+        state_json
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    error!("❌ Failed to parse workout draft: {}", e);
+                    WeightliftingError::DatabaseError {
+                        message: format!("Failed to parse workout draft: {}", e),
+                    }
+                })
+            })
+            .transpose()
+    }
+
+    /// Create the workout session and draft tables if they don't exist
+    pub(crate) fn create_workout_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating workout_sessions and active_workout_drafts tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS workout_sessions (
+                id TEXT PRIMARY KEY,
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER NOT NULL,
+                duration_seconds INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS active_workout_drafts (
+                id TEXT PRIMARY KEY,
+                state TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create workout tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Workout tables ready");
+        Ok(())
+    }
+}
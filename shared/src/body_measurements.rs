@@ -0,0 +1,437 @@
+// Bodyweight, body fat and circumference tracking with time-series queries
+
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::stats::{moving_average, slope_per_week, TimeSeriesPoint};
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Body sites where circumference can be measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasurementSite {
+    Neck,
+    Shoulders,
+    Chest,
+    Waist,
+    Hips,
+    LeftBicep,
+    RightBicep,
+    LeftForearm,
+    RightForearm,
+    LeftThigh,
+    RightThigh,
+    LeftCalf,
+    RightCalf,
+}
+
+/// A circumference measured at one site
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circumference {
+    pub site: MeasurementSite,
+    pub value_cm: f64,
+}
+
+/// A set of body measurements taken at one time
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyMeasurement {
+    pub id: String,
+    pub recorded_at: i64, // Unix timestamp in seconds
+    pub bodyweight_kg: Option<f64>,
+    pub body_fat_percentage: Option<f64>,
+    pub circumferences: Vec<Circumference>,
+}
+
+/// A measured quantity that can be charted over time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyMetric {
+    Bodyweight,
+    BodyFatPercentage,
+    Circumference { site: MeasurementSite },
+}
+
+/// A metric's raw series, its smoothed series and its trend
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricTrend {
+    pub metric: BodyMetric,
+    pub points: Vec<TimeSeriesPoint>,
+    pub smoothed: Vec<TimeSeriesPoint>,
+    pub slope_per_week: Option<f64>,
+}
+
+impl MeasurementSite {
+    /// Stable name used in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeasurementSite::Neck => "neck",
+            MeasurementSite::Shoulders => "shoulders",
+            MeasurementSite::Chest => "chest",
+            MeasurementSite::Waist => "waist",
+            MeasurementSite::Hips => "hips",
+            MeasurementSite::LeftBicep => "left_bicep",
+            MeasurementSite::RightBicep => "right_bicep",
+            MeasurementSite::LeftForearm => "left_forearm",
+            MeasurementSite::RightForearm => "right_forearm",
+            MeasurementSite::LeftThigh => "left_thigh",
+            MeasurementSite::RightThigh => "right_thigh",
+            MeasurementSite::LeftCalf => "left_calf",
+            MeasurementSite::RightCalf => "right_calf",
+        }
+    }
+
+    /// Parse a database name back into a site
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            MeasurementSite::Neck,
+            MeasurementSite::Shoulders,
+            MeasurementSite::Chest,
+            MeasurementSite::Waist,
+            MeasurementSite::Hips,
+            MeasurementSite::LeftBicep,
+            MeasurementSite::RightBicep,
+            MeasurementSite::LeftForearm,
+            MeasurementSite::RightForearm,
+            MeasurementSite::LeftThigh,
+            MeasurementSite::RightThigh,
+            MeasurementSite::LeftCalf,
+            MeasurementSite::RightCalf,
+        ]
+        .into_iter()
+        .find(|site| site.as_str() == name)
+    }
+}
+
+impl BodyMeasurement {
+    /// Create an empty measurement with automatic UUID generation
+    pub fn new(recorded_at: i64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            recorded_at,
+            bodyweight_kg: None,
+            body_fat_percentage: None,
+            circumferences: Vec::new(),
+        }
+    }
+
+    /// Validate that at least one value is present and all values are in range
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bodyweight_kg.is_none() && self.body_fat_percentage.is_none() && self.circumferences.is_empty() {
+            return Err("Measurement must contain at least one value".to_string());
+        }
+
+        if let Some(weight) = self.bodyweight_kg {
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(format!("Bodyweight must be positive, got {}", weight));
+            }
+        }
+
+        if let Some(body_fat) = self.body_fat_percentage {
+            if !(0.0..100.0).contains(&body_fat) {
+                return Err(format!("Body fat must be between 0 and 100%, got {}", body_fat));
+            }
+        }
+
+        for (i, circumference) in self.circumferences.iter().enumerate() {
+            if !circumference.value_cm.is_finite() || circumference.value_cm <= 0.0 {
+                return Err(format!("{} circumference must be positive, got {}", circumference.site.as_str(), circumference.value_cm));
+            }
+            if self.circumferences[..i].iter().any(|c| c.site == circumference.site) {
+                return Err(format!("{} measured more than once", circumference.site.as_str()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the value of a metric in this measurement, if it was recorded
+    pub fn value(&self, metric: BodyMetric) -> Option<f64> {
+        match metric {
+            BodyMetric::Bodyweight => self.bodyweight_kg,
+            BodyMetric::BodyFatPercentage => self.body_fat_percentage,
+            BodyMetric::Circumference { site } => self.circumferences
+                .iter()
+                .find(|c| c.site == site)
+                .map(|c| c.value_cm),
+        }
+    }
+}
+
+/// Repository for body measurements, sharing the exercise database
+pub struct BodyMeasurementRepository {
+    repository: ExerciseRepository,
+}
+
+impl BodyMeasurementRepository {
+    /// Add a new body measurement
+    pub fn add_measurement(&self, measurement: BodyMeasurement) -> Result<(), WeightliftingError> {
+        info!("⚖️ Adding body measurement {} at {}", measurement.id, measurement.recorded_at);
+        debug!("📝 Measurement details: {:?}", measurement);
+
+        measurement.validate().map_err(|message| {
+            warn!("❌ Invalid measurement: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let mut conn = self.repository.connection()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO body_measurements (id, recorded_at, bodyweight_kg, body_fat_percentage)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                measurement.id,
+                measurement.recorded_at,
+                measurement.bodyweight_kg,
+                measurement.body_fat_percentage
+            ],
+        )?;
+        for circumference in &measurement.circumferences {
+            tx.execute(
+                "INSERT INTO body_circumferences (measurement_id, site, value_cm) VALUES (?1, ?2, ?3)",
+                params![measurement.id, circumference.site.as_str(), circumference.value_cm],
+            )?;
+        }
+        // Bodyweight sets logged after this time now count at the new bodyweight
+        if measurement.bodyweight_kg.is_some() {
+            ExerciseRepository::recount_training_totals(&tx)?;
+        }
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to insert measurement '{}': {}", measurement.id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to insert measurement: {}", e),
+            }
+        })?;
+
+        info!("✅ Successfully added body measurement: {}", measurement.id);
+        Ok(())
+    }
+
+    /// Get a body measurement by ID
+    pub fn get_measurement(&self, id: String) -> Result<BodyMeasurement, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        let measurement = Self::query_measurements(&conn, "WHERE id = ?1", params![id])?
+            .pop()
+            .ok_or_else(|| {
+                warn!("❌ Body measurement not found with ID '{}'", id);
+                WeightliftingError::RecordNotFound { id: id.clone() }
+            })?;
+        Ok(measurement)
+    }
+
+    /// Get the most recent measurement, if any
+    pub fn get_latest_measurement(&self) -> Result<Option<BodyMeasurement>, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        let id: Option<String> = conn.query_row(
+            "SELECT id FROM body_measurements ORDER BY recorded_at DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+
+        match id {
+            Some(id) => Ok(Self::query_measurements(&conn, "WHERE id = ?1", params![id])?.pop()),
+            None => Ok(None),
+        }
+    }
+
+    /// Get all measurements recorded between `start` and `end` (inclusive), oldest first
+    pub fn get_measurements_between(&self, start: i64, end: i64) -> Result<Vec<BodyMeasurement>, WeightliftingError> {
+        info!("📚 Retrieving body measurements between {} and {}", start, end);
+
+        let conn = self.repository.connection()?;
+        let measurements = Self::query_measurements(
+            &conn,
+            "WHERE recorded_at BETWEEN ?1 AND ?2",
+            params![start, end],
+        )?;
+
+        info!("✅ Successfully retrieved {} measurements", measurements.len());
+        Ok(measurements)
+    }
+
+    /// Delete a measurement by ID
+    /// Returns true if the measurement was deleted, false if it wasn't found
+    pub fn delete_measurement(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting body measurement with ID: {}", id);
+
+        let mut conn = self.repository.connection()?;
+        let tx = conn.transaction()?;
+        let had_bodyweight = tx.query_row(
+            "SELECT bodyweight_kg IS NOT NULL FROM body_measurements WHERE id = ?1",
+            [&id],
+            |row| row.get(0),
+        ).optional()?.unwrap_or(false);
+        tx.execute("DELETE FROM body_circumferences WHERE measurement_id = ?1", [&id])?;
+        let rows_affected = tx.execute("DELETE FROM body_measurements WHERE id = ?1", [&id])?;
+        if had_bodyweight {
+            ExerciseRepository::recount_training_totals(&tx)?;
+        }
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to delete measurement '{}': {}", id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to delete measurement: {}", e),
+            }
+        })?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get the recorded values of a metric between `start` and `end`, oldest first
+    pub fn get_metric_series(&self, metric: BodyMetric, start: i64, end: i64) -> Result<Vec<TimeSeriesPoint>, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        Self::query_metric_series(&conn, metric, start, end)
+    }
+
+    /// Get a metric's series, its trailing moving average over `window` points
+    /// and its least-squares trend in units per week
+    pub fn get_metric_trend(&self, metric: BodyMetric, start: i64, end: i64, window: u32) -> Result<MetricTrend, WeightliftingError> {
+        info!("📈 Computing {:?} trend between {} and {}", metric, start, end);
+
+        if window == 0 {
+            return Err(WeightliftingError::InvalidInput {
+                message: "Moving average window must be at least 1".to_string(),
+            });
+        }
+
+        let conn = self.repository.connection()?;
+        let points = Self::query_metric_series(&conn, metric, start, end)?;
+        let smoothed = moving_average(&points, window as usize);
+        let slope_per_week = slope_per_week(&points);
+
+        debug!("📉 {} points, slope {:?} per week", points.len(), slope_per_week);
+        Ok(MetricTrend { metric, points, smoothed, slope_per_week })
+    }
+
+    /// Latest bodyweight recorded at or before `at`
+    pub(crate) fn bodyweight_at(conn: &Connection, at: i64) -> Result<Option<f64>, WeightliftingError> {
+        let weight = conn.query_row(
+            "SELECT bodyweight_kg FROM body_measurements
+             WHERE bodyweight_kg IS NOT NULL AND recorded_at <= ?1
+             ORDER BY recorded_at DESC LIMIT 1",
+            [at],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(weight)
+    }
+
+    /// The first bodyweight recorded at or after `since`
+    pub(crate) fn first_bodyweight_since(conn: &Connection, since: i64) -> Result<Option<f64>, WeightliftingError> {
+        let weight = conn.query_row(
+            "SELECT bodyweight_kg FROM body_measurements
+             WHERE bodyweight_kg IS NOT NULL AND recorded_at >= ?1
+             ORDER BY recorded_at LIMIT 1",
+            [since],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(weight)
+    }
+
+    pub(crate) fn query_metric_series(conn: &Connection, metric: BodyMetric, start: i64, end: i64) -> Result<Vec<TimeSeriesPoint>, WeightliftingError> {
+        let point_from_row = |row: &Row| -> rusqlite::Result<TimeSeriesPoint> {
+            Ok(TimeSeriesPoint { timestamp: row.get(0)?, value: row.get(1)? })
+        };
+
+        let points = match metric {
+            BodyMetric::Bodyweight | BodyMetric::BodyFatPercentage => {
+                let column = if metric == BodyMetric::Bodyweight { "bodyweight_kg" } else { "body_fat_percentage" };
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT recorded_at, {0} FROM body_measurements
+                     WHERE {0} IS NOT NULL AND recorded_at BETWEEN ?1 AND ?2
+                     ORDER BY recorded_at",
+                    column
+                ))?;
+                let points = stmt.query_map(params![start, end], point_from_row)?.collect::<Result<Vec<_>, _>>()?;
+                points
+            }
+            BodyMetric::Circumference { site } => {
+                let mut stmt = conn.prepare(
+                    "SELECT m.recorded_at, c.value_cm FROM body_circumferences c
+                     JOIN body_measurements m ON m.id = c.measurement_id
+                     WHERE c.site = ?1 AND m.recorded_at BETWEEN ?2 AND ?3
+                     ORDER BY m.recorded_at",
+                )?;
+                let points = stmt.query_map(params![site.as_str(), start, end], point_from_row)?.collect::<Result<Vec<_>, _>>()?;
+                points
+            }
+        };
+        Ok(points)
+    }
+
+    /// Load measurements matching `filter` with their circumferences, oldest first
+    fn query_measurements(conn: &Connection, filter: &str, filter_params: impl rusqlite::Params + Clone) -> Result<Vec<BodyMeasurement>, WeightliftingError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, recorded_at, bodyweight_kg, body_fat_percentage FROM body_measurements {} ORDER BY recorded_at",
+            filter
+        ))?;
+        let mut measurements = stmt
+            .query_map(filter_params.clone(), |row| {
+                Ok(BodyMeasurement {
+                    id: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    bodyweight_kg: row.get(2)?,
+                    body_fat_percentage: row.get(3)?,
+                    circumferences: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT c.measurement_id, c.site, c.value_cm FROM body_circumferences c
+             WHERE c.measurement_id IN (SELECT id FROM body_measurements {})
+             ORDER BY c.rowid",
+            filter
+        ))?;
+        let mut circumferences: HashMap<String, Vec<Circumference>> = HashMap::new();
+        let rows = stmt.query_map(filter_params, |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?))
+        })?;
+        for row in rows {
+            let (measurement_id, site, value_cm) = row?;
+            match MeasurementSite::parse(&site) {
+                Some(site) => circumferences.entry(measurement_id).or_default().push(Circumference { site, value_cm }),
+                None => warn!("⚠️ Skipping unknown measurement site '{}'", site),
+            }
+        }
+
+        for measurement in &mut measurements {
+            measurement.circumferences = circumferences.remove(&measurement.id).unwrap_or_default();
+        }
+        Ok(measurements)
+    }
+}
+
+impl ExerciseRepository {
+    /// Get the body measurement repository backed by the same database
+    pub fn body_measurements(&self) -> Arc<BodyMeasurementRepository> {
+        Arc::new(BodyMeasurementRepository { repository: self.clone() })
+    }
+
+    /// Create the body measurement tables if they don't exist
+    pub(crate) fn create_body_measurement_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating body measurement tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS body_measurements (
+                id TEXT PRIMARY KEY,
+                recorded_at INTEGER NOT NULL,
+                bodyweight_kg REAL,
+                body_fat_percentage REAL
+            );
+            CREATE INDEX IF NOT EXISTS idx_body_measurements_recorded_at
+                ON body_measurements (recorded_at);
+            CREATE TABLE IF NOT EXISTS body_circumferences (
+                measurement_id TEXT NOT NULL,
+                site TEXT NOT NULL,
+                value_cm REAL NOT NULL,
+                PRIMARY KEY (measurement_id, site)
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create body measurement tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Body measurement tables ready");
+        Ok(())
+    }
+}
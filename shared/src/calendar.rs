@@ -0,0 +1,167 @@
+// Calendar date arithmetic without a date library
+// Dates are proleptic Gregorian; timestamps are interpreted in UTC
+
+use serde::{Deserialize, Serialize};
+
+const DAY_SECONDS: i64 = 86_400;
+
+/// Day of the week, Monday first as in ISO 8601 and RFC 5545's default week start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// A date without a time of day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CalendarDate {
+    pub year: i32,
+    pub month: u8, // 1-12
+    pub day: u8,   // 1-31
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Days since Monday (Monday = 0)
+    pub fn index(&self) -> i64 {
+        *self as i64
+    }
+
+    /// Two-letter RFC 5545 code (MO, TU, ...)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "MO",
+            Weekday::Tuesday => "TU",
+            Weekday::Wednesday => "WE",
+            Weekday::Thursday => "TH",
+            Weekday::Friday => "FR",
+            Weekday::Saturday => "SA",
+            Weekday::Sunday => "SU",
+        }
+    }
+
+    /// Parse a two-letter RFC 5545 code
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|day| day.code() == code)
+    }
+}
+
+/// Whether `year` is a leap year
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in a month of a year
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl CalendarDate {
+    /// Create a date, returning None if it does not exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let date = Self { year, month, day };
+        date.is_valid().then_some(date)
+    }
+
+    /// Whether the date exists in the calendar
+    pub fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month) && self.day >= 1 && self.day <= days_in_month(self.year, self.month)
+    }
+
+    /// Days since 1970-01-01
+    ///
+    /// Uses Howard Hinnant's days_from_civil algorithm.
+    pub fn to_days(&self) -> i64 {
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Date for a number of days since 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u8;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u8;
+        let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// UTC date containing a Unix timestamp
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        Self::from_days(timestamp.div_euclid(DAY_SECONDS))
+    }
+
+    /// Unix timestamp of midnight UTC at the start of the date
+    pub fn to_timestamp(&self) -> i64 {
+        self.to_days() * DAY_SECONDS
+    }
+
+    /// Day of the week
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        Weekday::ALL[(self.to_days() + 3).rem_euclid(7) as usize]
+    }
+
+    /// The date `days` days later (or earlier when negative)
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// The Monday starting this date's week
+    pub fn start_of_week(&self) -> Self {
+        self.add_days(-self.weekday().index())
+    }
+
+    /// The first day of this date's month
+    pub fn start_of_month(&self) -> Self {
+        Self { day: 1, ..*self }
+    }
+
+    /// The same day `months` months later, None if that day does not exist (e.g. 31 April)
+    pub fn add_months(&self, months: i64) -> Option<Self> {
+        let month_index = self.year as i64 * 12 + (self.month as i64 - 1) + months;
+        Self::new(month_index.div_euclid(12) as i32, (month_index.rem_euclid(12) + 1) as u8, self.day)
+    }
+
+    /// Format as an RFC 5545 / ISO 8601 basic date (YYYYMMDD)
+    pub fn to_basic_string(&self) -> String {
+        format!("{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+
+    /// Parse an RFC 5545 / ISO 8601 basic date (YYYYMMDD)
+    pub fn parse_basic(text: &str) -> Option<Self> {
+        if text.len() != 8 || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Self::new(text[0..4].parse().ok()?, text[4..6].parse().ok()?, text[6..8].parse().ok()?)
+    }
+}
@@ -0,0 +1,390 @@
+// Cardio and conditioning activities logged next to strength training
+
+use crate::achievements::TrainingEvent;
+use crate::calendar::CalendarDate;
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+const CARDIO_COLUMNS: &str = "id, activity_type, started_at, duration_seconds, distance_m, calories, \
+    average_heart_rate, max_heart_rate, load_kg, session_id, notes";
+
+/// Plausible heart rate range in beats per minute
+const HEART_RATE_RANGE: std::ops::RangeInclusive<u32> = 30..=250;
+
+/// Kind of cardio or conditioning work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CardioActivityType {
+    Run,
+    Walk,
+    Cycle,
+    Row,
+    Swim,
+    SkiErg,
+    Elliptical,
+    StairClimber,
+    SledPush,
+    SledPull,
+    JumpRope,
+    Other,
+}
+
+/// A logged cardio activity
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardioActivity {
+    pub id: String,
+    pub activity_type: CardioActivityType,
+    pub started_at: i64, // Unix timestamp in seconds
+    pub duration_seconds: u32,
+    pub distance_m: Option<f64>,
+    pub calories: Option<f64>,
+    pub average_heart_rate: Option<u32>, // Beats per minute
+    pub max_heart_rate: Option<u32>,
+    pub load_kg: Option<f64>,       // Weight on the sled or carried
+    pub session_id: Option<String>, // Workout session the activity belongs to
+    pub notes: Option<String>,
+}
+
+/// Pace and speed derived from an activity's distance and duration
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardioMetrics {
+    pub pace_seconds_per_km: Option<f64>,
+    pub pace_seconds_per_500m: Option<f64>, // Split used on rowing and ski ergometers
+    pub speed_kmh: Option<f64>,
+}
+
+/// Totals for one activity type within a week
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardioTypeTotals {
+    pub activity_type: CardioActivityType,
+    pub activity_count: u32,
+    pub duration_seconds: u64,
+    pub distance_m: f64,
+}
+
+/// Cardio totals for one calendar week, Monday to Sunday (UTC)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardioWeekSummary {
+    pub week_start: CalendarDate,
+    pub activity_count: u32,
+    pub duration_seconds: u64,
+    pub distance_m: f64,
+    pub calories: f64,
+    pub average_heart_rate: Option<f64>, // Weighted by duration over activities with a heart rate
+    pub by_type: Vec<CardioTypeTotals>,  // Longest total duration first
+}
+
+impl CardioActivityType {
+    pub const ALL: [CardioActivityType; 12] = [
+        CardioActivityType::Run,
+        CardioActivityType::Walk,
+        CardioActivityType::Cycle,
+        CardioActivityType::Row,
+        CardioActivityType::Swim,
+        CardioActivityType::SkiErg,
+        CardioActivityType::Elliptical,
+        CardioActivityType::StairClimber,
+        CardioActivityType::SledPush,
+        CardioActivityType::SledPull,
+        CardioActivityType::JumpRope,
+        CardioActivityType::Other,
+    ];
+
+    /// Stable name used in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardioActivityType::Run => "run",
+            CardioActivityType::Walk => "walk",
+            CardioActivityType::Cycle => "cycle",
+            CardioActivityType::Row => "row",
+            CardioActivityType::Swim => "swim",
+            CardioActivityType::SkiErg => "ski_erg",
+            CardioActivityType::Elliptical => "elliptical",
+            CardioActivityType::StairClimber => "stair_climber",
+            CardioActivityType::SledPush => "sled_push",
+            CardioActivityType::SledPull => "sled_pull",
+            CardioActivityType::JumpRope => "jump_rope",
+            CardioActivityType::Other => "other",
+        }
+    }
+
+    /// Parse a database name back into an activity type
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|activity_type| activity_type.as_str() == name)
+    }
+}
+
+impl CardioActivity {
+    /// Create a new activity with automatic UUID generation
+    ///
+    /// Distance, calories, heart rate, load and session are left empty; set them on the returned value when known.
+    pub fn new(activity_type: CardioActivityType, started_at: i64, duration_seconds: u32) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            activity_type,
+            started_at,
+            duration_seconds,
+            distance_m: None,
+            calories: None,
+            average_heart_rate: None,
+            max_heart_rate: None,
+            load_kg: None,
+            session_id: None,
+            notes: None,
+        }
+    }
+
+    /// Validate that the activity's values are within sensible ranges
+    pub fn validate(&self) -> Result<(), String> {
+        if self.duration_seconds == 0 {
+            return Err("Activity must last at least one second".to_string());
+        }
+
+        for (label, value) in [("Distance", self.distance_m), ("Load", self.load_kg)] {
+            if let Some(value) = value.filter(|v| !v.is_finite() || *v <= 0.0) {
+                return Err(format!("{} must be positive, got {}", label, value));
+            }
+        }
+
+        if let Some(calories) = self.calories.filter(|c| !c.is_finite() || *c < 0.0) {
+            return Err(format!("Calories must be a non-negative number, got {}", calories));
+        }
+
+        for (label, heart_rate) in [("Average", self.average_heart_rate), ("Max", self.max_heart_rate)] {
+            if let Some(heart_rate) = heart_rate.filter(|hr| !HEART_RATE_RANGE.contains(hr)) {
+                return Err(format!(
+                    "{} heart rate must be {}-{} bpm, got {}",
+                    label, HEART_RATE_RANGE.start(), HEART_RATE_RANGE.end(), heart_rate
+                ));
+            }
+        }
+
+        if let (Some(average), Some(max)) = (self.average_heart_rate, self.max_heart_rate) {
+            if average > max {
+                return Err(format!("Average heart rate {} is above the max of {}", average, max));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pace and speed, None where the activity has no distance
+    pub fn metrics(&self) -> CardioMetrics {
+        let seconds = self.duration_seconds as f64;
+        let distance_m = self.distance_m.filter(|d| *d > 0.0);
+        CardioMetrics {
+            pace_seconds_per_km: distance_m.map(|d| seconds / d * 1000.0),
+            pace_seconds_per_500m: distance_m.map(|d| seconds / d * 500.0),
+            speed_kmh: distance_m.map(|d| d / 1000.0 / (seconds / 3600.0)),
+        }
+    }
+}
+
+/// Pace and speed of an activity, for hosts that only hold the record
+pub fn cardio_metrics(activity: CardioActivity) -> CardioMetrics {
+    activity.metrics()
+}
+
+/// Summarize activities into one entry per calendar week from `first_week` to `last_week`
+fn summarize_weeks(activities: &[CardioActivity], first_week: CalendarDate, last_week: CalendarDate) -> Vec<CardioWeekSummary> {
+    let mut summaries = Vec::new();
+    let mut week_start = first_week;
+    while week_start <= last_week {
+        let week_end = week_start.add_days(7).to_timestamp();
+        let in_week: Vec<&CardioActivity> = activities
+            .iter()
+            .filter(|a| a.started_at >= week_start.to_timestamp() && a.started_at < week_end)
+            .collect();
+
+        let mut by_type: Vec<CardioTypeTotals> = Vec::new();
+        for activity in &in_week {
+            let index = match by_type.iter().position(|t| t.activity_type == activity.activity_type) {
+                Some(index) => index,
+                None => {
+                    by_type.push(CardioTypeTotals {
+                        activity_type: activity.activity_type,
+                        activity_count: 0,
+                        duration_seconds: 0,
+                        distance_m: 0.0,
+                    });
+                    by_type.len() - 1
+                }
+            };
+            let totals = &mut by_type[index];
+            totals.activity_count += 1;
+            totals.duration_seconds += activity.duration_seconds as u64;
+            totals.distance_m += activity.distance_m.unwrap_or(0.0);
+        }
+        by_type.sort_by_key(|totals| std::cmp::Reverse(totals.duration_seconds));
+
+        let (heart_beats, heart_seconds) = in_week
+            .iter()
+            .filter_map(|a| a.average_heart_rate.map(|hr| (hr as f64 * a.duration_seconds as f64, a.duration_seconds as f64)))
+            .fold((0.0, 0.0), |(beats, seconds), (b, s)| (beats + b, seconds + s));
+
+        summaries.push(CardioWeekSummary {
+            week_start,
+            activity_count: in_week.len() as u32,
+            duration_seconds: in_week.iter().map(|a| a.duration_seconds as u64).sum(),
+            distance_m: in_week.iter().filter_map(|a| a.distance_m).sum(),
+            calories: in_week.iter().filter_map(|a| a.calories).sum(),
+            average_heart_rate: (heart_seconds > 0.0).then(|| heart_beats / heart_seconds),
+            by_type,
+        });
+        week_start = week_start.add_days(7);
+    }
+    summaries
+}
+
+impl ExerciseRepository {
+    /// Log a cardio activity
+    pub fn log_cardio_activity(&self, activity: CardioActivity) -> Result<(), WeightliftingError> {
+        info!("🏃 Logging {} for {} seconds", activity.activity_type.as_str(), activity.duration_seconds);
+
+        activity.validate().map_err(|message| {
+            warn!("❌ Invalid cardio activity: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        conn.execute(
+            &format!(
+                "INSERT INTO cardio_activities ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                CARDIO_COLUMNS
+            ),
+            params![
+                activity.id,
+                activity.activity_type.as_str(),
+                activity.started_at,
+                activity.duration_seconds,
+                activity.distance_m,
+                activity.calories,
+                activity.average_heart_rate,
+                activity.max_heart_rate,
+                activity.load_kg,
+                activity.session_id,
+                activity.notes
+            ],
+        ).map_err(|e| {
+            error!("❌ Failed to insert cardio activity '{}': {}", activity.id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to insert cardio activity: {}", e),
+            }
+        })?;
+
+        drop(conn);
+
+        info!("✅ Logged cardio activity: {}", activity.id);
+        self.notify_achievements(TrainingEvent::Cardio(activity.started_at));
+        Ok(())
+    }
+
+    /// Get the cardio activities started between `start` and `end` (inclusive), oldest first
+    pub fn get_cardio_activities(&self, start: i64, end: i64) -> Result<Vec<CardioActivity>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_cardio_activities(&conn, start, end)
+    }
+
+    /// Delete a cardio activity by ID
+    /// Returns true if the activity was deleted, false if it wasn't found
+    pub fn delete_cardio_activity(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting cardio activity with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM cardio_activities WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Summarize cardio per calendar week for every week touching `start`..=`end`
+    ///
+    /// Each summary covers its whole Monday-Sunday week, including days outside
+    /// the range. Weeks without activities are included with zero totals so
+    /// charts have no gaps.
+    pub fn get_cardio_weekly_summaries(&self, start: i64, end: i64) -> Result<Vec<CardioWeekSummary>, WeightliftingError> {
+        info!("📅 Summarizing cardio by week between {} and {}", start, end);
+
+        if start > end {
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Start {} is after end {}", start, end),
+            });
+        }
+
+        let first_week = CalendarDate::from_timestamp(start).start_of_week();
+        let last_week = CalendarDate::from_timestamp(end).start_of_week();
+
+        let conn = self.connection()?;
+        let activities = Self::query_cardio_activities(
+            &conn,
+            first_week.to_timestamp(),
+            last_week.add_days(7).to_timestamp() - 1,
+        )?;
+        let summaries = summarize_weeks(&activities, first_week, last_week);
+
+        debug!("📊 {} activities over {} weeks", activities.len(), summaries.len());
+        Ok(summaries)
+    }
+
+    fn query_cardio_activities(conn: &Connection, start: i64, end: i64) -> Result<Vec<CardioActivity>, WeightliftingError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM cardio_activities WHERE started_at BETWEEN ?1 AND ?2 ORDER BY started_at, rowid",
+            CARDIO_COLUMNS
+        ))?;
+        let activities = stmt
+            .query_map(params![start, end], cardio_activity_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(activities)
+    }
+
+    /// Create the cardio activity table if it doesn't exist
+    pub(crate) fn create_cardio_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating cardio_activities table if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cardio_activities (
+                id TEXT PRIMARY KEY,
+                activity_type TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                duration_seconds INTEGER NOT NULL,
+                distance_m REAL,
+                calories REAL,
+                average_heart_rate INTEGER,
+                max_heart_rate INTEGER,
+                load_kg REAL,
+                session_id TEXT,
+                notes TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_cardio_activities_started
+                ON cardio_activities (started_at);",
+        ).map_err(|e| {
+            error!("❌ Failed to create cardio_activities table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Cardio table ready");
+        Ok(())
+    }
+}
+
+fn cardio_activity_from_row(row: &Row) -> rusqlite::Result<CardioActivity> {
+    let type_name: String = row.get(1)?;
+    let activity_type = CardioActivityType::parse(&type_name)
+        .ok_or_else(|| rusqlite::Error::InvalidColumnType(1, "activity_type".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(CardioActivity {
+        id: row.get(0)?,
+        activity_type,
+        started_at: row.get(2)?,
+        duration_seconds: row.get(3)?,
+        distance_m: row.get(4)?,
+        calories: row.get(5)?,
+        average_heart_rate: row.get(6)?,
+        max_heart_rate: row.get(7)?,
+        load_kg: row.get(8)?,
+        session_id: row.get(9)?,
+        notes: row.get(10)?,
+    })
+}
@@ -0,0 +1,241 @@
+// Chart-ready series: training and body metrics bucketed by period and downsampled
+
+use crate::body_measurements::{BodyMeasurementRepository, BodyMetric};
+use crate::calendar::{days_in_month, CalendarDate};
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::rpe::estimate_logged_one_rm;
+use crate::stats::{lttb_indices, TimeSeriesPoint};
+use log::{debug, info, warn};
+
+/// What a chart plots
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChartMetric {
+    /// Estimated 1RM of each set
+    EstimatedOneRm { exercise_id: String },
+    /// Weight of each strength-comparable set
+    HeaviestWeight { exercise_id: String },
+    /// Volume of each set, of one exercise or all of them
+    Volume { exercise_id: Option<String> },
+    /// Reps of each set, of one exercise or all of them
+    Reps { exercise_id: Option<String> },
+    Body { metric: BodyMetric },
+}
+
+/// Period points are grouped into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartBucket {
+    Day,
+    Week, // Monday-based
+    Month,
+}
+
+/// How a bucket's values become its plotted value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesAggregation {
+    Average,
+    Min,
+    Max,
+    Sum,
+}
+
+/// A chart request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartQuery {
+    pub metric: ChartMetric,
+    pub start: i64,
+    pub end: i64,
+    pub bucket: Option<ChartBucket>,           // Raw points when not set
+    pub aggregation: Option<SeriesAggregation>, // The metric's natural aggregation when not set, needs a bucket
+    pub max_points: Option<u32>,               // Downsampled with LTTB above this many points
+}
+
+/// One plotted point; raw points have a count of 1 and min and max equal to the value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPoint {
+    pub timestamp: i64, // Start of the bucket for bucketed series, in UTC
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub count: u32,
+}
+
+/// A series ready to plot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    pub metric: ChartMetric,
+    pub aggregation: SeriesAggregation,
+    pub points: Vec<ChartPoint>,
+    pub raw_point_count: u32, // Points before bucketing and downsampling
+}
+
+impl ChartMetric {
+    /// Aggregation used when a query doesn't ask for one
+    pub fn default_aggregation(&self) -> SeriesAggregation {
+        match self {
+            ChartMetric::EstimatedOneRm { .. } | ChartMetric::HeaviestWeight { .. } => SeriesAggregation::Max,
+            ChartMetric::Volume { .. } | ChartMetric::Reps { .. } => SeriesAggregation::Sum,
+            ChartMetric::Body { .. } => SeriesAggregation::Average,
+        }
+    }
+}
+
+impl ChartBucket {
+    /// Start of the bucket containing `timestamp`
+    pub fn start_of(&self, timestamp: i64) -> i64 {
+        let date = CalendarDate::from_timestamp(timestamp);
+        match self {
+            ChartBucket::Day => date,
+            ChartBucket::Week => date.start_of_week(),
+            ChartBucket::Month => date.start_of_month(),
+        }
+        .to_timestamp()
+    }
+
+    /// Last second of the bucket containing `timestamp`
+    pub fn end_of(&self, timestamp: i64) -> i64 {
+        let date = CalendarDate::from_timestamp(timestamp);
+        let next = match self {
+            ChartBucket::Day => date.add_days(1),
+            ChartBucket::Week => date.start_of_week().add_days(7),
+            ChartBucket::Month => date.start_of_month().add_days(days_in_month(date.year, date.month) as i64),
+        };
+        next.to_timestamp() - 1
+    }
+}
+
+/// Group time-ordered points into buckets, plotting each bucket's `aggregation`
+fn bucket_points(points: &[TimeSeriesPoint], bucket: ChartBucket, aggregation: SeriesAggregation) -> Vec<ChartPoint> {
+    let mut buckets: Vec<(ChartPoint, f64)> = Vec::new(); // Each bucket with its running sum
+    for point in points {
+        let timestamp = bucket.start_of(point.timestamp);
+        match buckets.last_mut() {
+            Some((last, sum)) if last.timestamp == timestamp => {
+                last.min = last.min.min(point.value);
+                last.max = last.max.max(point.value);
+                last.count += 1;
+                *sum += point.value;
+            }
+            _ => buckets.push((
+                ChartPoint { timestamp, value: point.value, min: point.value, max: point.value, count: 1 },
+                point.value,
+            )),
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(point, sum)| ChartPoint {
+            value: match aggregation {
+                SeriesAggregation::Average => sum / point.count as f64,
+                SeriesAggregation::Min => point.min,
+                SeriesAggregation::Max => point.max,
+                SeriesAggregation::Sum => sum,
+            },
+            ..point
+        })
+        .collect()
+}
+
+/// Plot points one to one
+fn raw_chart_points(points: &[TimeSeriesPoint]) -> Vec<ChartPoint> {
+    points
+        .iter()
+        .map(|p| ChartPoint { timestamp: p.timestamp, value: p.value, min: p.value, max: p.value, count: 1 })
+        .collect()
+}
+
+impl ExerciseRepository {
+    /// Get a metric's series between `start` and `end` (inclusive), bucketed and downsampled for charts
+    ///
+    /// Points are grouped by the query's bucket first, then reduced to
+    /// `max_points` with Largest-Triangle-Three-Buckets, which keeps peaks and
+    /// dips that plain averaging would flatten. Bucketed series cover whole
+    /// buckets, so the first and last buckets also include the parts of their
+    /// day, week or month outside the range, as cardio weekly summaries do.
+    pub fn get_chart_series(&self, query: ChartQuery) -> Result<ChartSeries, WeightliftingError> {
+        info!("📈 Building chart series for {:?}", query.metric);
+
+        if query.start > query.end {
+            warn!("❌ Chart range starts after it ends: {} > {}", query.start, query.end);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Chart range start {} is after its end {}", query.start, query.end),
+            });
+        }
+        if let Some(max_points) = query.max_points.filter(|&m| m < 2) {
+            warn!("❌ Too few chart points requested: {}", max_points);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("A chart needs at least 2 points, got {}", max_points),
+            });
+        }
+
+        if query.aggregation.is_some() && query.bucket.is_none() {
+            warn!("❌ Chart aggregation requested without a bucket");
+            return Err(WeightliftingError::InvalidInput {
+                message: "A chart aggregation needs a bucket to aggregate over".to_string(),
+            });
+        }
+
+        let (start, end) = match query.bucket {
+            Some(bucket) => (bucket.start_of(query.start), bucket.end_of(query.end)),
+            None => (query.start, query.end),
+        };
+        let conn = self.connection()?;
+        let raw = match query.metric {
+            ChartMetric::Body { metric } => BodyMeasurementRepository::query_metric_series(&conn, metric, start, end)?,
+            ChartMetric::EstimatedOneRm { ref exercise_id } | ChartMetric::HeaviestWeight { ref exercise_id } => {
+                Self::ensure_exercise_exists(&conn, exercise_id)?;
+                let is_one_rm = matches!(query.metric, ChartMetric::EstimatedOneRm { .. });
+                Self::query_sets_between(&conn, Some(exercise_id), start, end)?
+                    .iter()
+                    .filter_map(|set| {
+                        let value = if is_one_rm {
+                            estimate_logged_one_rm(set)
+                        } else {
+                            set.is_strength_comparable().then_some(set.weight_kg)
+                        };
+                        value.map(|value| TimeSeriesPoint { timestamp: set.performed_at, value })
+                    })
+                    .collect()
+            }
+            ChartMetric::Volume { ref exercise_id } | ChartMetric::Reps { ref exercise_id } => {
+                if let Some(id) = exercise_id {
+                    Self::ensure_exercise_exists(&conn, id)?;
+                }
+                let mut points = Vec::new();
+                for set in Self::query_sets_between(&conn, exercise_id.as_deref(), start, end)? {
+                    let value = match query.metric {
+                        ChartMetric::Volume { .. } => Self::set_volume(&conn, &set)?,
+                        _ => set.total_reps() as f64,
+                    };
+                    points.push(TimeSeriesPoint { timestamp: set.performed_at, value });
+                }
+                points
+            }
+        };
+
+        let aggregation = query.aggregation.unwrap_or_else(|| query.metric.default_aggregation());
+        let mut points = match query.bucket {
+            Some(bucket) => bucket_points(&raw, bucket, aggregation),
+            None => raw_chart_points(&raw),
+        };
+
+        if let Some(max_points) = query.max_points {
+            let values: Vec<TimeSeriesPoint> =
+                points.iter().map(|p| TimeSeriesPoint { timestamp: p.timestamp, value: p.value }).collect();
+            let kept = lttb_indices(&values, max_points as usize);
+            if kept.len() < points.len() {
+                debug!("📉 Downsampling {} points to {}", points.len(), kept.len());
+                points = kept.into_iter().map(|i| points[i].clone()).collect();
+            }
+        }
+
+        info!("✅ Chart series has {} points from {} raw points", points.len(), raw.len());
+        Ok(ChartSeries {
+            metric: query.metric,
+            aggregation,
+            points,
+            raw_point_count: raw.len() as u32,
+        })
+    }
+}
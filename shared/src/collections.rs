@@ -0,0 +1,325 @@
+// User tags, favorites and saved smart collections of exercises
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::muscles::{normalize_muscle_group, BodyRegion};
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Criteria an exercise must meet to appear in a filtered list
+///
+/// Empty lists and unset options don't restrict the results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExerciseFilter {
+    pub name_contains: Option<String>,
+    pub tags: Vec<String>,             // Exercise must carry every tag
+    pub muscle_groups: Vec<String>,    // Exercise must target at least one
+    pub body_regions: Vec<BodyRegion>, // Exercise must target at least one
+    pub favorites_only: bool,
+    pub available_equipment: Option<Vec<String>>, // Exercise must need nothing else
+    pub min_difficulty: Option<u8>,
+    pub max_difficulty: Option<u8>,
+}
+
+/// A named, saved filter whose exercises are worked out when it is evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartCollection {
+    pub id: String,
+    pub name: String,
+    pub filter: ExerciseFilter,
+}
+
+/// Trim tags and drop blanks and case-insensitive duplicates, keeping the first spelling
+pub(crate) fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !normalized.iter().any(|existing| existing.to_lowercase() == tag.to_lowercase()) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+fn has_tag(exercise: &Exercise, tag: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    exercise.tags.iter().any(|existing| existing.to_lowercase() == tag)
+}
+
+impl ExerciseFilter {
+    /// Validate the filter's difficulty range and muscle names
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_difficulty, self.max_difficulty) {
+            if min > max {
+                return Err(format!("Minimum difficulty {} is above maximum {}", min, max));
+            }
+        }
+
+        if let Some(unknown) = self.muscle_groups.iter().find(|name| normalize_muscle_group(name).is_none()) {
+            return Err(format!("Unrecognized muscle group '{}'", unknown));
+        }
+
+        Ok(())
+    }
+
+    /// Whether an exercise meets every criterion
+    pub fn matches(&self, exercise: &Exercise) -> bool {
+        if let Some(ref needle) = self.name_contains {
+            if !exercise.name.to_lowercase().contains(&needle.trim().to_lowercase()) {
+                return false;
+            }
+        }
+
+        if !self.tags.iter().all(|tag| has_tag(exercise, tag)) {
+            return false;
+        }
+
+        let targeted: Vec<_> = exercise.muscle_groups.iter().filter_map(|name| normalize_muscle_group(name)).collect();
+        if !self.muscle_groups.is_empty()
+            && !self.muscle_groups.iter().filter_map(|name| normalize_muscle_group(name)).any(|group| targeted.contains(&group))
+        {
+            return false;
+        }
+        if !self.body_regions.is_empty() && !targeted.iter().any(|group| self.body_regions.contains(&group.region())) {
+            return false;
+        }
+
+        if self.favorites_only && !exercise.is_favorite {
+            return false;
+        }
+
+        if let Some(ref available) = self.available_equipment {
+            if !exercise.equipment_ids.iter().all(|id| available.contains(id)) {
+                return false;
+            }
+        }
+
+        self.min_difficulty.is_none_or(|min| exercise.difficulty_level >= min)
+            && self.max_difficulty.is_none_or(|max| exercise.difficulty_level <= max)
+    }
+}
+
+impl SmartCollection {
+    /// Create a new smart collection with automatic UUID generation
+    pub fn new(name: String, filter: ExerciseFilter) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            filter,
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Star or unstar an exercise
+    pub fn set_exercise_favorite(&self, exercise_id: String, is_favorite: bool) -> Result<(), WeightliftingError> {
+        info!("⭐ Setting favorite = {} for exercise {}", is_favorite, exercise_id);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        conn.execute(
+            "UPDATE exercises SET is_favorite = ?1 WHERE id = ?2",
+            params![is_favorite, exercise_id],
+        )?;
+        Ok(())
+    }
+
+    /// Tag an exercise; tags are matched case-insensitively
+    pub fn add_exercise_tag(&self, exercise_id: String, tag: String) -> Result<(), WeightliftingError> {
+        info!("🏷️ Tagging exercise {} with '{}'", exercise_id, tag);
+
+        if tag.trim().is_empty() {
+            warn!("❌ Empty tag for exercise {}", exercise_id);
+            return Err(WeightliftingError::InvalidInput {
+                message: "Tag cannot be empty".to_string(),
+            });
+        }
+
+        let conn = self.connection()?;
+        let mut exercise = Self::query_exercise(&conn, &exercise_id)?;
+        exercise.tags.push(tag);
+        Self::update_tags(&conn, &exercise_id, &normalize_tags(&exercise.tags))
+    }
+
+    /// Remove a tag from an exercise
+    /// Returns true if the tag was removed, false if the exercise didn't carry it
+    pub fn remove_exercise_tag(&self, exercise_id: String, tag: String) -> Result<bool, WeightliftingError> {
+        info!("🏷️ Removing tag '{}' from exercise {}", tag, exercise_id);
+
+        let conn = self.connection()?;
+        let exercise = Self::query_exercise(&conn, &exercise_id)?;
+        if !has_tag(&exercise, &tag) {
+            return Ok(false);
+        }
+
+        let tag = tag.trim().to_lowercase();
+        let remaining: Vec<String> = exercise.tags.into_iter().filter(|existing| existing.to_lowercase() != tag).collect();
+        Self::update_tags(&conn, &exercise_id, &remaining)?;
+        Ok(true)
+    }
+
+    /// Get every tag in use, sorted alphabetically
+    pub fn get_all_tags(&self) -> Result<Vec<String>, WeightliftingError> {
+        let conn = self.connection()?;
+        let all_tags: Vec<String> = Self::query_exercises(&conn, "", [])?
+            .into_iter()
+            .flat_map(|exercise| exercise.tags)
+            .collect();
+
+        let mut tags = normalize_tags(&all_tags);
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        Ok(tags)
+    }
+
+    /// Get the exercises matching a filter, sorted by name
+    pub fn filter_exercises(&self, filter: ExerciseFilter) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("🔎 Filtering exercises");
+        debug!("📝 Filter: {:?}", filter);
+
+        filter.validate().map_err(|message| {
+            warn!("❌ Invalid filter: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        let exercises = Self::query_filtered_exercises(&conn, &filter)?;
+        info!("✅ {} exercises match the filter", exercises.len());
+        Ok(exercises)
+    }
+
+    /// Save a smart collection, replacing any existing collection with the same ID
+    pub fn save_smart_collection(&self, collection: SmartCollection) -> Result<(), WeightliftingError> {
+        info!("📂 Saving smart collection: {} (ID: {})", collection.name, collection.id);
+
+        if collection.name.trim().is_empty() {
+            warn!("❌ Smart collection name is empty");
+            return Err(WeightliftingError::InvalidInput {
+                message: "Collection name cannot be empty".to_string(),
+            });
+        }
+        collection.filter.validate().map_err(|message| {
+            warn!("❌ Invalid filter: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let filter_json = serde_json::to_string(&collection.filter).map_err(|e| {
+            error!("❌ Failed to serialize filter: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize filter: {}", e),
+            }
+        })?;
+
+        let conn = self.connection()?;
+        conn.execute(
+            "INSERT INTO smart_collections (id, name, filter) VALUES (?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, filter = excluded.filter",
+            params![collection.id, collection.name, filter_json],
+        )?;
+
+        info!("✅ Saved smart collection: {}", collection.name);
+        Ok(())
+    }
+
+    /// Get a smart collection by ID
+    pub fn get_smart_collection(&self, id: String) -> Result<SmartCollection, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_smart_collection(&conn, &id)
+    }
+
+    /// Get all smart collections, sorted by name
+    pub fn get_all_smart_collections(&self) -> Result<Vec<SmartCollection>, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare("SELECT id, name, filter FROM smart_collections ORDER BY name")?;
+        let collections = stmt.query_map([], smart_collection_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(collections)
+    }
+
+    /// Delete a smart collection by ID
+    /// Returns true if the collection was deleted, false if it wasn't found
+    pub fn delete_smart_collection(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting smart collection with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM smart_collections WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get the exercises currently matching a smart collection's filter, sorted by name
+    pub fn evaluate_smart_collection(&self, id: String) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("📂 Evaluating smart collection {}", id);
+
+        let conn = self.connection()?;
+        let collection = Self::query_smart_collection(&conn, &id)?;
+        let exercises = Self::query_filtered_exercises(&conn, &collection.filter)?;
+
+        info!("✅ '{}' contains {} exercises", collection.name, exercises.len());
+        Ok(exercises)
+    }
+
+    fn query_filtered_exercises(conn: &Connection, filter: &ExerciseFilter) -> Result<Vec<Exercise>, WeightliftingError> {
+        Ok(Self::query_exercises(conn, "ORDER BY name", [])?
+            .into_iter()
+            .filter(|exercise| filter.matches(exercise))
+            .collect())
+    }
+
+    fn update_tags(conn: &Connection, exercise_id: &str, tags: &[String]) -> Result<(), WeightliftingError> {
+        let tags_json = serde_json::to_string(tags).map_err(|e| {
+            error!("❌ Failed to serialize tags: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize tags: {}", e),
+            }
+        })?;
+        conn.execute("UPDATE exercises SET tags = ?1 WHERE id = ?2", params![tags_json, exercise_id])?;
+        debug!("🏷️ Exercise {} tags: {:?}", exercise_id, tags);
+        Ok(())
+    }
+
+    fn query_smart_collection(conn: &Connection, id: &str) -> Result<SmartCollection, WeightliftingError> {
+        conn.query_row(
+            "SELECT id, name, filter FROM smart_collections WHERE id = ?1",
+            [id],
+            smart_collection_from_row,
+        ).map_err(|e| {
+            warn!("❌ Smart collection not found with ID '{}': {}", id, e);
+            WeightliftingError::RecordNotFound { id: id.to_string() }
+        })
+    }
+
+    /// Create the smart collections table if it doesn't exist
+    pub(crate) fn create_collection_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating smart_collections table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS smart_collections (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                filter TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create smart_collections table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Smart collections table ready");
+        Ok(())
+    }
+}
+
+fn smart_collection_from_row(row: &Row) -> rusqlite::Result<SmartCollection> {
+    let filter_json: String = row.get(2)?;
+    let filter = serde_json::from_str(&filter_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(2, "filter".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(SmartCollection {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        filter,
+    })
+}
@@ -0,0 +1,335 @@
+// Equipment catalog and gym profiles listing the equipment available at a gym
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+const GYM_PROFILE_COLUMNS: &str = "id, name, equipment_ids, smallest_increment_kg";
+
+/// Equipment registered in every new database
+const STANDARD_EQUIPMENT: [(&str, &str); 14] = [
+    ("barbell", "Barbell"),
+    ("dumbbell", "Dumbbell"),
+    ("kettlebell", "Kettlebell"),
+    ("ez-bar", "EZ Bar"),
+    ("trap-bar", "Trap Bar"),
+    ("bench", "Bench"),
+    ("squat-rack", "Squat Rack"),
+    ("pull-up-bar", "Pull-up Bar"),
+    ("dip-station", "Dip Station"),
+    ("cable-machine", "Cable Machine"),
+    ("smith-machine", "Smith Machine"),
+    ("leg-press-machine", "Leg Press Machine"),
+    ("resistance-band", "Resistance Band"),
+    ("plates", "Weight Plates"),
+];
+
+/// Legacy free-text values (as IDs) that meant no equipment at all
+const NO_EQUIPMENT_TEXT: [&str; 6] = ["none", "bodyweight", "body-weight", "no-equipment", "n-a", "na"];
+
+/// A piece of equipment exercises can require
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equipment {
+    pub id: String, // Lowercase slug, e.g. "pull-up-bar"
+    pub name: String,
+}
+
+/// A gym and the equipment available there
+#[derive(Debug, Clone, PartialEq)]
+pub struct GymProfile {
+    pub id: String,
+    pub name: String,
+    pub equipment_ids: Vec<String>,
+    pub smallest_increment_kg: Option<f64>, // Smallest load jump the gym's plates allow, None for the app default
+}
+
+/// Derive an equipment ID from its display name ("Pull-up Bar" -> "pull-up-bar")
+pub fn equipment_id_for_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl Equipment {
+    /// Create equipment with an ID derived from its name
+    pub fn new(name: String) -> Self {
+        Self {
+            id: equipment_id_for_name(&name),
+            name,
+        }
+    }
+}
+
+impl GymProfile {
+    /// Create a new gym profile with automatic UUID generation
+    pub fn new(name: String, equipment_ids: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            equipment_ids,
+            smallest_increment_kg: None,
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Register a piece of equipment, replacing the name of an existing one with the same ID
+    pub fn add_equipment(&self, equipment: Equipment) -> Result<(), WeightliftingError> {
+        info!("🏋️ Adding equipment: {} (ID: {})", equipment.name, equipment.id);
+
+        if equipment.name.trim().is_empty() || equipment.id != equipment_id_for_name(&equipment.id) {
+            warn!("❌ Invalid equipment: {:?}", equipment);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!(
+                    "Equipment needs a name and a lowercase hyphenated ID, got '{}' ('{}')",
+                    equipment.name, equipment.id
+                ),
+            });
+        }
+
+        let conn = self.connection()?;
+        conn.execute(
+            "INSERT INTO equipment (id, name) VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name",
+            params![equipment.id, equipment.name],
+        )?;
+
+        info!("✅ Successfully added equipment: {}", equipment.name);
+        Ok(())
+    }
+
+    /// Get all registered equipment, sorted by name
+    pub fn get_all_equipment(&self) -> Result<Vec<Equipment>, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare("SELECT id, name FROM equipment ORDER BY name")?;
+        let equipment = stmt
+            .query_map([], |row| Ok(Equipment { id: row.get(0)?, name: row.get(1)? }))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(equipment)
+    }
+
+    /// Save a gym profile, replacing any existing profile with the same ID
+    pub fn save_gym_profile(&self, gym: GymProfile) -> Result<(), WeightliftingError> {
+        info!("🏢 Saving gym profile: {} (ID: {})", gym.name, gym.id);
+        debug!("📝 Gym details: {:?}", gym);
+
+        if gym.name.trim().is_empty() {
+            warn!("❌ Gym profile name is empty");
+            return Err(WeightliftingError::InvalidInput {
+                message: "Gym name cannot be empty".to_string(),
+            });
+        }
+
+        if let Some(increment_kg) = gym.smallest_increment_kg.filter(|i| !i.is_finite() || *i <= 0.0) {
+            warn!("❌ Invalid smallest increment: {}", increment_kg);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Smallest increment must be positive, got {}", increment_kg),
+            });
+        }
+
+        let conn = self.connection()?;
+        Self::ensure_equipment_exists(&conn, &gym.equipment_ids)?;
+
+        let equipment_json = serde_json::to_string(&gym.equipment_ids).map_err(|e| {
+            error!("❌ Failed to serialize gym equipment: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize gym equipment: {}", e),
+            }
+        })?;
+
+        conn.execute(
+            "INSERT INTO gym_profiles (id, name, equipment_ids, smallest_increment_kg) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, equipment_ids = excluded.equipment_ids,
+                smallest_increment_kg = excluded.smallest_increment_kg",
+            params![gym.id, gym.name, equipment_json, gym.smallest_increment_kg],
+        )?;
+
+        info!("✅ Saved gym profile with {} pieces of equipment", gym.equipment_ids.len());
+        Ok(())
+    }
+
+    /// Get a gym profile by ID
+    pub fn get_gym_profile(&self, id: String) -> Result<GymProfile, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_gym_profile(&conn, &id)
+    }
+
+    /// Get all gym profiles, sorted by name
+    pub fn get_all_gym_profiles(&self) -> Result<Vec<GymProfile>, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM gym_profiles ORDER BY name", GYM_PROFILE_COLUMNS))?;
+        let gyms = stmt.query_map([], gym_profile_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(gyms)
+    }
+
+    /// Delete a gym profile by ID
+    /// Returns true if the profile was deleted, false if it wasn't found
+    pub fn delete_gym_profile(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting gym profile with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM gym_profiles WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get the exercises whose required equipment is all available at a gym, sorted by name
+    pub fn get_exercises_for_gym(&self, gym_id: String) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("🏢 Retrieving exercises performable at gym {}", gym_id);
+
+        let conn = self.connection()?;
+        let gym = Self::query_gym_profile(&conn, &gym_id)?;
+        let equipment_json = serde_json::to_string(&gym.equipment_ids).map_err(|e| {
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize gym equipment: {}", e),
+            }
+        })?;
+
+        let exercises = Self::query_exercises(
+            &conn,
+            "WHERE NOT EXISTS (
+                SELECT 1 FROM json_each(exercises.equipment_ids) needed
+                WHERE needed.value NOT IN (SELECT value FROM json_each(?1))
+            ) ORDER BY name",
+            [&equipment_json],
+        )?;
+
+        info!("✅ {} exercises can be performed at {}", exercises.len(), gym.name);
+        Ok(exercises)
+    }
+
+    /// Return an InvalidInput error unless every equipment ID is registered
+    pub(crate) fn ensure_equipment_exists(conn: &Connection, equipment_ids: &[String]) -> Result<(), WeightliftingError> {
+        for id in equipment_ids {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM equipment WHERE id = ?1)",
+                [id],
+                |row| row.get(0),
+            )?;
+
+            if !exists {
+                warn!("❌ Unknown equipment '{}'", id);
+                return Err(WeightliftingError::InvalidInput {
+                    message: format!("Unknown equipment '{}'", id),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn query_gym_profile(conn: &Connection, id: &str) -> Result<GymProfile, WeightliftingError> {
+        conn.query_row(
+            &format!("SELECT {} FROM gym_profiles WHERE id = ?1", GYM_PROFILE_COLUMNS),
+            [id],
+            gym_profile_from_row,
+        ).map_err(|e| {
+            warn!("❌ Gym profile not found with ID '{}': {}", id, e);
+            WeightliftingError::RecordNotFound { id: id.to_string() }
+        })
+    }
+
+    /// Create the equipment and gym tables, moving free-text equipment from older databases
+    pub(crate) fn create_equipment_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating equipment and gym_profiles tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS equipment (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS gym_profiles (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                equipment_ids TEXT NOT NULL,
+                smallest_increment_kg REAL
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create equipment tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        Self::add_column_if_missing(conn, "gym_profiles", "smallest_increment_kg", "REAL")?;
+
+        for (id, name) in STANDARD_EQUIPMENT {
+            conn.execute("INSERT OR IGNORE INTO equipment (id, name) VALUES (?1, ?2)", params![id, name])?;
+        }
+
+        // Databases created before equipment was structured stored one free-text value per exercise
+        Self::add_column_if_missing(conn, "exercises", "equipment_ids", "TEXT NOT NULL DEFAULT '[]'")?;
+        if Self::has_column(conn, "exercises", "equipment_needed")? {
+            Self::migrate_free_text_equipment(conn)?;
+        }
+
+        debug!("✅ Equipment tables ready");
+        Ok(())
+    }
+
+    /// Convert the legacy `equipment_needed` text column into equipment IDs, then drop it
+    fn migrate_free_text_equipment(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🔧 Migrating free-text exercise equipment");
+
+        let legacy: Vec<(String, String)> = {
+            let mut stmt = conn.prepare("SELECT id, equipment_needed FROM exercises WHERE equipment_needed IS NOT NULL")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        for (exercise_id, text) in legacy {
+            let mut equipment_ids: Vec<String> = Vec::new();
+            // Check the whole value first so "N/A" isn't split into "n" and "a"
+            let no_equipment = NO_EQUIPMENT_TEXT.contains(&equipment_id_for_name(&text).as_str());
+            let parts = text.split([',', '/', '&', '+']).filter(|part| {
+                let id = equipment_id_for_name(part);
+                !no_equipment && id.chars().count() > 1 && !NO_EQUIPMENT_TEXT.contains(&id.as_str())
+            });
+            for part in parts {
+                let equipment = Equipment::new(part.trim().to_string());
+                tx.execute(
+                    "INSERT OR IGNORE INTO equipment (id, name) VALUES (?1, ?2)",
+                    params![equipment.id, equipment.name],
+                )?;
+                if !equipment_ids.contains(&equipment.id) {
+                    equipment_ids.push(equipment.id);
+                }
+            }
+
+            debug!("🔧 Exercise {}: '{}' -> {:?}", exercise_id, text, equipment_ids);
+            let equipment_json = serde_json::to_string(&equipment_ids).map_err(|e| {
+                WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize equipment: {}", e),
+                }
+            })?;
+            tx.execute(
+                "UPDATE exercises SET equipment_ids = ?1 WHERE id = ?2",
+                params![equipment_json, exercise_id],
+            )?;
+        }
+        tx.execute("ALTER TABLE exercises DROP COLUMN equipment_needed", [])?;
+        tx.commit()?;
+
+        info!("✅ Free-text equipment migrated");
+        Ok(())
+    }
+}
+
+fn gym_profile_from_row(row: &Row) -> rusqlite::Result<GymProfile> {
+    let equipment_json: String = row.get(2)?;
+    let equipment_ids = serde_json::from_str(&equipment_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(2, "equipment_ids".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(GymProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        equipment_ids,
+        smallest_increment_kg: row.get(3)?,
+    })
+}
@@ -11,9 +11,21 @@ pub enum WeightliftingError {
     #[error("Exercise not found with ID: {id}")]
     ExerciseNotFound { id: String },
 
+    /// Record not found errors for entities other than exercises
+    #[error("Record not found with ID: {id}")]
+    RecordNotFound { id: String },
+
     /// Invalid input errors
     #[error("Invalid input: {message}")]
     InvalidInput { message: String },
+
+    /// Not enough stored data to compute a result
+    #[error("Insufficient data: {message}")]
+    InsufficientData { message: String },
+
+    /// Operation not allowed in the current state
+    #[error("Invalid state: {message}")]
+    InvalidState { message: String },
 }
 
 impl From<rusqlite::Error> for WeightliftingError {
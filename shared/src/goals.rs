@@ -0,0 +1,376 @@
+// Training goals and an evaluator that measures progress from stored data
+
+use crate::body_measurements::BodyMeasurementRepository;
+use crate::calendar::CalendarDate;
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::rpe::estimate_logged_one_rm;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const DAY_SECONDS: i64 = 86_400;
+/// How far back sets count towards a strength goal's current estimated 1RM
+const STRENGTH_LOOKBACK_SECONDS: i64 = 28 * DAY_SECONDS;
+/// Length of the calendar week weekly volume and frequency goals are measured over
+const WEEK_SECONDS: i64 = 7 * DAY_SECONDS;
+
+/// What a goal is aiming for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GoalTarget {
+    /// Reach an estimated 1RM on an exercise
+    Strength { exercise_id: String, target_one_rm_kg: f64 },
+    /// Sustain a weekly training volume (Monday-based weeks), for one exercise or overall
+    Volume { exercise_id: Option<String>, target_weekly_volume_kg: f64 },
+    /// Sustain a number of training sessions per week
+    Frequency { sessions_per_week: u32 },
+    /// Reach a bodyweight, either by gaining or losing
+    Bodyweight { target_kg: f64 },
+}
+
+/// A training goal with a deadline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    pub id: String,
+    pub name: String,
+    pub target: GoalTarget,
+    pub created_at: i64,
+    pub deadline: i64,
+    pub start_value: Option<f64>, // Captured when the goal is added; sets which way a bodyweight goal goes
+}
+
+/// Where a goal stands relative to its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    OnTrack,
+    OffTrack,
+    Achieved,
+    Missed,
+}
+
+/// Which way a goal's value has to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GoalDirection {
+    Increase,
+    Decrease,
+}
+
+/// Evaluated progress towards a goal
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub goal_id: String,
+    pub current_value: Option<f64>,
+    pub target_value: f64,
+    pub percent_complete: f64,
+    pub expected_percent: f64, // Where a steady pace would be by now; for weekly goals, within the current week
+    pub status: GoalStatus,
+}
+
+impl GoalTarget {
+    fn target_value(&self) -> f64 {
+        match *self {
+            GoalTarget::Strength { target_one_rm_kg, .. } => target_one_rm_kg,
+            GoalTarget::Volume { target_weekly_volume_kg, .. } => target_weekly_volume_kg,
+            GoalTarget::Frequency { sessions_per_week } => sessions_per_week as f64,
+            GoalTarget::Bodyweight { target_kg } => target_kg,
+        }
+    }
+
+    /// Rate goals are about sustaining a weekly level rather than reaching a value once
+    fn is_rate(&self) -> bool {
+        matches!(self, GoalTarget::Volume { .. } | GoalTarget::Frequency { .. })
+    }
+
+    /// Strength and rate goals always go up; bodyweight goes towards the
+    /// target from wherever it started
+    fn direction(&self, start_value: Option<f64>) -> GoalDirection {
+        match *self {
+            GoalTarget::Bodyweight { target_kg } if start_value.is_some_and(|start| start > target_kg) => {
+                GoalDirection::Decrease
+            }
+            _ => GoalDirection::Increase,
+        }
+    }
+}
+
+/// Start of the window rate goals are measured over at `at`: the Monday of its week
+fn rate_window_start(at: i64) -> i64 {
+    CalendarDate::from_timestamp(at).start_of_week().to_timestamp()
+}
+
+impl Goal {
+    /// Create a new goal with automatic UUID generation
+    pub fn new(name: String, target: GoalTarget, created_at: i64, deadline: i64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            target,
+            created_at,
+            deadline,
+            start_value: None,
+        }
+    }
+
+    /// Validate the goal's target and dates
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Goal name cannot be empty".to_string());
+        }
+
+        if self.deadline <= self.created_at {
+            return Err("Goal deadline must be after its creation".to_string());
+        }
+
+        let target = self.target.target_value();
+        if !target.is_finite() || target <= 0.0 {
+            return Err(format!("Goal target must be positive, got {}", target));
+        }
+
+        Ok(())
+    }
+
+    /// Compute progress from the goal's current value at `now`
+    fn progress(&self, current_value: Option<f64>, now: i64) -> GoalProgress {
+        let target_value = self.target.target_value();
+        let current = current_value.unwrap_or(0.0);
+        let direction = self.target.direction(self.start_value);
+
+        let reached = match direction {
+            GoalDirection::Increase => current >= target_value,
+            GoalDirection::Decrease => current <= target_value,
+        };
+        let fraction = match (direction, self.start_value) {
+            _ if reached => 1.0,
+            // Value goals measure the distance covered from where the user started
+            (_, Some(start)) if !self.target.is_rate() && (target_value - start) * (target_value - current) > 0.0 => {
+                (current - start) / (target_value - start)
+            }
+            (GoalDirection::Increase, _) => current / target_value,
+            (GoalDirection::Decrease, _) => target_value / current,
+        };
+        let percent_complete = if current_value.is_some() { (fraction * 100.0).clamp(0.0, 100.0) } else { 0.0 };
+
+        let elapsed = if self.target.is_rate() && now < self.deadline {
+            // Weekly levels build up over each week, so pace is measured within it
+            (now - rate_window_start(now)) as f64 / WEEK_SECONDS as f64
+        } else {
+            (now - self.created_at) as f64 / (self.deadline - self.created_at) as f64
+        };
+        let expected_percent = (elapsed * 100.0).clamp(0.0, 100.0);
+
+        let status = if percent_complete >= 100.0 && (!self.target.is_rate() || now >= self.deadline) {
+            GoalStatus::Achieved
+        } else if now >= self.deadline {
+            GoalStatus::Missed
+        } else if percent_complete >= expected_percent {
+            GoalStatus::OnTrack
+        } else {
+            GoalStatus::OffTrack
+        };
+
+        GoalProgress {
+            goal_id: self.id.clone(),
+            current_value,
+            target_value,
+            percent_complete,
+            expected_percent,
+            status,
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Add a goal, capturing its starting value from the data stored so far
+    pub fn add_goal(&self, goal: Goal) -> Result<Goal, WeightliftingError> {
+        info!("🎯 Adding goal: {} (ID: {})", goal.name, goal.id);
+        debug!("📝 Goal details: {:?}", goal);
+
+        goal.validate().map_err(|message| {
+            warn!("❌ Invalid goal: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        if let GoalTarget::Strength { ref exercise_id, .. } | GoalTarget::Volume { exercise_id: Some(ref exercise_id), .. } = goal.target {
+            Self::ensure_exercise_exists(&conn, exercise_id)?;
+        }
+
+        let start_value = match goal.start_value {
+            Some(value) => Some(value),
+            None => Self::current_goal_value(&conn, &goal, goal.created_at)?,
+        };
+        let goal = Goal { start_value, ..goal };
+
+        let target_json = serde_json::to_string(&goal.target).map_err(|e| {
+            error!("❌ Failed to serialize goal target: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize goal target: {}", e),
+            }
+        })?;
+
+        conn.execute(
+            "INSERT INTO goals (id, name, target, created_at, deadline, start_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![goal.id, goal.name, target_json, goal.created_at, goal.deadline, goal.start_value],
+        )?;
+
+        info!("✅ Successfully added goal: {}", goal.name);
+        Ok(goal)
+    }
+
+    /// Get a goal by ID
+    pub fn get_goal(&self, id: String) -> Result<Goal, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_goal(&conn, &id)
+    }
+
+    /// Get all goals, soonest deadline first
+    pub fn get_all_goals(&self) -> Result<Vec<Goal>, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, target, created_at, deadline, start_value FROM goals ORDER BY deadline, name",
+        )?;
+        let goals = stmt.query_map([], goal_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(goals)
+    }
+
+    /// Delete a goal by ID
+    /// Returns true if the goal was deleted, false if it wasn't found
+    pub fn delete_goal(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting goal with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM goals WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Evaluate progress towards a goal as of `now`
+    pub fn evaluate_goal(&self, id: String, now: i64) -> Result<GoalProgress, WeightliftingError> {
+        info!("🧮 Evaluating goal: {}", id);
+
+        let conn = self.connection()?;
+        let goal = Self::with_start_value(&conn, Self::query_goal(&conn, &id)?)?;
+        let current_value = Self::current_goal_value(&conn, &goal, now)?;
+
+        let progress = goal.progress(current_value, now);
+        info!("✅ Goal '{}' is {:.0}% complete ({:?})", goal.name, progress.percent_complete, progress.status);
+        Ok(progress)
+    }
+
+    /// Evaluate progress towards every goal as of `now`
+    pub fn evaluate_all_goals(&self, now: i64) -> Result<Vec<GoalProgress>, WeightliftingError> {
+        let goals = self.get_all_goals()?;
+        let conn = self.connection()?;
+
+        goals
+            .into_iter()
+            .map(|goal| {
+                let goal = Self::with_start_value(&conn, goal)?;
+                Ok(goal.progress(Self::current_goal_value(&conn, &goal, now)?, now))
+            })
+            .collect()
+    }
+
+    /// Fill in the start of a bodyweight goal added before any bodyweight was logged
+    ///
+    /// Without a start the goal can't tell a cut from a bulk, so the first
+    /// weigh-in after it was created stands in for it.
+    fn with_start_value(conn: &Connection, goal: Goal) -> Result<Goal, WeightliftingError> {
+        match goal.target {
+            GoalTarget::Bodyweight { .. } if goal.start_value.is_none() => {
+                let start_value = BodyMeasurementRepository::first_bodyweight_since(conn, goal.created_at)?;
+                Ok(Goal { start_value, ..goal })
+            }
+            _ => Ok(goal),
+        }
+    }
+
+    /// Measure the value a goal tracks as of `now`, None when nothing relevant is stored
+    ///
+    /// Rate goals count the calendar week up to `now`, or the 7 days ending at
+    /// the deadline once it has passed.
+    fn current_goal_value(conn: &Connection, goal: &Goal, now: i64) -> Result<Option<f64>, WeightliftingError> {
+        let (week_start, at) = if goal.target.is_rate() && now >= goal.deadline {
+            (goal.deadline - WEEK_SECONDS + 1, goal.deadline)
+        } else {
+            (rate_window_start(now), now)
+        };
+        let value = match &goal.target {
+            GoalTarget::Strength { exercise_id, .. } => {
+                Self::query_sets_between(conn, Some(exercise_id), at - STRENGTH_LOOKBACK_SECONDS, at)?
+                    .iter()
+                    .filter_map(estimate_logged_one_rm)
+                    .reduce(f64::max)
+            }
+            GoalTarget::Volume { exercise_id, .. } => {
+                let volume = Self::query_sets_between(conn, exercise_id.as_deref(), week_start, at)?
+                    .iter()
+                    .map(|set| Self::set_volume(conn, set))
+                    .sum::<Result<f64, _>>()?;
+                Some(volume)
+            }
+            GoalTarget::Frequency { .. } => {
+                let sessions: HashSet<String> = Self::query_sets_between(conn, None, week_start, at)?
+                    .iter()
+                    .map(|set| set.session_key())
+                    .collect();
+                Some(sessions.len() as f64)
+            }
+            GoalTarget::Bodyweight { .. } => BodyMeasurementRepository::bodyweight_at(conn, at)?,
+        };
+        Ok(value)
+    }
+
+    fn query_goal(conn: &Connection, id: &str) -> Result<Goal, WeightliftingError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, name, target, created_at, deadline, start_value FROM goals WHERE id = ?1",
+        )?;
+        stmt.query_row([id], goal_from_row).map_err(|e| {
+            warn!("❌ Goal not found with ID '{}': {}", id, e);
+            WeightliftingError::RecordNotFound { id: id.to_string() }
+        })
+    }
+
+    /// Create the goals table if it doesn't exist
+    pub(crate) fn create_goal_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating goals table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS goals (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                target TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                deadline INTEGER NOT NULL,
+                start_value REAL
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create goals table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Goals table ready");
+        Ok(())
+    }
+}
+
+fn goal_from_row(row: &Row) -> rusqlite::Result<Goal> {
+    let target_json: String = row.get(2)?;
+    let target = serde_json::from_str(&target_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(2, "target".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(Goal {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        target,
+        created_at: row.get(3)?,
+        deadline: row.get(4)?,
+        start_value: row.get(5)?,
+    })
+}
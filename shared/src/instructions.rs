@@ -0,0 +1,333 @@
+// Structured form guidance and media attachments for exercises
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+const MEDIA_COLUMNS: &str =
+    "id, exercise_id, kind, file_path, content_hash, mime_type, width, height, duration_seconds, caption";
+
+/// Step-by-step instructions and coaching notes for an exercise
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseInstructions {
+    pub exercise_id: String,
+    pub steps: Vec<String>, // In the order they are performed
+    pub coaching_cues: Vec<String>,
+    pub common_mistakes: Vec<String>,
+}
+
+/// The type of media attached to an exercise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+/// An image or video demonstrating an exercise
+///
+/// The media itself lives outside the database, referenced by a local
+/// file path, a content hash, or both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaAttachment {
+    pub id: String,
+    pub exercise_id: String,
+    pub kind: MediaKind,
+    pub file_path: Option<String>,
+    pub content_hash: Option<String>,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_seconds: Option<f64>, // Videos only
+    pub caption: Option<String>,
+}
+
+/// An exercise with all of its instructional content
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseDetail {
+    pub exercise: Exercise,
+    pub instructions: Option<ExerciseInstructions>,
+    pub media: Vec<MediaAttachment>,
+}
+
+impl MediaKind {
+    /// Stable name used in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Image => "image",
+            MediaKind::Video => "video",
+        }
+    }
+
+    /// Parse a database name back into a kind
+    pub fn parse(name: &str) -> Option<Self> {
+        [MediaKind::Image, MediaKind::Video].into_iter().find(|kind| kind.as_str() == name)
+    }
+}
+
+impl ExerciseInstructions {
+    /// Validate that no step, cue or mistake is blank
+    pub fn validate(&self) -> Result<(), String> {
+        for (label, items) in [("Step", &self.steps), ("Coaching cue", &self.coaching_cues), ("Common mistake", &self.common_mistakes)] {
+            if let Some(index) = items.iter().position(|item| item.trim().is_empty()) {
+                return Err(format!("{} {} cannot be empty", label, index + 1));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MediaAttachment {
+    /// Create an attachment for a local file with automatic UUID generation
+    pub fn new(exercise_id: String, kind: MediaKind, file_path: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            exercise_id,
+            kind,
+            file_path: Some(file_path),
+            content_hash: None,
+            mime_type: None,
+            width: None,
+            height: None,
+            duration_seconds: None,
+            caption: None,
+        }
+    }
+
+    /// Validate the attachment's reference and metadata
+    pub fn validate(&self) -> Result<(), String> {
+        let blank = |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+        if blank(&self.file_path) && blank(&self.content_hash) {
+            return Err("Media needs a file path or a content hash".to_string());
+        }
+
+        if let Some(ref hash) = self.content_hash {
+            if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Content hash must be hexadecimal, got '{}'", hash));
+            }
+        }
+
+        if self.width == Some(0) || self.height == Some(0) {
+            return Err("Media dimensions must be positive".to_string());
+        }
+
+        match self.duration_seconds {
+            Some(_) if self.kind == MediaKind::Image => Err("Images cannot have a duration".to_string()),
+            Some(duration) if !duration.is_finite() || duration <= 0.0 => {
+                Err(format!("Video duration must be positive, got {}", duration))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Set an exercise's instructions, replacing any existing ones
+    pub fn set_exercise_instructions(&self, instructions: ExerciseInstructions) -> Result<(), WeightliftingError> {
+        info!("📖 Setting instructions for exercise {} ({} steps)", instructions.exercise_id, instructions.steps.len());
+
+        instructions.validate().map_err(|message| {
+            warn!("❌ Invalid instructions: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &instructions.exercise_id)?;
+
+        let to_json = |items: &Vec<String>| {
+            serde_json::to_string(items).map_err(|e| {
+                error!("❌ Failed to serialize instructions: {}", e);
+                WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize instructions: {}", e),
+                }
+            })
+        };
+
+        conn.execute(
+            "INSERT INTO exercise_instructions (exercise_id, steps, coaching_cues, common_mistakes)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(exercise_id) DO UPDATE SET
+                steps = excluded.steps,
+                coaching_cues = excluded.coaching_cues,
+                common_mistakes = excluded.common_mistakes",
+            params![
+                instructions.exercise_id,
+                to_json(&instructions.steps)?,
+                to_json(&instructions.coaching_cues)?,
+                to_json(&instructions.common_mistakes)?
+            ],
+        )?;
+
+        info!("✅ Saved instructions for exercise {}", instructions.exercise_id);
+        Ok(())
+    }
+
+    /// Get an exercise's instructions, if any have been written
+    pub fn get_exercise_instructions(&self, exercise_id: String) -> Result<Option<ExerciseInstructions>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        Self::query_instructions(&conn, &exercise_id)
+    }
+
+    /// Attach an image or video to an exercise
+    pub fn add_media_attachment(&self, media: MediaAttachment) -> Result<(), WeightliftingError> {
+        info!("🖼️ Attaching {} to exercise {} (ID: {})", media.kind.as_str(), media.exercise_id, media.id);
+        debug!("📝 Media details: {:?}", media);
+
+        media.validate().map_err(|message| {
+            warn!("❌ Invalid media attachment: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &media.exercise_id)?;
+
+        conn.execute(
+            &format!("INSERT INTO exercise_media ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", MEDIA_COLUMNS),
+            params![
+                media.id,
+                media.exercise_id,
+                media.kind.as_str(),
+                media.file_path,
+                media.content_hash.as_deref().map(str::to_lowercase),
+                media.mime_type,
+                media.width,
+                media.height,
+                media.duration_seconds,
+                media.caption
+            ],
+        )?;
+
+        info!("✅ Attached media {}", media.id);
+        Ok(())
+    }
+
+    /// Get an exercise's media, in the order it was attached
+    pub fn get_media_attachments(&self, exercise_id: String) -> Result<Vec<MediaAttachment>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        Self::query_media(&conn, &exercise_id)
+    }
+
+    /// Delete a media attachment by ID
+    /// Returns true if the attachment was deleted, false if it wasn't found
+    pub fn delete_media_attachment(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting media attachment with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM exercise_media WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get an exercise together with its instructions and media
+    pub fn get_exercise_detail(&self, id: String) -> Result<ExerciseDetail, WeightliftingError> {
+        info!("📖 Looking up exercise detail for ID: {}", id);
+
+        let conn = self.connection()?;
+        let exercise = Self::query_exercise(&conn, &id)?;
+        let detail = ExerciseDetail {
+            instructions: Self::query_instructions(&conn, &id)?,
+            media: Self::query_media(&conn, &id)?,
+            exercise,
+        };
+
+        info!("✅ Found {} with {} media attachments", detail.exercise.name, detail.media.len());
+        Ok(detail)
+    }
+
+    fn query_instructions(conn: &Connection, exercise_id: &str) -> Result<Option<ExerciseInstructions>, WeightliftingError> {
+        let instructions = conn.query_row(
+            "SELECT exercise_id, steps, coaching_cues, common_mistakes FROM exercise_instructions WHERE exercise_id = ?1",
+            [exercise_id],
+            instructions_from_row,
+        ).optional()?;
+        Ok(instructions)
+    }
+
+    fn query_media(conn: &Connection, exercise_id: &str) -> Result<Vec<MediaAttachment>, WeightliftingError> {
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM exercise_media WHERE exercise_id = ?1 ORDER BY rowid", MEDIA_COLUMNS)
+        )?;
+        let media = stmt.query_map([exercise_id], media_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(media)
+    }
+
+    /// Remove an exercise's instructions and media references
+    pub(crate) fn delete_instructional_content(conn: &Connection, exercise_id: &str) -> Result<(), WeightliftingError> {
+        conn.execute("DELETE FROM exercise_instructions WHERE exercise_id = ?1", [exercise_id])?;
+        conn.execute("DELETE FROM exercise_media WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(())
+    }
+
+    /// Create the instructions and media tables if they don't exist
+    pub(crate) fn create_instruction_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating exercise_instructions and exercise_media tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS exercise_instructions (
+                exercise_id TEXT PRIMARY KEY,
+                steps TEXT NOT NULL,
+                coaching_cues TEXT NOT NULL,
+                common_mistakes TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS exercise_media (
+                id TEXT PRIMARY KEY,
+                exercise_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                file_path TEXT,
+                content_hash TEXT,
+                mime_type TEXT,
+                width INTEGER,
+                height INTEGER,
+                duration_seconds REAL,
+                caption TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_exercise_media_exercise
+                ON exercise_media (exercise_id);",
+        ).map_err(|e| {
+            error!("❌ Failed to create instruction tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Instruction tables ready");
+        Ok(())
+    }
+}
+
+fn instructions_from_row(row: &Row) -> rusqlite::Result<ExerciseInstructions> {
+    let list = |index: usize, column: &str| -> rusqlite::Result<Vec<String>> {
+        let json: String = row.get(index)?;
+        serde_json::from_str(&json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(index, column.to_string(), rusqlite::types::Type::Text))
+    };
+
+    Ok(ExerciseInstructions {
+        exercise_id: row.get(0)?,
+        steps: list(1, "steps")?,
+        coaching_cues: list(2, "coaching_cues")?,
+        common_mistakes: list(3, "common_mistakes")?,
+    })
+}
+
+fn media_from_row(row: &Row) -> rusqlite::Result<MediaAttachment> {
+    let kind_name: String = row.get(2)?;
+    let kind = MediaKind::parse(&kind_name)
+        .ok_or_else(|| rusqlite::Error::InvalidColumnType(2, "kind".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(MediaAttachment {
+        id: row.get(0)?,
+        exercise_id: row.get(1)?,
+        kind,
+        file_path: row.get(3)?,
+        content_hash: row.get(4)?,
+        mime_type: row.get(5)?,
+        width: row.get(6)?,
+        height: row.get(7)?,
+        duration_seconds: row.get(8)?,
+        caption: row.get(9)?,
+    })
+}
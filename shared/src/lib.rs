@@ -1,11 +1,82 @@
 // Main library file for weightlifting core
 // This exposes the public API and sets up UniFFI bindings
 
+// Lints triggered by code generated in the UniFFI scaffolding
+#![allow(unpredictable_function_pointer_comparisons, clippy::empty_line_after_doc_comments)]
+
 pub mod models;
 pub mod repository;
 pub mod error;
+pub mod rpe;
+pub mod progression;
+pub mod active_workout;
+pub mod stats;
+pub mod body_measurements;
+pub mod goals;
+pub mod calendar;
+pub mod schedule;
+pub mod variations;
+pub mod substitutes;
+pub mod equipment;
+pub mod muscles;
+pub mod localization;
+pub mod instructions;
+pub mod collections;
+pub mod usage;
+pub mod warmup;
+pub mod training_max;
+pub mod cardio;
+pub mod scoring;
+pub mod meets;
+pub mod standards;
+pub mod achievements;
+pub mod charts;
 
-use models::Exercise;
+use achievements::{
+    default_achievements, Achievement, AchievementListener, AchievementRule, TrainingStreak, UnlockedAchievement,
+};
+use active_workout::{
+    ActiveWorkout, ActiveWorkoutState, ExerciseGroup, ExerciseGroupKind, ExerciseGroupPlan, RestTimer,
+    WorkoutExerciseEntry, WorkoutExerciseStatus, WorkoutStatus, WorkoutSummary,
+};
+use body_measurements::{
+    BodyMeasurement, BodyMeasurementRepository, BodyMetric, Circumference, MeasurementSite, MetricTrend,
+};
+use calendar::{CalendarDate, Weekday};
+use charts::{ChartBucket, ChartMetric, ChartPoint, ChartQuery, ChartSeries, SeriesAggregation};
+use cardio::{cardio_metrics, CardioActivity, CardioActivityType, CardioMetrics, CardioTypeTotals, CardioWeekSummary};
+use collections::{ExerciseFilter, SmartCollection};
+use equipment::{equipment_id_for_name, Equipment, GymProfile};
+use goals::{Goal, GoalProgress, GoalStatus, GoalTarget};
+use instructions::{ExerciseDetail, ExerciseInstructions, MediaAttachment, MediaKind};
+use localization::{difficulty_label, locale_fallback_chain, ExerciseTranslation, LocalizedExercise};
+use meets::{
+    meet_result, AttemptPlan, AttemptStatus, AttemptStrategy, CompetitionLift, Meet, MeetAttempt, MeetResult,
+};
+use models::{DropSegment, Exercise, LoggedSet, SetType};
+use muscles::{
+    muscle_taxonomy, normalize_muscle_group, BodyRegion, MuscleGroup, MuscleGroupInfo, MuscleInvolvement, MuscleRole,
+    MuscleVolume,
+};
+use progression::{Prescription, ProgressionDecision, ProgressionRule, ProgressionScheme};
+use rpe::{estimate_one_rm, rpe_chart, rpe_percentage, LoadSuggestion, RpeChartEntry};
+use schedule::{
+    format_recurrence_rule, parse_recurrence_rule, PlannedWorkout, RecurrenceFrequency, RecurrenceRule,
+    ScheduleException, ScheduleRepository, ScheduledOccurrence,
+};
+use scoring::{
+    dots_points, ipf_gl_points, powerlifting_scores, wilks_2020_points, wilks_points, BestTotal, CompetitionEquipment,
+    CompetitionEvent, LiftRecord, LifterProfile, PowerliftingScores, Sex,
+};
+use standards::{
+    classify_strength, strength_standards, CanonicalLift, StrengthClassification, StrengthLevel, StrengthThreshold,
+};
+use stats::{downsample_series, TimeSeriesPoint};
+use substitutes::SubstituteCandidate;
+use training_max::{PercentagePrescription, ResolvedPrescription, TrainingMax};
+use usage::{ExerciseSortOrder, ExerciseUsage};
+use variations::ExerciseSummary;
+use warmup::{generate_warmup, PlateCount, WarmupScheme, WarmupSet, WarmupStep};
 use repository::ExerciseRepository;
 use error::WeightliftingError;
 use log::{info, warn};
@@ -65,27 +136,58 @@ pub fn create_in_memory_repository() -> Result<Arc<ExerciseRepository>, Weightli
     }
 }
 
-// UniFFI-generated clone function for ExerciseRepository
-// This function is automatically called by UniFFI's Swift bindings
-#[no_mangle]
-pub extern "C" fn uniffi_weightlifting_core_fn_clone_exerciserepository(
-    ptr: *const std::ffi::c_void,
-    call_status: *mut uniffi::RustCallStatus,
-) -> *const std::ffi::c_void {
-    uniffi::rust_call(unsafe { &mut *call_status }, || {
-        let obj = unsafe { Arc::from_raw(ptr as *const ExerciseRepository) };
-        let cloned = Arc::clone(&obj);
-        // Don't drop the original Arc
-        std::mem::forget(obj);
-        Ok(Arc::into_raw(cloned) as *const std::ffi::c_void)
-    })
+// UniFFI-generated clone functions for the exported interfaces
+// These functions are automatically called by UniFFI's Swift bindings
+macro_rules! uniffi_clone_fn {
+    ($name:ident, $type:ty) => {
+        #[no_mangle]
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
+        pub extern "C" fn $name(
+            ptr: *const std::ffi::c_void,
+            call_status: *mut uniffi::RustCallStatus,
+        ) -> *const std::ffi::c_void {
+            uniffi::rust_call(unsafe { &mut *call_status }, || {
+                let obj = unsafe { Arc::from_raw(ptr as *const $type) };
+                let cloned = Arc::clone(&obj);
+                // Don't drop the original Arc
+                std::mem::forget(obj);
+                Ok(Arc::into_raw(cloned) as *const std::ffi::c_void)
+            })
+        }
+    };
 }
 
+uniffi_clone_fn!(uniffi_weightlifting_core_fn_clone_exerciserepository, ExerciseRepository);
+uniffi_clone_fn!(uniffi_weightlifting_core_fn_clone_activeworkout, ActiveWorkout);
+uniffi_clone_fn!(uniffi_weightlifting_core_fn_clone_bodymeasurementrepository, BodyMeasurementRepository);
+uniffi_clone_fn!(uniffi_weightlifting_core_fn_clone_schedulerepository, ScheduleRepository);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
+    fn add_test_exercise(repo: &ExerciseRepository, id: &str, name: &str) {
+        let exercise = Exercise::new(
+            id.to_string(),
+            name.to_string(),
+            None,
+            vec!["Quadriceps".to_string()],
+            vec!["barbell".to_string()],
+            5,
+        );
+        repo.add_exercise(exercise).expect("Failed to add exercise");
+    }
+
+    fn session_sets(exercise_id: &str, session: &str, weight_kg: f64, reps: &[u32], performed_at: i64) -> Vec<LoggedSet> {
+        reps.iter()
+            .map(|&r| LoggedSet {
+                session_id: Some(session.to_string()),
+                ..LoggedSet::new(exercise_id.to_string(), weight_kg, r, performed_at)
+            })
+            .collect()
+    }
+
     #[test]
     fn test_exercise_creation() {
         initialize_logging();
@@ -96,7 +198,7 @@ mod tests {
             "Push-up".to_string(),
             Some("Basic bodyweight exercise".to_string()),
             vec!["Chest".to_string(), "Triceps".to_string()],
-            None,
+            vec![],
             5,
         );
 
@@ -121,7 +223,7 @@ mod tests {
             "Squat".to_string(),
             Some("Leg exercise".to_string()),
             vec!["Quadriceps".to_string(), "Glutes".to_string()],
-            Some("Barbell".to_string()),
+            vec!["barbell".to_string()],
             6,
         );
 
@@ -155,7 +257,7 @@ mod tests {
             "Deadlift".to_string(),
             Some("Full body compound movement".to_string()),
             vec!["Hamstrings".to_string(), "Glutes".to_string(), "Back".to_string()],
-            Some("Barbell".to_string()),
+            vec!["barbell".to_string()],
             9,
         );
 
@@ -165,4 +267,1641 @@ mod tests {
 
         debug!("✅ test_file_based_repository passed");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rpe_chart_lookup() {
+        initialize_logging();
+        debug!("🧪 Running test_rpe_chart_lookup");
+
+        assert_eq!(rpe_percentage(1, 10.0), Some(100.0));
+        assert_eq!(rpe_percentage(5, 8.0), rpe_percentage(7, 10.0));
+        assert_eq!(rpe_percentage(3, 9.5), Some(90.7));
+        assert_eq!(rpe_percentage(13, 10.0), None);
+        assert_eq!(rpe_percentage(5, 7.25), None);
+        assert_eq!(rpe_chart().len(), 12 * 9);
+
+        debug!("✅ test_rpe_chart_lookup passed");
+    }
+
+    #[test]
+    fn test_suggest_load_from_recent_sets() {
+        initialize_logging();
+        debug!("🧪 Running test_suggest_load_from_recent_sets");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let exercise = Exercise::new(
+            "squat".to_string(),
+            "Squat".to_string(),
+            None,
+            vec!["Quadriceps".to_string()],
+            vec!["barbell".to_string()],
+            6,
+        );
+        repo.add_exercise(exercise).expect("Failed to add exercise");
+
+        // No effort-rated sets yet
+        repo.log_set(LoggedSet::new("squat".to_string(), 100.0, 5, 1_000)).expect("Failed to log set");
+        assert!(matches!(
+            repo.suggest_load("squat".to_string(), 5, 8.0),
+            Err(WeightliftingError::InsufficientData { .. })
+        ));
+
+        // 100 kg x 5 @ RPE 8 and 105 kg x 3 @ RIR 2 (RPE 8) both estimate ~120-125 kg
+        repo.log_set(LoggedSet { rpe: Some(8.0), ..LoggedSet::new("squat".to_string(), 100.0, 5, 2_000) })
+            .expect("Failed to log set");
+        repo.log_set(LoggedSet { rir: Some(2), ..LoggedSet::new("squat".to_string(), 105.0, 3, 3_000) })
+            .expect("Failed to log set");
+
+        let suggestion = repo.suggest_load("squat".to_string(), 5, 8.0).expect("Failed to suggest load");
+        assert_eq!(suggestion.sets_considered, 2);
+        assert!(suggestion.estimated_one_rm_kg > 120.0 && suggestion.estimated_one_rm_kg < 125.0);
+        assert_eq!(suggestion.suggested_weight_kg % 2.5, 0.0);
+
+        repo.set_load_increment(5.0).expect("Failed to set increment");
+        let coarse = repo.suggest_load("squat".to_string(), 5, 8.0).expect("Failed to suggest load");
+        assert_eq!(coarse.suggested_weight_kg % 5.0, 0.0);
+
+        assert!(repo.log_set(LoggedSet { rpe: Some(11.0), ..LoggedSet::new("squat".to_string(), 100.0, 5, 4_000) }).is_err());
+        assert!(matches!(
+            repo.suggest_load("missing".to_string(), 5, 8.0),
+            Err(WeightliftingError::ExerciseNotFound { .. })
+        ));
+
+        debug!("✅ test_suggest_load_from_recent_sets passed");
+    }
+
+    #[test]
+    fn test_linear_progression_with_deload() {
+        initialize_logging();
+        debug!("🧪 Running test_linear_progression_with_deload");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+
+        let rule = ProgressionRule {
+            exercise_id: "squat".to_string(),
+            scheme: ProgressionScheme::Linear { sets: 3, reps: 5, increment_kg: 2.5 },
+            starting_weight_kg: 60.0,
+            deload_after_failures: 2,
+            deload_percentage: 10.0,
+        };
+        repo.set_progression_rule(rule.clone()).expect("Failed to set rule");
+        assert_eq!(repo.get_progression_rule("squat".to_string()).unwrap(), Some(rule));
+
+        let start = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(start.decision, ProgressionDecision::Start);
+        assert_eq!(start.weight_kg, 60.0);
+
+        for set in session_sets("squat", "s1", 100.0, &[5, 5, 5], 1_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Increase);
+        assert_eq!(next.weight_kg, 102.5);
+
+        for set in session_sets("squat", "s2", 102.5, &[5, 4, 3], 2_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Repeat);
+        assert_eq!(next.weight_kg, 102.5);
+
+        for set in session_sets("squat", "s3", 102.5, &[5, 5, 4], 3_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Deload);
+        assert_eq!(next.weight_kg, 92.5);
+        assert!(next.explanation.ends_with("to 92.5 kg"));
+        assert_eq!(next.sessions_evaluated, 3);
+
+        // A miss right after the deload repeats the new weight instead of deloading again
+        for set in session_sets("squat", "s4", 92.5, &[5, 5, 3], 4_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Repeat);
+        assert_eq!(next.weight_kg, 92.5);
+
+        for set in session_sets("squat", "s5", 92.5, &[5, 4, 4], 5_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("squat".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Deload);
+        assert_eq!(next.weight_kg, 82.5);
+
+        debug!("✅ test_linear_progression_with_deload passed");
+    }
+
+    #[test]
+    fn test_double_progression() {
+        initialize_logging();
+        debug!("🧪 Running test_double_progression");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "row", "Dumbbell Row");
+
+        repo.set_progression_rule(ProgressionRule {
+            exercise_id: "row".to_string(),
+            scheme: ProgressionScheme::DoubleProgression { sets: 3, min_reps: 8, max_reps: 12, increment_kg: 2.0 },
+            starting_weight_kg: 20.0,
+            deload_after_failures: 0,
+            deload_percentage: 0.0,
+        }).expect("Failed to set rule");
+
+        for set in session_sets("row", "s1", 30.0, &[10, 9, 9], 1_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("row".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Repeat);
+        assert_eq!((next.weight_kg, next.min_reps, next.max_reps), (30.0, 10, 12));
+
+        for set in session_sets("row", "s2", 30.0, &[12, 12, 12], 2_000) {
+            repo.log_set(set).expect("Failed to log set");
+        }
+        let next = repo.evaluate_progression("row".to_string()).expect("Failed to evaluate");
+        assert_eq!(next.decision, ProgressionDecision::Increase);
+        assert_eq!((next.weight_kg, next.min_reps), (32.0, 8));
+
+        // Deleting the exercise takes its rule with it
+        assert!(repo.delete_exercise("row".to_string()).unwrap());
+        assert!(!repo.delete_progression_rule("row".to_string()).unwrap());
+
+        debug!("✅ test_double_progression passed");
+    }
+
+    #[test]
+    fn test_active_workout_lifecycle() {
+        initialize_logging();
+        debug!("🧪 Running test_active_workout_lifecycle");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        add_test_exercise(&repo, "bench", "Bench Press");
+        add_test_exercise(&repo, "row", "Row");
+
+        let workout = repo
+            .start_workout(vec!["squat".to_string(), "bench".to_string()], 1_000)
+            .expect("Failed to start workout");
+        assert!(matches!(
+            repo.start_workout(vec![], 1_000),
+            Err(WeightliftingError::InvalidState { .. })
+        ));
+        assert_eq!(workout.state().current_index, Some(0));
+
+        workout.log_set(LoggedSet::new("squat".to_string(), 100.0, 5, 1_100), 120).expect("Failed to log set");
+        assert_eq!(workout.rest_remaining_seconds(1_160), Some(60));
+        workout.complete_exercise(1_300).expect("Failed to complete exercise");
+
+        workout.pause(1_400).expect("Failed to pause");
+        assert!(workout.log_set(LoggedSet::new("bench".to_string(), 60.0, 8, 1_450), 0).is_err());
+        workout.resume(1_600).expect("Failed to resume");
+        assert_eq!(workout.elapsed_seconds(1_600), 400);
+
+        workout.add_exercise("row".to_string(), 1_650).expect("Failed to add exercise");
+        workout.move_exercise(2, 1, 1_660).expect("Failed to reorder");
+        let state = workout.skip_exercise(1_670).expect("Failed to skip");
+        assert_eq!(state.exercises[1].exercise_id, "row");
+        assert_eq!(state.exercises[1].status, WorkoutExerciseStatus::InProgress);
+        assert_eq!(state.exercises[2].exercise_id, "bench");
+        assert_eq!(state.exercises[2].status, WorkoutExerciseStatus::Skipped);
+
+        // Simulate a crash: the draft is restored from SQLite
+        drop(workout);
+        let workout = repo.resume_workout().expect("Failed to resume").expect("Draft missing");
+        assert_eq!(workout.state().sets.len(), 1);
+
+        let stale = repo.resume_workout().expect("Failed to resume").expect("Draft missing");
+
+        workout.log_set(LoggedSet::new("bench".to_string(), 60.0, 8, 1_700), 0).expect("Failed to log set");
+        let summary = workout.finish(1_800).expect("Failed to finish");
+        assert_eq!(summary.set_count, 2);
+        assert_eq!(summary.total_volume_kg, 980.0);
+        assert_eq!(summary.duration_seconds, 600);
+        assert!(workout.pause(1_900).is_err());
+
+        assert!(repo.resume_workout().expect("Failed to check draft").is_none());
+        let logged = repo.get_recent_sets("squat".to_string(), 10).expect("Failed to get sets");
+        assert_eq!(logged[0].session_id, Some(summary.session_id));
+
+        // A second handle can't bring the finished workout's draft back
+        assert!(matches!(
+            stale.log_set(LoggedSet::new("bench".to_string(), 60.0, 8, 1_850), 0),
+            Err(WeightliftingError::InvalidState { .. })
+        ));
+        assert!(repo.resume_workout().expect("Failed to check draft").is_none());
+        repo.start_workout(vec!["squat".to_string()], 2_000).expect("Failed to start next workout");
+
+        debug!("✅ test_active_workout_lifecycle passed");
+    }
+
+    #[test]
+    fn test_body_measurement_trends() {
+        initialize_logging();
+        debug!("🧪 Running test_body_measurement_trends");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let body = repo.body_measurements();
+        let day = 86_400;
+
+        // Bodyweight falls 0.5 kg per week, waist measured twice
+        for week in 0..5 {
+            let mut measurement = BodyMeasurement::new(week * 7 * day);
+            measurement.bodyweight_kg = Some(90.0 - 0.5 * week as f64);
+            if week % 4 == 0 {
+                measurement.circumferences.push(Circumference { site: MeasurementSite::Waist, value_cm: 90.0 - week as f64 });
+            }
+            body.add_measurement(measurement).expect("Failed to add measurement");
+        }
+
+        let latest = body.get_latest_measurement().expect("Failed to get latest").expect("No measurement");
+        assert_eq!(latest.bodyweight_kg, Some(88.0));
+        assert_eq!(body.get_measurement(latest.id.clone()).expect("Failed to get").circumferences[0].value_cm, 86.0);
+        assert_eq!(body.get_measurements_between(day, 15 * day).expect("Failed to query").len(), 2);
+
+        let trend = body.get_metric_trend(BodyMetric::Bodyweight, 0, 100 * day, 2).expect("Failed to get trend");
+        assert_eq!(trend.points.len(), 5);
+        assert_eq!(trend.smoothed[1].value, 89.75);
+        assert!((trend.slope_per_week.unwrap() + 0.5).abs() < 1e-9);
+
+        let waist = body
+            .get_metric_series(BodyMetric::Circumference { site: MeasurementSite::Waist }, 0, 100 * day)
+            .expect("Failed to get series");
+        assert_eq!(waist.len(), 2);
+
+        assert!(body.add_measurement(BodyMeasurement::new(0)).is_err());
+        assert!(matches!(
+            body.get_measurement("missing".to_string()),
+            Err(WeightliftingError::RecordNotFound { .. })
+        ));
+
+        debug!("✅ test_body_measurement_trends passed");
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        initialize_logging();
+        debug!("🧪 Running test_goal_progress");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "bench", "Bench Press");
+        let day = 86_400;
+
+        // 1 rep at RPE 10 estimates a 1RM equal to the weight lifted
+        repo.log_set(LoggedSet::new("bench".to_string(), 80.0, 1, day)).expect("Failed to log set");
+
+        let strength = repo.add_goal(Goal::new(
+            "Bench 100".to_string(),
+            GoalTarget::Strength { exercise_id: "bench".to_string(), target_one_rm_kg: 100.0 },
+            2 * day,
+            102 * day,
+        )).expect("Failed to add goal");
+        assert_eq!(strength.start_value, Some(80.0));
+
+        repo.log_set(LoggedSet::new("bench".to_string(), 90.0, 1, 20 * day)).expect("Failed to log set");
+        let progress = repo.evaluate_goal(strength.id.clone(), 22 * day).expect("Failed to evaluate");
+        assert_eq!(progress.current_value, Some(90.0));
+        assert_eq!(progress.percent_complete, 50.0);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+
+        // Sets fall out of the 4 week window and the deadline passes
+        let progress = repo.evaluate_goal(strength.id.clone(), 200 * day).expect("Failed to evaluate");
+        assert_eq!(progress.status, GoalStatus::Missed);
+
+        let frequency = repo.add_goal(Goal::new(
+            "Train 2x/week".to_string(),
+            GoalTarget::Frequency { sessions_per_week: 2 },
+            0,
+            100 * day,
+        )).expect("Failed to add goal");
+        // Thursday: one session so far is ahead of the 3/7 of a week gone
+        let progress = repo.evaluate_goal(frequency.id.clone(), 21 * day).expect("Failed to evaluate");
+        assert_eq!(progress.percent_complete, 50.0);
+        assert!((progress.expected_percent - 300.0 / 7.0).abs() < 1e-9);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+        // Sunday night of the next week with nothing logged
+        assert_eq!(repo.evaluate_goal(frequency.id.clone(), 32 * day - 1).unwrap().status, GoalStatus::OffTrack);
+
+        // A strength goal already met when it's created
+        let met = repo.add_goal(Goal::new(
+            "Bench 85".to_string(),
+            GoalTarget::Strength { exercise_id: "bench".to_string(), target_one_rm_kg: 85.0 },
+            21 * day,
+            50 * day,
+        )).expect("Failed to add goal");
+        assert_eq!(met.start_value, Some(90.0));
+        assert_eq!(repo.evaluate_goal(met.id.clone(), 21 * day).unwrap().status, GoalStatus::Achieved);
+        assert!(repo.delete_goal(met.id).unwrap());
+
+        let body = repo.body_measurements();
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(90.0), ..BodyMeasurement::new(0) }).unwrap();
+        let cut = repo.add_goal(Goal::new(
+            "Cut to 85".to_string(),
+            GoalTarget::Bodyweight { target_kg: 85.0 },
+            day,
+            11 * day,
+        )).expect("Failed to add goal");
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(84.8), ..BodyMeasurement::new(5 * day) }).unwrap();
+        assert_eq!(repo.evaluate_goal(cut.id, 6 * day).unwrap().status, GoalStatus::Achieved);
+
+        assert_eq!(repo.evaluate_all_goals(6 * day).unwrap().len(), 3);
+        assert!(repo.delete_goal(frequency.id).unwrap());
+
+        // A cut added before any bodyweight is logged goes down from the first weigh-in
+        let fresh = create_in_memory_repository().expect("Failed to create repository");
+        let cut = fresh.add_goal(Goal::new(
+            "Cut to 80".to_string(),
+            GoalTarget::Bodyweight { target_kg: 80.0 },
+            0,
+            30 * day,
+        )).expect("Failed to add goal");
+        assert_eq!(cut.start_value, None);
+        let body = fresh.body_measurements();
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(85.0), ..BodyMeasurement::new(day) }).unwrap();
+        let progress = fresh.evaluate_goal(cut.id.clone(), 2 * day).unwrap();
+        assert_eq!(progress.percent_complete, 0.0);
+        assert_eq!(progress.status, GoalStatus::OffTrack);
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(82.5), ..BodyMeasurement::new(10 * day) }).unwrap();
+        let progress = fresh.evaluate_goal(cut.id, 10 * day).unwrap();
+        assert!((progress.percent_complete - 50.0).abs() < 1e-9);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+
+        debug!("✅ test_goal_progress passed");
+    }
+
+    #[test]
+    fn test_calendar_dates() {
+        initialize_logging();
+        debug!("🧪 Running test_calendar_dates");
+
+        let date = CalendarDate::new(2024, 2, 29).expect("Leap day should exist");
+        assert_eq!(CalendarDate::from_days(date.to_days()), date);
+        assert_eq!(date.weekday(), Weekday::Thursday);
+        assert_eq!(date.add_days(1), CalendarDate::new(2024, 3, 1).unwrap());
+        assert_eq!(date.add_months(12), None);
+        assert_eq!(CalendarDate::from_timestamp(0), CalendarDate::new(1970, 1, 1).unwrap());
+        assert_eq!(CalendarDate::new(2023, 2, 29), None);
+        assert_eq!(CalendarDate::parse_basic("20260601"), CalendarDate::new(2026, 6, 1));
+
+        debug!("✅ test_calendar_dates passed");
+    }
+
+    #[test]
+    fn test_recurring_schedule() {
+        initialize_logging();
+        debug!("🧪 Running test_recurring_schedule");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        let schedule = repo.schedule();
+        let date = |day| CalendarDate::new(2026, 6, day).unwrap();
+
+        let rule = parse_recurrence_rule("RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=6".to_string())
+            .expect("Failed to parse rule");
+        assert_eq!(format_recurrence_rule(rule.clone()), "FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=6");
+        assert!(parse_recurrence_rule("FREQ=YEARLY".to_string()).is_err());
+
+        // 2026-06-03 is a Wednesday
+        let workout = PlannedWorkout::new("Full Body".to_string(), vec!["squat".to_string()], date(3), Some(rule));
+        schedule.add_planned_workout(workout.clone()).expect("Failed to add planned workout");
+
+        let dates = |occurrences: Vec<ScheduledOccurrence>| occurrences.iter().map(|o| o.date.day).collect::<Vec<_>>();
+        let occurrences = schedule.get_occurrences_between(date(1), date(30)).expect("Failed to expand");
+        assert_eq!(dates(occurrences), vec![3, 5, 8, 10, 12, 15]);
+
+        schedule.cancel_occurrence(workout.id.clone(), date(5)).expect("Failed to cancel");
+        schedule.reschedule_occurrence(workout.id.clone(), date(15), date(16)).expect("Failed to reschedule");
+        assert!(schedule.cancel_occurrence(workout.id.clone(), date(6)).is_err());
+
+        let occurrences = schedule.get_occurrences_between(date(9), date(16)).expect("Failed to expand");
+        assert_eq!(dates(occurrences.clone()), vec![10, 12, 16]);
+        assert!(occurrences[2].is_rescheduled);
+        assert_eq!(occurrences[2].original_date, date(15));
+
+        assert!(schedule.restore_occurrence(workout.id.clone(), date(5)).unwrap());
+        let stored = schedule.get_planned_workout(workout.id.clone()).expect("Failed to get planned workout");
+        assert_eq!(stored.exceptions.len(), 1);
+
+        let monthly = PlannedWorkout::new(
+            "Test day".to_string(),
+            vec![],
+            CalendarDate::new(2026, 1, 31).unwrap(),
+            Some(parse_recurrence_rule("FREQ=MONTHLY".to_string()).unwrap()),
+        );
+        schedule.add_planned_workout(monthly).expect("Failed to add planned workout");
+        let occurrences = schedule
+            .get_occurrences_between(CalendarDate::new(2026, 1, 1).unwrap(), CalendarDate::new(2026, 5, 31).unwrap())
+            .expect("Failed to expand");
+        let test_days: Vec<_> = occurrences.iter().filter(|o| o.name == "Test day").map(|o| o.date.month).collect();
+        assert_eq!(test_days, vec![1, 3, 5]);
+
+        debug!("✅ test_recurring_schedule passed");
+    }
+
+    #[test]
+    fn test_exercise_variations() {
+        initialize_logging();
+        debug!("🧪 Running test_exercise_variations");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        for (id, name) in [("squat", "Back Squat"), ("pause", "Pause Squat"), ("tempo-pause", "Tempo Pause Squat"), ("front", "Front Squat")] {
+            add_test_exercise(&repo, id, name);
+        }
+        repo.set_exercise_parent("pause".to_string(), Some("squat".to_string())).unwrap();
+        repo.set_exercise_parent("front".to_string(), Some("squat".to_string())).unwrap();
+        repo.set_exercise_parent("tempo-pause".to_string(), Some("pause".to_string())).unwrap();
+
+        let names = |exercises: Vec<Exercise>| exercises.into_iter().map(|e| e.name).collect::<Vec<_>>();
+        assert_eq!(names(repo.get_variations("squat".to_string(), false).unwrap()), vec!["Front Squat", "Pause Squat"]);
+        assert_eq!(
+            names(repo.get_variations("squat".to_string(), true).unwrap()),
+            vec!["Front Squat", "Pause Squat", "Tempo Pause Squat"]
+        );
+        assert_eq!(repo.get_base_exercise("tempo-pause".to_string()).unwrap().id, "squat");
+
+        // Cycles are rejected, including self-parenting
+        for (child, parent) in [("squat", "tempo-pause"), ("pause", "pause")] {
+            assert!(matches!(
+                repo.set_exercise_parent(child.to_string(), Some(parent.to_string())),
+                Err(WeightliftingError::InvalidInput { .. })
+            ));
+        }
+
+        // Analytics roll up from the whole family
+        for set in session_sets("squat", "a", 100.0, &[5, 5], 1_000)
+            .into_iter()
+            .chain(session_sets("tempo-pause", "b", 80.0, &[3], 2_000))
+        {
+            repo.log_set(set).unwrap();
+        }
+        let own = repo.get_exercise_summary("squat".to_string(), false).unwrap();
+        assert_eq!((own.set_count, own.total_reps, own.total_volume_kg), (2, 10, 1_000.0));
+        let rolled_up = repo.get_exercise_summary("squat".to_string(), true).unwrap();
+        assert_eq!((rolled_up.set_count, rolled_up.total_reps, rolled_up.total_volume_kg), (3, 13, 1_240.0));
+        assert_eq!(rolled_up.last_performed_at, Some(2_000));
+
+        // Deleting a variation moves its own variations up to its parent
+        repo.delete_exercise("pause".to_string()).unwrap();
+        assert_eq!(repo.get_exercise_parent("tempo-pause".to_string()).unwrap().unwrap().id, "squat");
+
+        debug!("✅ test_exercise_variations passed");
+    }
+
+    #[test]
+    fn test_suggest_substitutes() {
+        initialize_logging();
+        debug!("🧪 Running test_suggest_substitutes");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let exercises = [
+            ("squat", "Back Squat", vec!["Quadriceps", "Glutes"], vec!["barbell", "squat-rack"], 6),
+            ("leg-press", "Leg Press", vec!["Quadriceps", "Glutes"], vec!["leg-press-machine"], 4),
+            ("goblet", "Goblet Squat", vec!["quadriceps", "glutes"], vec!["dumbbell"], 4),
+            ("lunge", "Walking Lunge", vec!["Quadriceps"], vec![], 5),
+            ("bench", "Bench Press", vec!["Chest"], vec!["barbell", "bench"], 6),
+        ];
+        for (id, name, muscles, equipment, difficulty) in exercises {
+            let muscles = muscles.into_iter().map(String::from).collect();
+            let equipment = equipment.into_iter().map(String::from).collect();
+            let exercise = Exercise::new(id.to_string(), name.to_string(), None, muscles, equipment, difficulty);
+            repo.add_exercise(exercise).unwrap();
+        }
+
+        let candidates = repo
+            .suggest_substitutes("squat".to_string(), vec!["dumbbell".to_string()], 10)
+            .unwrap();
+        let ids: Vec<_> = candidates.iter().map(|c| c.exercise.id.as_str()).collect();
+        // Bench shares no muscles; the unavailable leg press still outranks a partial muscle match
+        assert_eq!(ids, vec!["goblet", "leg-press", "lunge"]);
+
+        let goblet = &candidates[0];
+        assert_eq!((goblet.muscle_overlap_score, goblet.equipment_score), (1.0, 1.0));
+        assert!((goblet.difficulty_score - 7.0 / 9.0).abs() < 1e-9);
+        assert_eq!(candidates[1].equipment_score, 0.0);
+
+        assert_eq!(repo.suggest_substitutes("squat".to_string(), vec![], 1).unwrap().len(), 1);
+
+        debug!("✅ test_suggest_substitutes passed");
+    }
+
+    #[test]
+    fn test_gym_profiles() {
+        initialize_logging();
+        debug!("🧪 Running test_gym_profiles");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        assert_eq!(equipment_id_for_name("Pull-up Bar"), "pull-up-bar");
+        repo.add_equipment(Equipment::new("Suspension Trainer".to_string())).unwrap();
+        assert!(repo.add_equipment(Equipment { id: "Bad ID".to_string(), name: "Bad".to_string() }).is_err());
+
+        let exercises = [
+            ("squat", "Back Squat", vec!["barbell", "squat-rack"]),
+            ("push-up", "Push-up", vec![]),
+            ("row", "Inverted Row", vec!["suspension-trainer"]),
+            ("curl", "Dumbbell Curl", vec!["dumbbell"]),
+        ];
+        for (id, name, equipment) in exercises {
+            let equipment = equipment.into_iter().map(String::from).collect();
+            let exercise = Exercise::new(id.to_string(), name.to_string(), None, vec!["Back".to_string()], equipment, 5);
+            repo.add_exercise(exercise).unwrap();
+        }
+
+        // Unknown equipment is rejected rather than stored as free text
+        let unknown = Exercise::new_with_uuid("Sled Push".to_string(), None, vec!["Quadriceps".to_string()], vec!["sled".to_string()], 5);
+        assert!(matches!(repo.add_exercise(unknown), Err(WeightliftingError::InvalidInput { .. })));
+
+        let hotel = GymProfile::new("Hotel Gym".to_string(), vec!["dumbbell".to_string(), "suspension-trainer".to_string()]);
+        repo.save_gym_profile(hotel.clone()).unwrap();
+        let names: Vec<_> = repo.get_exercises_for_gym(hotel.id.clone()).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["Dumbbell Curl", "Inverted Row", "Push-up"]);
+
+        // Saving again updates the inventory
+        repo.save_gym_profile(GymProfile { equipment_ids: vec!["barbell".to_string(), "squat-rack".to_string()], ..hotel.clone() }).unwrap();
+        let ids: Vec<_> = repo.get_exercises_for_gym(hotel.id.clone()).unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["squat", "push-up"]);
+        assert_eq!(repo.get_all_gym_profiles().unwrap().len(), 1);
+
+        debug!("✅ test_gym_profiles passed");
+    }
+
+    #[test]
+    fn test_free_text_equipment_migration() {
+        initialize_logging();
+        debug!("🧪 Running test_free_text_equipment_migration");
+
+        let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let db_path = temp_file.path().to_str().unwrap().to_string();
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE exercises (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    muscle_groups TEXT NOT NULL,
+                    equipment_needed TEXT,
+                    difficulty_level INTEGER NOT NULL
+                );
+                INSERT INTO exercises VALUES ('dl', 'Deadlift', NULL, '[\"Back\"]', 'Barbell', 8);
+                INSERT INTO exercises VALUES ('hip', 'Hip Thrust', NULL, '[\"Glutes\"]', 'Barbell, Hip Thrust Pad', 5);
+                INSERT INTO exercises VALUES ('plank', 'Plank', NULL, '[\"Core\"]', NULL, 2);
+                INSERT INTO exercises VALUES ('pushup', 'Push-up', NULL, '[\"Chest\"]', 'Bodyweight', 2);
+                INSERT INTO exercises VALUES ('dip', 'Dip', NULL, '[\"Chest\"]', 'None / Dip Station', 4);
+                INSERT INTO exercises VALUES ('crunch', 'Crunch', NULL, '[\"Core\"]', 'N/A', 1);",
+            ).unwrap();
+        }
+
+        let repo = create_exercise_repository(db_path).expect("Failed to open legacy database");
+        assert_eq!(repo.get_exercise("dl".to_string()).unwrap().equipment_ids, vec!["barbell"]);
+        assert_eq!(repo.get_exercise("hip".to_string()).unwrap().equipment_ids, vec!["barbell", "hip-thrust-pad"]);
+        assert!(!repo.get_exercise("plank".to_string()).unwrap().requires_equipment());
+        assert!(!repo.get_exercise("pushup".to_string()).unwrap().requires_equipment());
+        assert_eq!(repo.get_exercise("dip".to_string()).unwrap().equipment_ids, vec!["dip-station"]);
+        assert!(!repo.get_exercise("crunch".to_string()).unwrap().requires_equipment());
+        assert!(!repo.get_all_equipment().unwrap().iter().any(|e| ["bodyweight", "none", "n", "a"].contains(&e.id.as_str())));
+        assert!(repo.get_all_equipment().unwrap().iter().any(|e| e.id == "hip-thrust-pad" && e.name == "Hip Thrust Pad"));
+
+        debug!("✅ test_free_text_equipment_migration passed");
+    }
+
+    #[test]
+    fn test_muscle_group_normalization() {
+        initialize_logging();
+        debug!("🧪 Running test_muscle_group_normalization");
+
+        assert_eq!(normalize_muscle_group("Quads"), Some(MuscleGroup::Quadriceps));
+        assert_eq!(normalize_muscle_group(" quadriceps"), Some(MuscleGroup::Quadriceps));
+        assert_eq!(normalize_muscle_group("upper-back"), Some(MuscleGroup::UpperBack));
+        assert_eq!(normalize_muscle_group("Elbows"), None);
+        assert_eq!(muscle_taxonomy().len(), MuscleGroup::ALL.len());
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let squat = Exercise::new_with_uuid(
+            "Squat".to_string(),
+            None,
+            vec!["Quads".to_string(), "quadriceps".to_string(), "glute".to_string()],
+            vec![],
+            6,
+        );
+        repo.add_exercise(squat.clone()).unwrap();
+        assert_eq!(repo.get_exercise(squat.id).unwrap().muscle_groups, vec!["Quadriceps", "Glutes"]);
+
+        let unknown = Exercise::new_with_uuid("Mystery".to_string(), None, vec!["Elbows".to_string()], vec![], 3);
+        assert!(matches!(repo.add_exercise(unknown), Err(WeightliftingError::InvalidInput { .. })));
+
+        let legs: Vec<_> = repo.get_exercises_for_region(BodyRegion::Legs).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(legs, vec!["Squat"]);
+        assert!(repo.get_exercises_for_region(BodyRegion::Arms).unwrap().is_empty());
+
+        // Existing rows are rewritten once when an older database is opened
+        let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let db_path = temp_file.path().to_str().unwrap().to_string();
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE exercises (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    muscle_groups TEXT NOT NULL,
+                    equipment_ids TEXT NOT NULL DEFAULT '[]',
+                    difficulty_level INTEGER NOT NULL
+                );
+                INSERT INTO exercises (id, name, muscle_groups, difficulty_level)
+                    VALUES ('row', 'Row', '[\"lats\", \"Lat\", \"Rear Delts\"]', 5);",
+            ).unwrap();
+        }
+        let repo = create_exercise_repository(db_path.clone()).expect("Failed to open legacy database");
+        assert_eq!(repo.get_exercise("row".to_string()).unwrap().muscle_groups, vec!["Lats", "Shoulders"]);
+
+        debug!("✅ test_muscle_group_normalization passed");
+    }
+
+    #[test]
+    fn test_muscle_involvement_weights() {
+        initialize_logging();
+        debug!("🧪 Running test_muscle_involvement_weights");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let bench = Exercise::new(
+            "bench".to_string(),
+            "Bench Press".to_string(),
+            None,
+            vec!["Chest".to_string()],
+            vec!["barbell".to_string(), "bench".to_string()],
+            6,
+        )
+        .with_muscle_involvement(vec![
+            MuscleInvolvement::new(MuscleGroup::Chest, MuscleRole::Primary),
+            MuscleInvolvement::new(MuscleGroup::Triceps, MuscleRole::Secondary),
+            MuscleInvolvement { weight: Some(0.3), ..MuscleInvolvement::new(MuscleGroup::Shoulders, MuscleRole::Secondary) },
+        ]);
+        repo.add_exercise(bench).unwrap();
+        // A plain string list still works, with every group counted fully
+        add_test_exercise(&repo, "squat", "Squat");
+
+        let stored = repo.get_exercise("bench".to_string()).unwrap();
+        assert_eq!(stored.muscle_groups, vec!["Chest", "Triceps", "Shoulders"]);
+        assert_eq!(stored.muscle_involvement.len(), 3);
+
+        let invalid = Exercise::new_with_uuid("Dip".to_string(), None, vec!["Chest".to_string()], vec![], 5)
+            .with_muscle_involvement(vec![MuscleInvolvement { weight: Some(1.5), ..MuscleInvolvement::new(MuscleGroup::Triceps, MuscleRole::Primary) }]);
+        assert!(matches!(repo.add_exercise(invalid), Err(WeightliftingError::InvalidInput { .. })));
+
+        for set in session_sets("bench", "a", 100.0, &[5, 5], 1_000).into_iter().chain(session_sets("squat", "a", 140.0, &[5], 1_000)) {
+            repo.log_set(set).unwrap();
+        }
+        let volume = repo.get_muscle_volume(0, 2_000).unwrap();
+        let credited: Vec<_> = volume.iter().map(|v| (v.muscle_group.as_str(), v.weighted_sets)).collect();
+        assert_eq!(credited, vec![("Chest", 2.0), ("Quadriceps", 1.0), ("Triceps", 1.0), ("Shoulders", 0.6)]);
+        assert!((volume[3].weighted_volume_kg - 300.0).abs() < 1e-9);
+
+        debug!("✅ test_muscle_involvement_weights passed");
+    }
+
+    #[test]
+    fn test_localized_exercises() {
+        initialize_logging();
+        debug!("🧪 Running test_localized_exercises");
+
+        assert_eq!(locale_fallback_chain("de_AT".to_string()), vec!["de-at", "de"]);
+        assert_eq!(difficulty_label(9, "es-MX".to_string()), "Muy difícil");
+        assert_eq!(difficulty_label(5, "fr".to_string()), "Moderate");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        add_test_exercise(&repo, "lunge", "Lunge");
+        let translate = |id: &str, locale: &str, name: &str| {
+            repo.set_exercise_translation(ExerciseTranslation {
+                exercise_id: id.to_string(),
+                locale: locale.to_string(),
+                name: name.to_string(),
+                description: None,
+            })
+            .unwrap();
+        };
+        translate("squat", "de", "Kniebeuge");
+        translate("squat", "de-AT", "Hocke");
+        translate("squat", "es", "Sentadilla");
+        translate("lunge", "de", "Ausfallschritt");
+
+        let austrian = repo.get_localized_exercise("squat".to_string(), "de-AT".to_string()).unwrap();
+        assert_eq!((austrian.exercise.name.as_str(), austrian.resolved_locale.as_deref()), ("Hocke", Some("de-at")));
+        assert_eq!(austrian.difficulty_label, "Mittel");
+        let swiss = repo.get_localized_exercise("squat".to_string(), "de-CH".to_string()).unwrap();
+        assert_eq!(swiss.exercise.name, "Kniebeuge");
+        let french = repo.get_localized_exercise("squat".to_string(), "fr".to_string()).unwrap();
+        assert_eq!((french.exercise.name.as_str(), french.resolved_locale), ("Squat", None));
+
+        let german: Vec<_> = repo.get_all_localized_exercises("de".to_string()).unwrap().into_iter().map(|l| l.exercise.name).collect();
+        assert_eq!(german, vec!["Ausfallschritt", "Kniebeuge"]);
+
+        // Search matches names in any language and answers in the requested one
+        let found = repo.search_exercises("sentad".to_string(), "de".to_string()).unwrap();
+        assert_eq!(found.iter().map(|l| l.exercise.name.as_str()).collect::<Vec<_>>(), vec!["Kniebeuge"]);
+        assert_eq!(repo.search_exercises("LUNGE".to_string(), "en".to_string()).unwrap().len(), 1);
+
+        repo.delete_exercise("squat".to_string()).unwrap();
+        assert_eq!(repo.search_exercises("kniebeuge".to_string(), "de".to_string()).unwrap().len(), 0);
+
+        debug!("✅ test_localized_exercises passed");
+    }
+
+    #[test]
+    fn test_exercise_instructions_and_media() {
+        initialize_logging();
+        debug!("🧪 Running test_exercise_instructions_and_media");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+
+        let detail = repo.get_exercise_detail("squat".to_string()).unwrap();
+        assert_eq!((detail.instructions, detail.media.len()), (None, 0));
+
+        let instructions = ExerciseInstructions {
+            exercise_id: "squat".to_string(),
+            steps: vec!["Unrack the bar".to_string(), "Sit down between your heels".to_string(), "Drive up".to_string()],
+            coaching_cues: vec!["Knees out".to_string()],
+            common_mistakes: vec!["Heels lifting".to_string()],
+        };
+        repo.set_exercise_instructions(instructions.clone()).unwrap();
+        assert!(repo.set_exercise_instructions(ExerciseInstructions { steps: vec![" ".to_string()], ..instructions.clone() }).is_err());
+
+        let photo = MediaAttachment {
+            width: Some(1080),
+            height: Some(1920),
+            ..MediaAttachment::new("squat".to_string(), MediaKind::Image, "media/squat-bottom.jpg".to_string())
+        };
+        let video = MediaAttachment {
+            file_path: None,
+            content_hash: Some("9F86D081884C7D65".to_string()),
+            duration_seconds: Some(12.5),
+            ..MediaAttachment::new("squat".to_string(), MediaKind::Video, String::new())
+        };
+        repo.add_media_attachment(photo.clone()).unwrap();
+        repo.add_media_attachment(video.clone()).unwrap();
+
+        // Media must point at something, and images have no duration
+        let dangling = MediaAttachment { file_path: None, ..MediaAttachment::new("squat".to_string(), MediaKind::Image, String::new()) };
+        assert!(matches!(repo.add_media_attachment(dangling), Err(WeightliftingError::InvalidInput { .. })));
+        let still_video = MediaAttachment { duration_seconds: Some(3.0), ..photo.clone() };
+        assert!(repo.add_media_attachment(still_video).is_err());
+
+        let detail = repo.get_exercise_detail("squat".to_string()).unwrap();
+        assert_eq!(detail.instructions, Some(instructions));
+        assert_eq!(detail.media.iter().map(|m| m.kind).collect::<Vec<_>>(), vec![MediaKind::Image, MediaKind::Video]);
+        assert_eq!(detail.media[1].content_hash.as_deref(), Some("9f86d081884c7d65"));
+
+        assert!(repo.delete_media_attachment(photo.id).unwrap());
+        repo.delete_exercise("squat".to_string()).unwrap();
+        add_test_exercise(&repo, "squat", "Squat");
+        assert_eq!(repo.get_exercise_detail("squat".to_string()).unwrap().media.len(), 0);
+
+        debug!("✅ test_exercise_instructions_and_media passed");
+    }
+
+    #[test]
+    fn test_tags_favorites_and_smart_collections() {
+        initialize_logging();
+        debug!("🧪 Running test_tags_favorites_and_smart_collections");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        let push_up = Exercise {
+            tags: vec!["Home".to_string(), " home ".to_string(), "".to_string()],
+            ..Exercise::new("push-up".to_string(), "Push-up".to_string(), None, vec!["Chest".to_string()], vec![], 3)
+        };
+        repo.add_exercise(push_up).unwrap();
+        add_test_exercise(&repo, "squat", "Squat");
+        let goblet = Exercise::new("goblet".to_string(), "Goblet Squat".to_string(), None, vec!["Quads".to_string()], vec!["dumbbell".to_string()], 4);
+        repo.add_exercise(goblet).unwrap();
+
+        assert_eq!(repo.get_exercise("push-up".to_string()).unwrap().tags, vec!["Home"]);
+        repo.add_exercise_tag("goblet".to_string(), "home".to_string()).unwrap();
+        repo.add_exercise_tag("squat".to_string(), "Competition".to_string()).unwrap();
+        assert_eq!(repo.get_all_tags().unwrap(), vec!["Competition", "Home"]);
+        repo.set_exercise_favorite("goblet".to_string(), true).unwrap();
+        assert!(repo.get_exercise("goblet".to_string()).unwrap().is_favorite);
+
+        // A saved collection re-evaluates as exercises change
+        let home_legs = SmartCollection::new(
+            "Home legs".to_string(),
+            ExerciseFilter {
+                tags: vec!["HOME".to_string()],
+                body_regions: vec![BodyRegion::Legs],
+                ..Default::default()
+            },
+        );
+        repo.save_smart_collection(home_legs.clone()).unwrap();
+        let ids = |exercises: Vec<Exercise>| exercises.into_iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(repo.evaluate_smart_collection(home_legs.id.clone()).unwrap()), vec!["goblet"]);
+
+        repo.add_exercise_tag("squat".to_string(), "Home".to_string()).unwrap();
+        assert_eq!(ids(repo.evaluate_smart_collection(home_legs.id.clone()).unwrap()), vec!["goblet", "squat"]);
+        assert!(repo.remove_exercise_tag("squat".to_string(), "home".to_string()).unwrap());
+        assert!(!repo.remove_exercise_tag("squat".to_string(), "home".to_string()).unwrap());
+
+        let stored = repo.get_smart_collection(home_legs.id.clone()).unwrap();
+        assert_eq!(stored, home_legs);
+
+        let favorites = ExerciseFilter { favorites_only: true, max_difficulty: Some(4), ..Default::default() };
+        assert_eq!(ids(repo.filter_exercises(favorites).unwrap()), vec!["goblet"]);
+        let bodyweight = ExerciseFilter { available_equipment: Some(vec![]), ..Default::default() };
+        assert_eq!(ids(repo.filter_exercises(bodyweight).unwrap()), vec!["push-up"]);
+        let invalid = ExerciseFilter { min_difficulty: Some(8), max_difficulty: Some(2), ..Default::default() };
+        assert!(matches!(repo.filter_exercises(invalid), Err(WeightliftingError::InvalidInput { .. })));
+
+        debug!("✅ test_tags_favorites_and_smart_collections passed");
+    }
+
+    #[test]
+    fn test_exercise_usage_and_frecency_ranking() {
+        initialize_logging();
+        debug!("🧪 Running test_exercise_usage_and_frecency_ranking");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        for (id, name) in [("bench", "Bench Press"), ("curl", "Curl"), ("deadlift", "Deadlift"), ("squat", "Squat")] {
+            add_test_exercise(&repo, id, name);
+        }
+
+        let day = 86_400;
+        let now = 100 * day;
+        // Squat: three sessions two months ago; deadlift: one session yesterday
+        for session in 0..3 {
+            for set in session_sets("squat", &format!("s{}", session), 100.0, &[5, 5], now - 60 * day + session * day) {
+                repo.log_set(set).unwrap();
+            }
+        }
+        for set in session_sets("deadlift", "d1", 140.0, &[5], now - day) {
+            repo.log_set(set).unwrap();
+        }
+        // Sessionless sets on the same day count as one use
+        repo.log_set(LoggedSet::new("bench".to_string(), 80.0, 8, now - 10 * day)).unwrap();
+        repo.log_set(LoggedSet::new("bench".to_string(), 80.0, 8, now - 10 * day + 600)).unwrap();
+
+        let squat = repo.get_exercise_usage("squat".to_string(), now).unwrap();
+        assert_eq!((squat.use_count, squat.set_count), (3, 6));
+        assert_eq!(squat.last_used_at, Some(now - 58 * day));
+        let bench = repo.get_exercise_usage("bench".to_string(), now).unwrap();
+        assert_eq!((bench.use_count, bench.set_count), (1, 2));
+        let curl = repo.get_exercise_usage("curl".to_string(), now).unwrap();
+        assert_eq!((curl.use_count, curl.last_used_at, curl.frecency_score), (0, None, 0.0));
+        assert!(matches!(
+            repo.get_exercise_usage("missing".to_string(), now),
+            Err(WeightliftingError::ExerciseNotFound { .. })
+        ));
+
+        let ids = |order| repo.get_exercises_sorted(order, now).unwrap().into_iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(ExerciseSortOrder::Name), vec!["bench", "curl", "deadlift", "squat"]);
+        assert_eq!(ids(ExerciseSortOrder::RecentlyUsed), vec!["deadlift", "bench", "squat", "curl"]);
+        assert_eq!(ids(ExerciseSortOrder::MostUsed), vec!["squat", "deadlift", "bench", "curl"]);
+        // Recent single uses outrank three stale ones
+        assert_eq!(ids(ExerciseSortOrder::Frecency), vec!["deadlift", "bench", "squat", "curl"]);
+
+        // Usage follows the log, so deleting sets lowers it
+        let usage = repo.get_all_exercise_usage(now).unwrap();
+        assert_eq!(usage[0].exercise_id, "deadlift");
+        let deadlift_set = repo.get_recent_sets("deadlift".to_string(), 1).unwrap().remove(0);
+        repo.delete_set(deadlift_set.id).unwrap();
+        assert_eq!(repo.get_all_exercise_usage(now).unwrap()[0].exercise_id, "bench");
+
+        debug!("✅ test_exercise_usage_and_frecency_ranking passed");
+    }
+
+    #[test]
+    fn test_grouped_exercises_interleave_sets() {
+        initialize_logging();
+        debug!("🧪 Running test_grouped_exercises_interleave_sets");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        for (id, name) in [("bench", "Bench Press"), ("squat", "Squat"), ("row", "Row"), ("curl", "Curl")] {
+            add_test_exercise(&repo, id, name);
+        }
+        let workout = repo
+            .start_workout(vec!["bench".to_string(), "squat".to_string(), "row".to_string(), "curl".to_string()], 0)
+            .unwrap();
+        let order = |workout: &ActiveWorkout| workout.state().exercises.into_iter().map(|e| e.exercise_id).collect::<Vec<_>>();
+        let current = |workout: &ActiveWorkout| {
+            let state = workout.state();
+            state.current_index.map(|i| state.exercises[i as usize].exercise_id.clone())
+        };
+
+        let invalid = ExerciseGroupPlan {
+            kind: ExerciseGroupKind::GiantSet,
+            exercise_ids: vec!["bench".to_string(), "row".to_string()],
+            rounds: 2,
+            rest_between_exercises_seconds: 0,
+            rest_after_round_seconds: 90,
+        };
+        assert!(matches!(workout.group_exercises(invalid.clone(), 5), Err(WeightliftingError::InvalidInput { .. })));
+
+        // A superset pulls its exercises together in plan order
+        let superset = workout
+            .group_exercises(ExerciseGroupPlan { kind: ExerciseGroupKind::Superset, ..invalid }, 10)
+            .unwrap();
+        assert_eq!(order(&workout), vec!["bench", "row", "squat", "curl"]);
+        assert!(matches!(
+            workout.group_exercises(ExerciseGroupPlan {
+                kind: ExerciseGroupKind::Circuit,
+                exercise_ids: vec!["row".to_string(), "curl".to_string()],
+                rounds: 1,
+                rest_between_exercises_seconds: 0,
+                rest_after_round_seconds: 0,
+            }, 15),
+            Err(WeightliftingError::InvalidState { .. })
+        ));
+
+        // Moving a member moves the whole superset, and nothing can land inside it
+        workout.move_exercise(1, 3, 16).unwrap();
+        assert_eq!(order(&workout), vec!["squat", "curl", "bench", "row"]);
+        workout.move_exercise(2, 0, 17).unwrap();
+        assert_eq!(order(&workout), vec!["bench", "row", "squat", "curl"]);
+        assert!(matches!(workout.move_exercise(2, 1, 18), Err(WeightliftingError::InvalidState { .. })));
+        assert_eq!(current(&workout).as_deref(), Some("bench"));
+
+        // Sets must alternate, resting only once each round is done
+        let set = |id: &str, at: i64| LoggedSet::new(id.to_string(), 60.0, 10, at);
+        assert!(matches!(workout.log_set(set("row", 20), 120), Err(WeightliftingError::InvalidState { .. })));
+        workout.log_set(set("bench", 30), 120).unwrap();
+        assert_eq!(current(&workout).as_deref(), Some("row"));
+        assert_eq!(workout.state().rest_timer, None);
+        workout.log_set(set("row", 60), 120).unwrap();
+        assert_eq!(workout.rest_remaining_seconds(60), Some(90));
+        assert_eq!(workout.state().groups[0].completed_rounds, 1);
+        workout.log_set(set("bench", 200), 0).unwrap();
+        workout.log_set(set("row", 230), 0).unwrap();
+
+        let state = workout.state();
+        assert_eq!(state.groups[0].next_position, None);
+        assert_eq!(state.groups[0].id, superset.id);
+        assert!(state.exercises[..2].iter().all(|e| e.status == WorkoutExerciseStatus::Completed));
+        assert_eq!(current(&workout).as_deref(), Some("squat"));
+
+        // Skipping a circuit member closes the round with the exercises that are left
+        workout.group_exercises(ExerciseGroupPlan {
+            kind: ExerciseGroupKind::Circuit,
+            exercise_ids: vec!["squat".to_string(), "curl".to_string()],
+            rounds: 2,
+            rest_between_exercises_seconds: 15,
+            rest_after_round_seconds: 60,
+        }, 240).unwrap();
+        workout.log_set(set("squat", 300), 0).unwrap();
+        assert_eq!(workout.rest_remaining_seconds(300), Some(15));
+        workout.skip_exercise(310).unwrap();
+        assert_eq!(workout.state().groups[1].completed_rounds, 1);
+        assert_eq!(current(&workout).as_deref(), Some("squat"));
+
+        // Groups survive an app restart
+        let restored = repo.resume_workout().unwrap().expect("Workout should be restored");
+        assert_eq!(restored.state().groups, workout.state().groups);
+        restored.log_set(set("squat", 400), 0).unwrap();
+        assert_eq!(restored.state().groups[1].next_position, None);
+        assert_eq!(restored.state().current_index, None);
+
+        let summary = restored.finish(500).unwrap();
+        assert_eq!(summary.exercise_ids, vec!["bench", "row", "squat"]);
+
+        debug!("✅ test_grouped_exercises_interleave_sets passed");
+    }
+
+    #[test]
+    fn test_warmup_generation() {
+        initialize_logging();
+        debug!("🧪 Running test_warmup_generation");
+
+        let squat = Exercise::new("squat".to_string(), "Squat".to_string(), None, vec!["Quadriceps".to_string()], vec!["barbell".to_string()], 7);
+        let plates: Vec<PlateCount> = [(20.0, 4), (10.0, 2), (5.0, 2), (2.5, 2), (1.25, 2)]
+            .iter()
+            .map(|&(weight_kg, count)| PlateCount { weight_kg, count })
+            .collect();
+
+        // 40/60/80% of 100 kg round down to loadable weights
+        let ramp = generate_warmup(squat.clone(), 100.0, 20.0, plates.clone(), WarmupScheme::Standard).unwrap();
+        let loads: Vec<(f64, u32)> = ramp.iter().map(|s| (s.weight_kg, s.reps)).collect();
+        assert_eq!(loads, vec![(20.0, 10), (40.0, 5), (60.0, 3), (80.0, 2)]);
+        assert_eq!(ramp[2].plates, vec![20.0]);
+        assert_eq!(ramp[3].plates, vec![20.0, 10.0]);
+
+        // Steps that round to the same load merge, and nothing reaches the working weight
+        let light = generate_warmup(squat.clone(), 30.0, 20.0, plates.clone(), WarmupScheme::Extended).unwrap();
+        let loads: Vec<(f64, u32)> = light.iter().map(|s| (s.weight_kg, s.reps)).collect();
+        assert_eq!(loads, vec![(20.0, 4), (22.5, 3), (25.0, 2), (27.5, 1)]);
+        assert!((light[3].percentage - 91.67).abs() < 0.01);
+
+        // Bodyweight exercises ramp the added load without a bar
+        let dip = Exercise::new("dip".to_string(), "Dip".to_string(), None, vec!["Chest".to_string()], vec![], 5);
+        let custom = WarmupScheme::Custom { steps: vec![WarmupStep { percentage: 0.0, reps: 8 }, WarmupStep { percentage: 50.0, reps: 3 }] };
+        let weighted = generate_warmup(dip.clone(), 25.0, 20.0, plates.clone(), custom.clone()).unwrap();
+        assert_eq!(weighted.iter().map(|s| s.weight_kg).collect::<Vec<_>>(), vec![0.0, 12.5]);
+        assert_eq!(weighted[1].plates, vec![10.0, 2.5]);
+        assert!(generate_warmup(dip, 0.0, 20.0, plates.clone(), custom).unwrap().is_empty());
+
+        // Loading searches plate combinations: 30 kg a side from 25/15/10 kg plates is 15 + 15
+        let bumpers: Vec<PlateCount> = [(25.0, 2), (15.0, 4), (10.0, 2)]
+            .iter()
+            .map(|&(weight_kg, count)| PlateCount { weight_kg, count })
+            .collect();
+        let single = WarmupScheme::Custom { steps: vec![WarmupStep { percentage: 80.0, reps: 2 }] };
+        let bumper_ramp = generate_warmup(squat.clone(), 100.0, 20.0, bumpers, single).unwrap();
+        assert_eq!(bumper_ramp[0].weight_kg, 80.0);
+        assert_eq!(bumper_ramp[0].plates, vec![15.0, 15.0]);
+
+        let descending = WarmupScheme::Custom { steps: vec![WarmupStep { percentage: 60.0, reps: 3 }, WarmupStep { percentage: 40.0, reps: 5 }] };
+        assert!(matches!(generate_warmup(squat.clone(), 100.0, 20.0, plates.clone(), descending), Err(WeightliftingError::InvalidInput { .. })));
+        assert!(matches!(generate_warmup(squat, 15.0, 20.0, plates, WarmupScheme::Minimal), Err(WeightliftingError::InvalidInput { .. })));
+
+        debug!("✅ test_warmup_generation passed");
+    }
+
+    #[test]
+    fn test_training_max_prescriptions() {
+        initialize_logging();
+        debug!("🧪 Running test_training_max_prescriptions");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        add_test_exercise(&repo, "bench", "Bench Press");
+
+        repo.set_training_max("squat".to_string(), 140.0, 1_000).unwrap();
+        repo.set_training_max("squat".to_string(), 145.0, 2_000).unwrap();
+        assert_eq!(repo.get_training_max("squat".to_string(), 500).unwrap(), None);
+        assert_eq!(repo.get_training_max("squat".to_string(), 1_500).unwrap().unwrap().weight_kg, 140.0);
+        let history = repo.get_training_max_history("squat".to_string()).unwrap();
+        assert_eq!(history.iter().map(|tm| tm.weight_kg).collect::<Vec<_>>(), vec![140.0, 145.0]);
+        assert!(matches!(repo.set_training_max("squat".to_string(), 0.0, 3_000), Err(WeightliftingError::InvalidInput { .. })));
+
+        // 5/3/1 week one top set: 85% x 5+
+        let top_set = PercentagePrescription { exercise_id: "squat".to_string(), sets: 1, reps: 5, percentage: 85.0, is_amrap: true };
+        let volume = PercentagePrescription { sets: 5, reps: 10, percentage: 50.0, is_amrap: false, ..top_set.clone() };
+
+        // 123.25 kg rounds to the default 2.5 kg increment
+        let resolved = repo.resolve_prescriptions(vec![top_set.clone(), volume], None, 2_500).unwrap();
+        assert_eq!((resolved[0].weight_kg, resolved[0].training_max_kg), (122.5, 145.0));
+        assert_eq!(resolved[0].label, "85% x 5+");
+        assert_eq!((resolved[1].weight_kg, resolved[1].label.as_str()), (72.5, "5 x 10 @ 50%"));
+
+        // A gym with fractional plates rounds more finely
+        let home = GymProfile { smallest_increment_kg: Some(0.5), ..GymProfile::new("Home".to_string(), vec!["barbell".to_string()]) };
+        repo.save_gym_profile(home.clone()).unwrap();
+        assert_eq!(repo.get_gym_profile(home.id.clone()).unwrap().smallest_increment_kg, Some(0.5));
+        let resolved = repo.resolve_prescriptions(vec![top_set.clone()], Some(home.id.clone()), 2_500).unwrap();
+        assert_eq!((resolved[0].weight_kg, resolved[0].increment_kg), (123.5, 0.5));
+        let at_first_max = repo.resolve_prescriptions(vec![top_set.clone()], Some(home.id), 1_500).unwrap();
+        assert_eq!(at_first_max[0].weight_kg, 119.0);
+
+        let bench = PercentagePrescription { exercise_id: "bench".to_string(), ..top_set };
+        assert!(matches!(repo.resolve_prescriptions(vec![bench], None, 2_500), Err(WeightliftingError::InsufficientData { .. })));
+
+        assert!(repo.delete_training_max("squat".to_string(), 2_000).unwrap());
+        assert_eq!(repo.get_training_max("squat".to_string(), 2_500).unwrap().unwrap().weight_kg, 140.0);
+
+        debug!("✅ test_training_max_prescriptions passed");
+    }
+
+    #[test]
+    fn test_set_types_validation_and_volume() {
+        initialize_logging();
+        debug!("🧪 Running test_set_types_validation_and_volume");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "curl", "Curl");
+        repo.add_exercise(Exercise::new("pull-up".to_string(), "Pull-up".to_string(), None, vec!["Lats".to_string()], vec![], 6)).unwrap();
+        repo.add_exercise(Exercise::new("plank".to_string(), "Plank".to_string(), None, vec!["Abs".to_string()], vec![], 2)).unwrap();
+        repo.body_measurements()
+            .add_measurement(BodyMeasurement { bodyweight_kg: Some(80.0), ..BodyMeasurement::new(0) })
+            .unwrap();
+
+        let drop_set = LoggedSet {
+            set_type: SetType::DropSet,
+            drops: vec![DropSegment { weight_kg: 15.0, reps: 6 }, DropSegment { weight_kg: 10.0, reps: 6 }],
+            ..LoggedSet::new("curl".to_string(), 20.0, 8, 100)
+        };
+        let weighted = LoggedSet {
+            set_type: SetType::WeightedBodyweight,
+            added_load_kg: Some(20.0),
+            ..LoggedSet::new("pull-up".to_string(), 0.0, 5, 200)
+        };
+        let assisted = LoggedSet {
+            set_type: SetType::AssistedBodyweight,
+            assistance_kg: Some(30.0),
+            is_amrap: true,
+            ..LoggedSet::new("pull-up".to_string(), 0.0, 10, 300)
+        };
+        let plank = LoggedSet {
+            set_type: SetType::TimedHold,
+            duration_seconds: Some(60),
+            ..LoggedSet::new("plank".to_string(), 0.0, 1, 400)
+        };
+        for set in [drop_set.clone(), weighted.clone(), assisted.clone(), plank.clone()] {
+            repo.log_set(set).unwrap();
+        }
+
+        // Type-specific fields round-trip through the database
+        let stored = repo.get_recent_sets("pull-up".to_string(), 2).unwrap();
+        assert_eq!(stored, vec![assisted.clone(), weighted.clone()]);
+        assert_eq!(repo.get_recent_sets("curl".to_string(), 1).unwrap()[0].drops, drop_set.drops);
+
+        assert_eq!(drop_set.volume(), 160.0 + 90.0 + 60.0);
+        assert_eq!(weighted.volume(), 100.0);
+        assert_eq!(weighted.volume_with_bodyweight(Some(80.0)), 500.0);
+        assert_eq!(assisted.volume_with_bodyweight(Some(80.0)), 500.0);
+        assert_eq!(plank.volume(), 0.0);
+
+        // Summaries count bodyweight and every drop, and holds give no strength estimate
+        let pull_ups = repo.get_exercise_summary("pull-up".to_string(), false).unwrap();
+        assert_eq!((pull_ups.total_volume_kg, pull_ups.best_estimated_one_rm_kg), (1000.0, None));
+        let curls = repo.get_exercise_summary("curl".to_string(), false).unwrap();
+        assert_eq!((curls.total_reps, curls.total_volume_kg), (20, 310.0));
+        assert_eq!(repo.get_exercise_summary("plank".to_string(), false).unwrap().best_estimated_one_rm_kg, None);
+
+        let invalid = [
+            LoggedSet { set_type: SetType::TimedHold, ..LoggedSet::new("plank".to_string(), 0.0, 1, 500) },
+            LoggedSet { duration_seconds: Some(30), ..LoggedSet::new("curl".to_string(), 20.0, 8, 500) },
+            LoggedSet { assistance_kg: Some(10.0), ..weighted.clone() },
+            LoggedSet { weight_kg: 20.0, ..weighted },
+            LoggedSet { is_amrap: true, ..plank },
+            LoggedSet { drops: vec![DropSegment { weight_kg: 25.0, reps: 5 }], ..drop_set.clone() },
+            LoggedSet { drops: vec![DropSegment { weight_kg: 15.0, reps: 0 }], ..drop_set },
+            LoggedSet { assistance_kg: Some(-5.0), ..assisted },
+        ];
+        for set in invalid {
+            assert!(matches!(repo.log_set(set), Err(WeightliftingError::InvalidInput { .. })));
+        }
+
+        debug!("✅ test_set_types_validation_and_volume passed");
+    }
+
+    #[test]
+    fn test_cardio_logging_and_weekly_summaries() {
+        initialize_logging();
+        debug!("🧪 Running test_cardio_logging_and_weekly_summaries");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        // 2024-01-01 was a Monday
+        let monday = CalendarDate::new(2024, 1, 1).unwrap().to_timestamp();
+        let day = 86_400;
+
+        let row = CardioActivity {
+            distance_m: Some(2_000.0),
+            average_heart_rate: Some(160),
+            max_heart_rate: Some(182),
+            calories: Some(110.0),
+            ..CardioActivity::new(CardioActivityType::Row, monday + 3_600, 480)
+        };
+        let run = CardioActivity {
+            distance_m: Some(5_000.0),
+            average_heart_rate: Some(150),
+            ..CardioActivity::new(CardioActivityType::Run, monday + 2 * day, 1_500)
+        };
+        let sled = CardioActivity { load_kg: Some(100.0), ..CardioActivity::new(CardioActivityType::SledPush, monday + 6 * day, 120) };
+        let next_week = CardioActivity::new(CardioActivityType::Row, monday + 8 * day, 600);
+        for activity in [row.clone(), run.clone(), sled.clone(), next_week] {
+            repo.log_cardio_activity(activity).unwrap();
+        }
+
+        // 2:00/500m on the rower and 5:00/km running at 12 km/h
+        assert_eq!(row.metrics().pace_seconds_per_500m, Some(120.0));
+        let run_metrics = cardio_metrics(run.clone());
+        assert_eq!((run_metrics.pace_seconds_per_km, run_metrics.speed_kmh), (Some(300.0), Some(12.0)));
+        assert_eq!(sled.metrics().speed_kmh, None);
+
+        let stored = repo.get_cardio_activities(monday, monday + 7 * day - 1).unwrap();
+        assert_eq!(stored, vec![row, run, sled]);
+
+        // A range starting mid-week still covers the whole week, and the empty third week is reported
+        let weeks = repo.get_cardio_weekly_summaries(monday + day, monday + 15 * day).unwrap();
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[0].week_start, CalendarDate::new(2024, 1, 1).unwrap());
+        assert_eq!((weeks[0].activity_count, weeks[0].duration_seconds, weeks[0].distance_m), (3, 2_100, 7_000.0));
+        assert_eq!(weeks[0].by_type[0].activity_type, CardioActivityType::Run);
+        let weighted_heart_rate = (160.0 * 480.0 + 150.0 * 1_500.0) / 1_980.0;
+        assert!((weeks[0].average_heart_rate.unwrap() - weighted_heart_rate).abs() < 1e-9);
+        assert_eq!((weeks[1].activity_count, weeks[2].activity_count), (1, 0));
+        assert_eq!(weeks[2].average_heart_rate, None);
+
+        let invalid = [
+            CardioActivity::new(CardioActivityType::Run, monday, 0),
+            CardioActivity { distance_m: Some(-1.0), ..CardioActivity::new(CardioActivityType::Run, monday, 60) },
+            CardioActivity { average_heart_rate: Some(170), max_heart_rate: Some(160), ..CardioActivity::new(CardioActivityType::Run, monday, 60) },
+        ];
+        for activity in invalid {
+            assert!(matches!(repo.log_cardio_activity(activity), Err(WeightliftingError::InvalidInput { .. })));
+        }
+
+        debug!("✅ test_cardio_logging_and_weekly_summaries passed");
+    }
+
+    #[test]
+    fn test_powerlifting_scores_and_best_total() {
+        initialize_logging();
+        debug!("🧪 Running test_powerlifting_scores_and_best_total");
+
+        // A 100 kg man totalling 700 kg raw
+        let scores = powerlifting_scores(700.0, 100.0, Sex::Male, CompetitionEquipment::Classic).unwrap();
+        assert!((scores.dots - 430.9).abs() < 0.5, "dots {}", scores.dots);
+        assert!((scores.wilks - 426.0).abs() < 1.0, "wilks {}", scores.wilks);
+        assert!((scores.wilks_2020 - 510.6).abs() < 1.0, "wilks 2020 {}", scores.wilks_2020);
+        assert!((scores.ipf_gl - 88.4).abs() < 0.5, "ipf gl {}", scores.ipf_gl);
+        assert!(dots_points(400.0, 60.0, Sex::Female).unwrap() > dots_points(400.0, 60.0, Sex::Male).unwrap());
+        // Bodyweight is clamped to the range each formula was fitted on
+        assert_eq!(wilks_points(500.0, 250.0, Sex::Male).unwrap(), wilks_points(500.0, 201.9, Sex::Male).unwrap());
+        assert_eq!(ipf_gl_points(300.0, 30.0, Sex::Female, CompetitionEquipment::Classic, CompetitionEvent::FullPower).unwrap(), 0.0);
+        assert!(matches!(wilks_2020_points(0.0, 80.0, Sex::Male), Err(WeightliftingError::InvalidInput { .. })));
+        assert!(matches!(dots_points(500.0, f64::NAN, Sex::Male), Err(WeightliftingError::InvalidInput { .. })));
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        assert!(matches!(repo.get_best_total(0), Err(WeightliftingError::InsufficientData { .. })));
+        for (id, name) in [("squat", "Squat"), ("bench", "Bench Press"), ("deadlift", "Deadlift")] {
+            add_test_exercise(&repo, id, name);
+        }
+        let missing = LifterProfile {
+            sex: Sex::Male,
+            equipment: CompetitionEquipment::Classic,
+            squat_exercise_id: Some("front-squat".to_string()),
+            bench_exercise_id: None,
+            deadlift_exercise_id: None,
+        };
+        assert!(matches!(repo.set_lifter_profile(missing), Err(WeightliftingError::ExerciseNotFound { .. })));
+        let profile = LifterProfile {
+            sex: Sex::Male,
+            equipment: CompetitionEquipment::Classic,
+            squat_exercise_id: Some("squat".to_string()),
+            bench_exercise_id: Some("bench".to_string()),
+            deadlift_exercise_id: Some("deadlift".to_string()),
+        };
+        repo.set_lifter_profile(profile.clone()).unwrap();
+        assert_eq!(repo.get_lifter_profile().unwrap(), Some(profile));
+
+        repo.log_set(LoggedSet::new("squat".to_string(), 240.0, 1, 1_000)).unwrap();
+        repo.log_set(LoggedSet::new("squat".to_string(), 220.0, 3, 2_000)).unwrap();
+        repo.log_set(LoggedSet::new("bench".to_string(), 160.0, 1, 1_000)).unwrap();
+
+        // No deadlift yet, so there is no total
+        let partial = repo.get_best_total(5_000).unwrap();
+        assert_eq!(partial.squat.as_ref().map(|r| (r.weight_kg, r.performed_at)), Some((240.0, 1_000)));
+        assert_eq!((partial.total_kg, partial.scores), (None, None));
+
+        repo.log_set(LoggedSet::new("deadlift".to_string(), 300.0, 1, 3_000)).unwrap();
+        repo.log_set(LoggedSet::new("deadlift".to_string(), 320.0, 1, 9_000)).unwrap();
+        repo.body_measurements()
+            .add_measurement(BodyMeasurement { bodyweight_kg: Some(100.0), ..BodyMeasurement::new(0) })
+            .unwrap();
+
+        // The later deadlift isn't counted yet
+        let best = repo.get_best_total(5_000).unwrap();
+        assert_eq!((best.total_kg, best.bodyweight_kg), (Some(700.0), Some(100.0)));
+        assert!(best.estimated_total_kg.unwrap() >= 700.0);
+        assert_eq!(best.scores, Some(scores));
+        assert_eq!(repo.get_best_total(10_000).unwrap().total_kg, Some(720.0));
+
+        debug!("✅ test_powerlifting_scores_and_best_total passed");
+    }
+
+    #[test]
+    fn test_meet_attempts_and_selection() {
+        initialize_logging();
+        debug!("🧪 Running test_meet_attempts_and_selection");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        let day = 86_400;
+        let now = 100 * day;
+
+        // Attempt selection needs the squat exercise from the lifter profile
+        assert!(matches!(
+            repo.suggest_attempts(CompetitionLift::Squat, AttemptStrategy::Standard, now),
+            Err(WeightliftingError::InsufficientData { .. })
+        ));
+        repo.set_lifter_profile(LifterProfile {
+            sex: Sex::Female,
+            equipment: CompetitionEquipment::Classic,
+            squat_exercise_id: Some("squat".to_string()),
+            bench_exercise_id: None,
+            deadlift_exercise_id: None,
+        }).unwrap();
+        // Too old to count as a recent max
+        repo.log_set(LoggedSet::new("squat".to_string(), 220.0, 1, now - 70 * day)).unwrap();
+        repo.log_set(LoggedSet::new("squat".to_string(), 200.0, 1, now - 10 * day)).unwrap();
+
+        let plan = repo.suggest_attempts(CompetitionLift::Squat, AttemptStrategy::Standard, now).unwrap();
+        assert_eq!((plan.exercise_id.as_str(), plan.estimated_max_kg), ("squat", 200.0));
+        assert_eq!(plan.attempts_kg, vec![182.5, 192.5, 200.0]);
+        let conservative = repo.suggest_attempts(CompetitionLift::Squat, AttemptStrategy::Conservative, now).unwrap();
+        assert_eq!(conservative.attempts_kg, vec![180.0, 190.0, 197.5]);
+        assert!(matches!(
+            repo.suggest_attempts(CompetitionLift::Bench, AttemptStrategy::Standard, now),
+            Err(WeightliftingError::InsufficientData { .. })
+        ));
+
+        let date = CalendarDate::new(2024, 6, 15).unwrap();
+        let meet = Meet { bodyweight_kg: Some(62.4), ..Meet::new("Spring Open".to_string(), date, "IPF".to_string(), "63".to_string(), CompetitionEquipment::Classic) };
+        repo.save_meet(meet.clone()).unwrap();
+        assert_eq!(repo.get_meet(meet.id.clone()).unwrap(), meet);
+
+        let attempt = |lift, attempt_number, weight_kg, status| MeetAttempt { lift, attempt_number, weight_kg, status };
+        let next = |repo: &ExerciseRepository| repo.suggest_next_attempt(meet.id.clone(), CompetitionLift::Squat, AttemptStrategy::Standard, now).unwrap();
+        assert_eq!(next(&repo), Some(182.5));
+
+        repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Squat, 1, 182.5, AttemptStatus::Good)).unwrap();
+        assert_eq!(next(&repo), Some(192.5));
+        repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Squat, 2, 192.5, AttemptStatus::NoLift)).unwrap();
+        assert_eq!(next(&repo), Some(192.5));
+        // A lighter attempt than the last one isn't allowed
+        assert!(matches!(
+            repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Squat, 3, 190.0, AttemptStatus::Planned)),
+            Err(WeightliftingError::InvalidInput { .. })
+        ));
+        repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Squat, 3, 192.5, AttemptStatus::Good)).unwrap();
+        assert_eq!(next(&repo), None);
+
+        repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Bench, 1, 100.0, AttemptStatus::Good)).unwrap();
+        let result = meet_result(repo.get_meet(meet.id.clone()).unwrap());
+        assert_eq!((result.best_squat_kg, result.best_bench_kg, result.total_kg, result.good_lifts), (Some(192.5), Some(100.0), None, 3));
+
+        let stored = repo
+            .record_attempt(meet.id.clone(), attempt(CompetitionLift::Deadlift, 1, 210.0, AttemptStatus::Good))
+            .unwrap();
+        assert_eq!(stored.result().total_kg, Some(502.5));
+        assert!(matches!(
+            repo.record_attempt(meet.id.clone(), attempt(CompetitionLift::Deadlift, 4, 230.0, AttemptStatus::Planned)),
+            Err(WeightliftingError::InvalidInput { .. })
+        ));
+
+        assert_eq!(repo.get_all_meets().unwrap().len(), 1);
+        assert!(repo.delete_meet(meet.id.clone()).unwrap());
+        assert!(matches!(repo.get_meet(meet.id), Err(WeightliftingError::RecordNotFound { .. })));
+
+        debug!("✅ test_meet_attempts_and_selection passed");
+    }
+
+    #[test]
+    fn test_strength_standards_classification() {
+        initialize_logging();
+        debug!("🧪 Running test_strength_standards_classification");
+
+        // At the reference bodyweight the squat ladder is 0.5-2.5x bodyweight in even steps
+        let reference = strength_standards(CanonicalLift::Squat, 80.0, Sex::Male).unwrap();
+        assert_eq!(reference.iter().map(|t| t.one_rm_kg).collect::<Vec<_>>(), vec![40.0, 80.0, 120.0, 160.0, 200.0]);
+        assert_eq!(reference[2], StrengthThreshold { level: StrengthLevel::Intermediate, one_rm_kg: 120.0 });
+        // Every lift scales the same ladder, so the spacing between levels is even
+        let bench = strength_standards(CanonicalLift::BenchPress, 63.0, Sex::Female).unwrap();
+        let gaps: Vec<f64> = bench.windows(2).map(|pair| pair[1].one_rm_kg - pair[0].one_rm_kg).collect();
+        assert!(gaps.iter().all(|gap| (gap - gaps[0]).abs() < 1e-9));
+
+        // Heavier lifters need more weight, but less per kilogram of bodyweight
+        let squat = classify_strength(CanonicalLift::Squat, 150.0, 100.0, Sex::Male).unwrap();
+        assert_eq!((squat.level, squat.next_level), (StrengthLevel::Intermediate, Some(StrengthLevel::Advanced)));
+        assert_eq!(squat.bodyweight_ratio, 1.5);
+        let advanced_kg = 160.0 * 1.25_f64.powf(2.0 / 3.0);
+        assert!((squat.kg_to_next_level.unwrap() - (advanced_kg - 150.0)).abs() < 1e-9);
+        assert!(squat.thresholds[2].one_rm_kg > 120.0 && squat.thresholds[2].one_rm_kg < 150.0);
+        // The strongest lifts top out at Elite
+        let elite = classify_strength(CanonicalLift::Deadlift, 300.0, 80.0, Sex::Male).unwrap();
+        assert_eq!((elite.level, elite.next_level, elite.kg_to_next_level), (StrengthLevel::Elite, None, None));
+        let weak = classify_strength(CanonicalLift::OverheadPress, 5.0, 60.0, Sex::Female).unwrap();
+        assert_eq!((weak.level, weak.next_level), (StrengthLevel::Beginner, Some(StrengthLevel::Novice)));
+        assert!(matches!(classify_strength(CanonicalLift::Squat, 100.0, 0.0, Sex::Male), Err(WeightliftingError::InvalidInput { .. })));
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        add_test_exercise(&repo, "bench", "Bench Press");
+        repo.set_canonical_lift("squat".to_string(), Some(CanonicalLift::Squat)).unwrap();
+        repo.set_canonical_lift("bench".to_string(), Some(CanonicalLift::BenchPress)).unwrap();
+        assert_eq!(repo.get_canonical_lift("squat".to_string()).unwrap(), Some(CanonicalLift::Squat));
+        assert!(matches!(repo.set_canonical_lift("missing".to_string(), None), Err(WeightliftingError::ExerciseNotFound { .. })));
+        repo.log_set(LoggedSet::new("squat".to_string(), 150.0, 1, 1_000)).unwrap();
+
+        // Needs a lifter profile and a bodyweight
+        assert!(matches!(repo.classify_exercise_strength("squat".to_string(), 2_000), Err(WeightliftingError::InsufficientData { .. })));
+        repo.set_lifter_profile(LifterProfile {
+            sex: Sex::Male,
+            equipment: CompetitionEquipment::Classic,
+            squat_exercise_id: None,
+            bench_exercise_id: None,
+            deadlift_exercise_id: None,
+        }).unwrap();
+        repo.body_measurements()
+            .add_measurement(BodyMeasurement { bodyweight_kg: Some(100.0), ..BodyMeasurement::new(0) })
+            .unwrap();
+
+        let classified = repo.classify_exercise_strength("squat".to_string(), 2_000).unwrap();
+        assert_eq!(classified, StrengthClassification { exercise_id: Some("squat".to_string()), ..squat });
+        // The bench has no sets yet, so it's left out of the overview
+        let overview = repo.get_strength_overview(2_000).unwrap();
+        assert_eq!(overview.iter().map(|c| c.lift).collect::<Vec<_>>(), vec![CanonicalLift::Squat]);
+        assert!(matches!(repo.classify_exercise_strength("bench".to_string(), 2_000), Err(WeightliftingError::InsufficientData { .. })));
+
+        repo.set_canonical_lift("bench".to_string(), None).unwrap();
+        assert_eq!(repo.get_canonical_lift("bench".to_string()).unwrap(), None);
+
+        debug!("✅ test_strength_standards_classification passed");
+    }
+
+    struct RecordingListener(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl AchievementListener for RecordingListener {
+        fn on_achievements_unlocked(&self, achievements: Vec<UnlockedAchievement>) {
+            self.0.lock().unwrap().extend(achievements.into_iter().map(|a| a.achievement.id));
+        }
+    }
+
+    #[test]
+    fn test_achievements_and_streaks() {
+        initialize_logging();
+        debug!("🧪 Running test_achievements_and_streaks");
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        assert_eq!(repo.get_all_achievements().unwrap().len(), default_achievements().len());
+        let notified = Arc::new(std::sync::Mutex::new(Vec::new()));
+        repo.set_achievement_listener(Box::new(RecordingListener(notified.clone())));
+
+        add_test_exercise(&repo, "bench", "Bench Press");
+        repo.set_canonical_lift("bench".to_string(), Some(CanonicalLift::BenchPress)).unwrap();
+        // 2024-01-01 was a Monday
+        let monday = CalendarDate::new(2024, 1, 1).unwrap().to_timestamp();
+        let week = 7 * 86_400;
+
+        repo.log_set(LoggedSet::new("bench".to_string(), 60.0, 5, monday + 3_600)).unwrap();
+        assert_eq!(*notified.lock().unwrap(), vec!["first-workout".to_string()]);
+        repo.log_set(LoggedSet::new("bench".to_string(), 100.0, 1, monday + 7_200)).unwrap();
+        assert_eq!(notified.lock().unwrap().last().map(String::as_str), Some("bench-100"));
+
+        // Custom achievements are checked against the data already logged
+        let bench_80 = Achievement {
+            id: "bench-80".to_string(),
+            name: "Bench 80".to_string(),
+            description: "Bench press 80 kg".to_string(),
+            rule: AchievementRule::ExerciseWeight { exercise_id: "bench".to_string(), weight_kg: 80.0 },
+        };
+        repo.save_achievement(bench_80.clone()).unwrap();
+        let unlocked = repo.evaluate_achievements().unwrap();
+        assert_eq!(unlocked, vec![UnlockedAchievement { achievement: bench_80, unlocked_at: monday + 7_200 }]);
+        assert!(repo.evaluate_achievements().unwrap().is_empty());
+        let bench_100 = repo.get_unlocked_achievements().unwrap().into_iter().find(|u| u.achievement.id == "bench-100").unwrap();
+        assert_eq!(bench_100.achievement.name, "Two Plates Bench");
+
+        // Writes check the running volume total, which deleted sets come out of
+        repo.save_achievement(Achievement {
+            id: "volume-1t".to_string(),
+            name: "One Tonne".to_string(),
+            description: "Lift 1000 kg in total".to_string(),
+            rule: AchievementRule::TotalVolume { volume_kg: 1_000.0 },
+        }).unwrap();
+        let mistake = LoggedSet::new("bench".to_string(), 100.0, 5, monday + 10_800);
+        repo.log_set(mistake.clone()).unwrap();
+        assert!(repo.delete_set(mistake.id).unwrap());
+        repo.log_set(LoggedSet::new("bench".to_string(), 100.0, 5, monday + 14_400)).unwrap();
+        assert_ne!(notified.lock().unwrap().last().map(String::as_str), Some("volume-1t"));
+        repo.log_set(LoggedSet::new("bench".to_string(), 100.0, 2, monday + 18_000)).unwrap();
+        assert_eq!(notified.lock().unwrap().last().map(String::as_str), Some("volume-1t"));
+        let volume_unlock = repo.get_unlocked_achievements().unwrap().into_iter().find(|u| u.achievement.id == "volume-1t").unwrap();
+        assert_eq!(volume_unlock.unlocked_at, monday + 18_000);
+
+        // Cardio keeps the weekly streak going
+        for i in 1..4 {
+            repo.log_cardio_activity(CardioActivity::new(CardioActivityType::Run, monday + i * week, 1_800)).unwrap();
+        }
+        assert_eq!(notified.lock().unwrap().last().map(String::as_str), Some("streak-4"));
+        let streak_unlock = repo.get_unlocked_achievements().unwrap().into_iter().find(|u| u.achievement.id == "streak-4").unwrap();
+        assert_eq!(streak_unlock.unlocked_at, monday + 3 * week);
+
+        // The streak survives a week without training until that week is over
+        let streak = repo.get_training_streak(monday + 4 * week + 86_400).unwrap();
+        assert_eq!(
+            streak,
+            TrainingStreak { current_weeks: 4, longest_weeks: 4, current_streak_start: CalendarDate::new(2024, 1, 1), trained_this_week: false }
+        );
+        let broken = repo.get_training_streak(monday + 5 * week).unwrap();
+        assert_eq!((broken.current_weeks, broken.longest_weeks, broken.current_streak_start), (0, 4, None));
+
+        repo.clear_achievement_listener();
+        let notified_count = notified.lock().unwrap().len();
+        repo.log_set(LoggedSet::new("bench".to_string(), 60.0, 5, monday + 5 * week)).unwrap();
+        assert_eq!(notified.lock().unwrap().len(), notified_count);
+
+        let invalid = Achievement { rule: AchievementRule::Workouts { count: 0 }, ..default_achievements()[0].clone() };
+        assert!(matches!(repo.save_achievement(invalid), Err(WeightliftingError::InvalidInput { .. })));
+        let unknown = Achievement {
+            rule: AchievementRule::ExerciseWeight { exercise_id: "missing".to_string(), weight_kg: 50.0 },
+            ..default_achievements()[0].clone()
+        };
+        assert!(matches!(repo.save_achievement(unknown), Err(WeightliftingError::ExerciseNotFound { .. })));
+
+        assert!(repo.delete_achievement("bench-80".to_string()).unwrap());
+        assert!(repo.get_unlocked_achievements().unwrap().iter().all(|u| u.achievement.id != "bench-80"));
+
+        // A backdated bodyweight raises the volume of bodyweight sets already logged
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "pull-up", "Pull-up");
+        let notified = Arc::new(std::sync::Mutex::new(Vec::new()));
+        repo.set_achievement_listener(Box::new(RecordingListener(notified.clone())));
+        for (id, volume_kg) in [("volume-400", 400.0), ("volume-600", 600.0)] {
+            repo.save_achievement(Achievement {
+                id: id.to_string(),
+                name: id.to_string(),
+                description: format!("Lift {} kg in total", volume_kg),
+                rule: AchievementRule::TotalVolume { volume_kg },
+            }).unwrap();
+        }
+        let weighted = LoggedSet {
+            set_type: SetType::WeightedBodyweight,
+            added_load_kg: Some(20.0),
+            ..LoggedSet::new("pull-up".to_string(), 0.0, 5, monday)
+        };
+        repo.log_set(weighted).unwrap();
+        assert!(repo.evaluate_achievements().unwrap().is_empty());
+        repo.body_measurements()
+            .add_measurement(BodyMeasurement { bodyweight_kg: Some(80.0), ..BodyMeasurement::new(0) })
+            .unwrap();
+        let unlocked = repo.evaluate_achievements().unwrap();
+        assert_eq!(unlocked.iter().map(|u| u.achievement.id.as_str()).collect::<Vec<_>>(), vec!["volume-400"]);
+        repo.log_set(LoggedSet::new("pull-up".to_string(), 20.0, 5, monday + 3_600)).unwrap();
+        assert_eq!(notified.lock().unwrap().last().map(String::as_str), Some("volume-600"));
+
+        debug!("✅ test_achievements_and_streaks passed");
+    }
+
+    #[test]
+    fn test_chart_series_bucketing_and_downsampling() {
+        initialize_logging();
+        debug!("🧪 Running test_chart_series_bucketing_and_downsampling");
+
+        // LTTB keeps the endpoints and the spike
+        let wave: Vec<TimeSeriesPoint> = (0..100)
+            .map(|i| TimeSeriesPoint { timestamp: i * 60, value: if i == 37 { 500.0 } else { (i as f64 / 5.0).sin() } })
+            .collect();
+        let reduced = downsample_series(wave.clone(), 10);
+        assert_eq!(reduced.len(), 10);
+        assert_eq!((reduced[0].timestamp, reduced[9].timestamp), (0, 99 * 60));
+        assert!(reduced.iter().any(|p| p.value == 500.0));
+        assert_eq!(downsample_series(wave[..5].to_vec(), 10), wave[..5].to_vec());
+        assert_eq!(downsample_series(wave.clone(), 2), vec![wave[0].clone(), wave[99].clone()]);
+
+        let repo = create_in_memory_repository().expect("Failed to create repository");
+        add_test_exercise(&repo, "squat", "Squat");
+        // 2024-01-01 was a Monday
+        let monday = CalendarDate::new(2024, 1, 1).unwrap().to_timestamp();
+        let day = 86_400;
+        for (weight, reps, at) in [(100.0, 5, monday + 3_600), (110.0, 3, monday + 7_200), (105.0, 5, monday + 2 * day), (120.0, 1, monday + 8 * day)] {
+            repo.log_set(LoggedSet::new("squat".to_string(), weight, reps, at)).unwrap();
+        }
+
+        let query = |metric: ChartMetric, bucket: Option<ChartBucket>| ChartQuery {
+            metric,
+            start: monday,
+            end: monday + 30 * day,
+            bucket,
+            aggregation: None,
+            max_points: None,
+        };
+        let heaviest = ChartMetric::HeaviestWeight { exercise_id: "squat".to_string() };
+        let weekly = repo.get_chart_series(query(heaviest.clone(), Some(ChartBucket::Week))).unwrap();
+        assert_eq!((weekly.aggregation, weekly.raw_point_count), (SeriesAggregation::Max, 4));
+        assert_eq!(
+            weekly.points,
+            vec![
+                ChartPoint { timestamp: monday, value: 110.0, min: 100.0, max: 110.0, count: 3 },
+                ChartPoint { timestamp: monday + 7 * day, value: 120.0, min: 120.0, max: 120.0, count: 1 },
+            ]
+        );
+        let average = repo.get_chart_series(ChartQuery { aggregation: Some(SeriesAggregation::Average), ..query(heaviest, Some(ChartBucket::Month)) }).unwrap();
+        assert_eq!(average.points.iter().map(|p| (p.timestamp, p.value)).collect::<Vec<_>>(), vec![(monday, 108.75)]);
+
+        // A range starting mid-week still sums the whole first week
+        let midweek = repo.get_chart_series(ChartQuery {
+            start: monday + 2 * day,
+            end: monday + 8 * day,
+            ..query(ChartMetric::Volume { exercise_id: None }, Some(ChartBucket::Week))
+        }).unwrap();
+        assert_eq!(midweek.points.iter().map(|p| (p.timestamp, p.value)).collect::<Vec<_>>(), vec![(monday, 1_355.0), (monday + 7 * day, 120.0)]);
+
+        let volume = repo.get_chart_series(query(ChartMetric::Volume { exercise_id: None }, Some(ChartBucket::Day))).unwrap();
+        assert_eq!(volume.points.iter().map(|p| p.value).collect::<Vec<_>>(), vec![830.0, 525.0, 120.0]);
+        let one_rm = repo.get_chart_series(query(ChartMetric::EstimatedOneRm { exercise_id: "squat".to_string() }, None)).unwrap();
+        assert_eq!(one_rm.points.len(), 4);
+        assert!(one_rm.points.iter().all(|p| p.count == 1 && p.value >= 100.0));
+
+        let body = repo.body_measurements();
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(80.0), ..BodyMeasurement::new(monday) }).unwrap();
+        body.add_measurement(BodyMeasurement { bodyweight_kg: Some(81.0), ..BodyMeasurement::new(monday + day) }).unwrap();
+        let bodyweight = repo.get_chart_series(query(ChartMetric::Body { metric: BodyMetric::Bodyweight }, Some(ChartBucket::Month))).unwrap();
+        assert_eq!((bodyweight.aggregation, bodyweight.points[0].value), (SeriesAggregation::Average, 80.5));
+
+        // Long histories are downsampled to the requested size
+        for i in 0..50 {
+            repo.log_set(LoggedSet::new("squat".to_string(), 100.0 + i as f64, 1, monday + (40 + i) * day)).unwrap();
+        }
+        let downsampled = repo.get_chart_series(ChartQuery {
+            end: monday + 100 * day,
+            max_points: Some(10),
+            ..query(ChartMetric::Reps { exercise_id: Some("squat".to_string()) }, Some(ChartBucket::Day))
+        }).unwrap();
+        assert_eq!((downsampled.points.len(), downsampled.raw_point_count), (10, 54));
+
+        assert!(matches!(repo.get_chart_series(ChartQuery { start: monday + day, end: monday, ..query(ChartMetric::Volume { exercise_id: None }, None) }), Err(WeightliftingError::InvalidInput { .. })));
+        assert!(matches!(repo.get_chart_series(ChartQuery { max_points: Some(1), ..query(ChartMetric::Volume { exercise_id: None }, None) }), Err(WeightliftingError::InvalidInput { .. })));
+        assert!(matches!(repo.get_chart_series(ChartQuery { aggregation: Some(SeriesAggregation::Max), ..query(ChartMetric::Volume { exercise_id: None }, None) }), Err(WeightliftingError::InvalidInput { .. })));
+        assert!(matches!(repo.get_chart_series(query(ChartMetric::Reps { exercise_id: Some("missing".to_string()) }, None)), Err(WeightliftingError::ExerciseNotFound { .. })));
+
+        debug!("✅ test_chart_series_bucketing_and_downsampling passed");
+    }
+}
@@ -0,0 +1,278 @@
+// Per-locale exercise names, descriptions and difficulty labels
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use std::collections::HashMap;
+
+/// Difficulty labels for the 1-2, 3-4, 5-6, 7-8 and 9-10 bands
+const DIFFICULTY_LABELS: [(&str, [&str; 5]); 3] = [
+    ("en", ["Very Easy", "Easy", "Moderate", "Hard", "Very Hard"]),
+    ("de", ["Sehr leicht", "Leicht", "Mittel", "Schwer", "Sehr schwer"]),
+    ("es", ["Muy fácil", "Fácil", "Moderado", "Difícil", "Muy difícil"]),
+];
+
+/// An exercise's name and description in one locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseTranslation {
+    pub exercise_id: String,
+    pub locale: String, // BCP 47 tag such as "de" or "es-MX"
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// An exercise with its content resolved for a requested locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedExercise {
+    pub exercise: Exercise,              // Name and description replaced by the translation used
+    pub resolved_locale: Option<String>, // None when the untranslated content was used
+    pub difficulty_label: String,
+}
+
+/// Normalize a locale tag for storage and comparison ("es_MX" -> "es-mx")
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+/// Locales to try for a requested locale, most specific first ("de-AT" -> ["de-at", "de"])
+pub fn locale_fallback_chain(locale: String) -> Vec<String> {
+    let normalized = normalize_locale(&locale);
+    let parts: Vec<&str> = normalized.split('-').filter(|part| !part.is_empty()).collect();
+    (1..=parts.len()).rev().map(|len| parts[..len].join("-")).collect()
+}
+
+/// Difficulty label for a 1-10 level in a locale, falling back to English
+pub fn difficulty_label(difficulty_level: u8, locale: String) -> String {
+    let band = match difficulty_level {
+        1..=2 => 0,
+        3..=4 => 1,
+        5..=6 => 2,
+        7..=8 => 3,
+        9..=10 => 4,
+        _ => return "Unknown".to_string(),
+    };
+
+    locale_fallback_chain(locale)
+        .iter()
+        .find_map(|candidate| DIFFICULTY_LABELS.iter().find(|(code, _)| code == candidate))
+        .unwrap_or(&DIFFICULTY_LABELS[0])
+        .1[band]
+        .to_string()
+}
+
+impl Exercise {
+    /// Human-readable difficulty in a locale, falling back to English
+    pub fn localized_difficulty_description(&self, locale: &str) -> String {
+        difficulty_label(self.difficulty_level, locale.to_string())
+    }
+}
+
+impl ExerciseTranslation {
+    /// Validate the translation's locale and name
+    pub fn validate(&self) -> Result<(), String> {
+        let locale = normalize_locale(&self.locale);
+        if locale.is_empty() || !locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid locale '{}'", self.locale));
+        }
+
+        if self.name.trim().is_empty() {
+            return Err("Translated name cannot be empty".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+impl ExerciseRepository {
+    /// Add or replace an exercise's translation for a locale
+    pub fn set_exercise_translation(&self, translation: ExerciseTranslation) -> Result<(), WeightliftingError> {
+        info!("🌍 Setting {} translation for exercise {}", translation.locale, translation.exercise_id);
+
+        translation.validate().map_err(|message| {
+            warn!("❌ Invalid translation: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &translation.exercise_id)?;
+
+        conn.execute(
+            "INSERT INTO exercise_translations (exercise_id, locale, name, description) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(exercise_id, locale) DO UPDATE SET name = excluded.name, description = excluded.description",
+            params![
+                translation.exercise_id,
+                normalize_locale(&translation.locale),
+                translation.name,
+                translation.description
+            ],
+        )?;
+
+        info!("✅ Saved translation '{}'", translation.name);
+        Ok(())
+    }
+
+    /// Get every translation of an exercise, sorted by locale
+    pub fn get_exercise_translations(&self, exercise_id: String) -> Result<Vec<ExerciseTranslation>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT exercise_id, locale, name, description FROM exercise_translations
+             WHERE exercise_id = ?1 ORDER BY locale",
+        )?;
+        let translations = stmt.query_map([&exercise_id], translation_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(translations)
+    }
+
+    /// Delete an exercise's translation for a locale
+    /// Returns true if the translation was deleted, false if it wasn't found
+    pub fn delete_exercise_translation(&self, exercise_id: String, locale: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting {} translation for exercise {}", locale, exercise_id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute(
+            "DELETE FROM exercise_translations WHERE exercise_id = ?1 AND locale = ?2",
+            params![exercise_id, normalize_locale(&locale)],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get an exercise in the most specific available translation of `locale`
+    ///
+    /// "de-AT" tries "de-at", then "de", then the untranslated content.
+    pub fn get_localized_exercise(&self, id: String, locale: String) -> Result<LocalizedExercise, WeightliftingError> {
+        info!("🌍 Looking up exercise {} for locale {}", id, locale);
+
+        let conn = self.connection()?;
+        let exercise = Self::query_exercise(&conn, &id)?;
+        let translations = Self::query_translations(&conn)?;
+        Ok(localize(exercise, &translations, &locale))
+    }
+
+    /// Get all exercises localized for `locale`, sorted by localized name
+    pub fn get_all_localized_exercises(&self, locale: String) -> Result<Vec<LocalizedExercise>, WeightliftingError> {
+        info!("🌍 Retrieving all exercises for locale {}", locale);
+
+        let conn = self.connection()?;
+        let translations = Self::query_translations(&conn)?;
+        let mut exercises: Vec<LocalizedExercise> = Self::query_exercises(&conn, "", [])?
+            .into_iter()
+            .map(|exercise| localize(exercise, &translations, &locale))
+            .collect();
+        sort_by_localized_name(&mut exercises);
+
+        info!("✅ Localized {} exercises", exercises.len());
+        Ok(exercises)
+    }
+
+    /// Search exercises whose name in any language contains `query`, localized for `locale`
+    pub fn search_exercises(&self, query: String, locale: String) -> Result<Vec<LocalizedExercise>, WeightliftingError> {
+        info!("🔎 Searching exercises for '{}' (locale {})", query, locale);
+
+        let needle = query.trim().to_lowercase();
+        let conn = self.connection()?;
+        let translations = Self::query_translations(&conn)?;
+
+        let mut matches: Vec<LocalizedExercise> = Self::query_exercises(&conn, "", [])?
+            .into_iter()
+            .filter(|exercise| {
+                exercise.name.to_lowercase().contains(&needle)
+                    || translations
+                        .get(&exercise.id)
+                        .is_some_and(|by_locale| by_locale.iter().any(|t| t.name.to_lowercase().contains(&needle)))
+            })
+            .map(|exercise| localize(exercise, &translations, &locale))
+            .collect();
+        sort_by_localized_name(&mut matches);
+
+        info!("✅ Found {} exercises matching '{}'", matches.len(), query);
+        Ok(matches)
+    }
+
+    /// Load every translation, grouped by exercise
+    fn query_translations(conn: &Connection) -> Result<HashMap<String, Vec<ExerciseTranslation>>, WeightliftingError> {
+        let mut stmt = conn.prepare("SELECT exercise_id, locale, name, description FROM exercise_translations")?;
+        let mut translations: HashMap<String, Vec<ExerciseTranslation>> = HashMap::new();
+        for translation in stmt.query_map([], translation_from_row)? {
+            let translation = translation?;
+            translations.entry(translation.exercise_id.clone()).or_default().push(translation);
+        }
+        Ok(translations)
+    }
+
+    /// Remove all translations of an exercise
+    pub(crate) fn delete_translations(conn: &Connection, exercise_id: &str) -> Result<(), WeightliftingError> {
+        conn.execute("DELETE FROM exercise_translations WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(())
+    }
+
+    /// Create the exercise translations table if it doesn't exist
+    pub(crate) fn create_translation_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating exercise_translations table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS exercise_translations (
+                exercise_id TEXT NOT NULL,
+                locale TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                PRIMARY KEY (exercise_id, locale)
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create exercise_translations table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Exercise translations table ready");
+        Ok(())
+    }
+}
+
+/// Apply the best translation for `locale` to an exercise
+fn localize(exercise: Exercise, translations: &HashMap<String, Vec<ExerciseTranslation>>, locale: &str) -> LocalizedExercise {
+    let difficulty_label = exercise.localized_difficulty_description(locale);
+    let translation = translations.get(&exercise.id).and_then(|by_locale| {
+        locale_fallback_chain(locale.to_string())
+            .into_iter()
+            .find_map(|candidate| by_locale.iter().find(|t| t.locale == candidate))
+    });
+
+    match translation {
+        Some(translation) => {
+            debug!("🌍 Using {} translation for {}", translation.locale, exercise.id);
+            LocalizedExercise {
+                exercise: Exercise {
+                    name: translation.name.clone(),
+                    // Fall back to the untranslated description rather than showing none
+                    description: translation.description.clone().or(exercise.description.clone()),
+                    ..exercise
+                },
+                resolved_locale: Some(translation.locale.clone()),
+                difficulty_label,
+            }
+        }
+        None => LocalizedExercise {
+            exercise,
+            resolved_locale: None,
+            difficulty_label,
+        },
+    }
+}
+
+fn sort_by_localized_name(exercises: &mut [LocalizedExercise]) {
+    exercises.sort_by_cached_key(|localized| localized.exercise.name.to_lowercase());
+}
+
+fn translation_from_row(row: &Row) -> rusqlite::Result<ExerciseTranslation> {
+    Ok(ExerciseTranslation {
+        exercise_id: row.get(0)?,
+        locale: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+    })
+}
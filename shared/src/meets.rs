@@ -0,0 +1,450 @@
+// Powerlifting meets with their attempts, and attempt selection from recent estimated maxes
+
+use crate::calendar::CalendarDate;
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::rpe::{estimate_logged_one_rm, round_to_increment};
+use crate::scoring::{CompetitionEquipment, LifterProfile};
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MEET_COLUMNS: &str = "id, name, date, federation, weight_class, equipment, bodyweight_kg, attempts, notes";
+
+/// Attempts per lift in a meet
+const ATTEMPTS_PER_LIFT: u8 = 3;
+
+/// Competition loads go up in 2.5 kg steps
+const MEET_INCREMENT_KG: f64 = 2.5;
+
+/// How far back sets count towards the estimated max used for attempt selection
+const RECENT_MAX_WINDOW_SECONDS: i64 = 8 * 7 * 86_400;
+
+/// One of the three competition lifts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompetitionLift {
+    Squat,
+    Bench,
+    Deadlift,
+}
+
+/// Outcome of an attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttemptStatus {
+    Planned, // Not taken yet
+    Good,
+    NoLift,
+}
+
+/// One attempt at a lift, planned or taken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetAttempt {
+    pub lift: CompetitionLift,
+    pub attempt_number: u8, // 1 to 3
+    pub weight_kg: f64,
+    pub status: AttemptStatus,
+}
+
+/// A powerlifting competition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meet {
+    pub id: String,
+    pub name: String,
+    pub date: CalendarDate,
+    pub federation: String,
+    pub weight_class: String, // As the federation writes it, e.g. "93" or "120+"
+    pub equipment: CompetitionEquipment,
+    pub bodyweight_kg: Option<f64>, // At weigh-in
+    pub attempts: Vec<MeetAttempt>,
+    pub notes: Option<String>,
+}
+
+/// Best good lifts of a meet and the total they make
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetResult {
+    pub best_squat_kg: Option<f64>,
+    pub best_bench_kg: Option<f64>,
+    pub best_deadlift_kg: Option<f64>,
+    pub total_kg: Option<f64>, // Only when every lift has a good attempt
+    pub good_lifts: u32,
+}
+
+/// How close to the estimated max the attempts go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptStrategy {
+    Conservative,
+    Standard,
+    Aggressive,
+}
+
+/// Suggested attempts for a lift
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttemptPlan {
+    pub lift: CompetitionLift,
+    pub exercise_id: String,
+    pub estimated_max_kg: f64,
+    pub attempts_kg: Vec<f64>, // Opener, second and third
+}
+
+impl CompetitionLift {
+    pub const ALL: [CompetitionLift; 3] = [CompetitionLift::Squat, CompetitionLift::Bench, CompetitionLift::Deadlift];
+}
+
+impl AttemptStrategy {
+    /// Percentages of the estimated max for the three attempts
+    pub fn percentages(&self) -> [f64; 3] {
+        match self {
+            AttemptStrategy::Conservative => [90.0, 95.0, 99.0],
+            AttemptStrategy::Standard => [91.0, 96.0, 100.0],
+            AttemptStrategy::Aggressive => [92.0, 98.0, 102.0],
+        }
+    }
+}
+
+impl LifterProfile {
+    /// The exercise logged as `lift`, if the profile sets one
+    pub fn exercise_for(&self, lift: CompetitionLift) -> Option<&String> {
+        match lift {
+            CompetitionLift::Squat => self.squat_exercise_id.as_ref(),
+            CompetitionLift::Bench => self.bench_exercise_id.as_ref(),
+            CompetitionLift::Deadlift => self.deadlift_exercise_id.as_ref(),
+        }
+    }
+}
+
+impl Meet {
+    /// Create a new meet with automatic UUID generation and no attempts
+    pub fn new(name: String, date: CalendarDate, federation: String, weight_class: String, equipment: CompetitionEquipment) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            date,
+            federation,
+            weight_class,
+            equipment,
+            bodyweight_kg: None,
+            attempts: Vec::new(),
+            notes: None,
+        }
+    }
+
+    /// Validate the meet's details and attempts
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Meet name cannot be empty".to_string());
+        }
+        if !self.date.is_valid() {
+            return Err(format!("Invalid meet date {:?}", self.date));
+        }
+        if self.weight_class.trim().is_empty() {
+            return Err("Weight class cannot be empty".to_string());
+        }
+        if let Some(bodyweight_kg) = self.bodyweight_kg.filter(|w| !w.is_finite() || *w <= 0.0) {
+            return Err(format!("Bodyweight must be positive, got {}", bodyweight_kg));
+        }
+
+        for attempt in &self.attempts {
+            if !(1..=ATTEMPTS_PER_LIFT).contains(&attempt.attempt_number) {
+                return Err(format!("Attempt number must be 1 to {}, got {}", ATTEMPTS_PER_LIFT, attempt.attempt_number));
+            }
+            if !attempt.weight_kg.is_finite() || attempt.weight_kg <= 0.0 {
+                return Err(format!("Attempt weight must be positive, got {}", attempt.weight_kg));
+            }
+        }
+
+        for lift in CompetitionLift::ALL {
+            let attempts = self.attempts_for(lift);
+            for pair in attempts.windows(2) {
+                if pair[0].attempt_number == pair[1].attempt_number {
+                    return Err(format!("{:?} attempt {} is listed twice", lift, pair[0].attempt_number));
+                }
+                // Federations don't allow taking a lighter attempt than the last one
+                if pair[1].weight_kg < pair[0].weight_kg {
+                    return Err(format!("{:?} attempt {} is lighter than the attempt before it", lift, pair[1].attempt_number));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Attempts at `lift`, in attempt order
+    pub fn attempts_for(&self, lift: CompetitionLift) -> Vec<&MeetAttempt> {
+        let mut attempts: Vec<&MeetAttempt> = self.attempts.iter().filter(|a| a.lift == lift).collect();
+        attempts.sort_by_key(|a| a.attempt_number);
+        attempts
+    }
+
+    /// Heaviest good attempt at `lift`
+    pub fn best_lift(&self, lift: CompetitionLift) -> Option<f64> {
+        self.attempts_for(lift)
+            .into_iter()
+            .filter(|a| a.status == AttemptStatus::Good)
+            .map(|a| a.weight_kg)
+            .reduce(f64::max)
+    }
+
+    /// Best lifts and total of the meet so far
+    pub fn result(&self) -> MeetResult {
+        let [best_squat_kg, best_bench_kg, best_deadlift_kg] = CompetitionLift::ALL.map(|lift| self.best_lift(lift));
+        MeetResult {
+            best_squat_kg,
+            best_bench_kg,
+            best_deadlift_kg,
+            total_kg: [best_squat_kg, best_bench_kg, best_deadlift_kg].into_iter().sum(),
+            good_lifts: self.attempts.iter().filter(|a| a.status == AttemptStatus::Good).count() as u32,
+        }
+    }
+}
+
+/// Best good lifts and total of a meet
+pub fn meet_result(meet: Meet) -> MeetResult {
+    meet.result()
+}
+
+/// Attempts at the strategy's percentages of `estimated_max_kg`, each at least one step above the last
+fn plan_attempts(estimated_max_kg: f64, strategy: AttemptStrategy) -> Vec<f64> {
+    let mut attempts: Vec<f64> = Vec::with_capacity(ATTEMPTS_PER_LIFT as usize);
+    for percentage in strategy.percentages() {
+        let weight_kg = round_to_increment(estimated_max_kg * percentage / 100.0, MEET_INCREMENT_KG);
+        let weight_kg = match attempts.last() {
+            Some(&previous) if weight_kg < previous + MEET_INCREMENT_KG => previous + MEET_INCREMENT_KG,
+            _ => weight_kg,
+        };
+        attempts.push(weight_kg);
+    }
+    attempts
+}
+
+impl ExerciseRepository {
+    /// Save a meet, replacing any existing meet with the same ID
+    pub fn save_meet(&self, meet: Meet) -> Result<(), WeightliftingError> {
+        info!("🏟️ Saving meet: {} (ID: {})", meet.name, meet.id);
+        debug!("📝 Meet details: {:?}", meet);
+
+        let conn = self.connection()?;
+        Self::write_meet(&conn, &meet)?;
+
+        info!("✅ Saved meet: {}", meet.name);
+        Ok(())
+    }
+
+    /// Get a meet by ID
+    pub fn get_meet(&self, id: String) -> Result<Meet, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_meet(&conn, &id)
+    }
+
+    /// Get all meets, most recent first
+    pub fn get_all_meets(&self) -> Result<Vec<Meet>, WeightliftingError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM meets ORDER BY date DESC, name", MEET_COLUMNS))?;
+        let meets = stmt.query_map([], meet_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(meets)
+    }
+
+    /// Delete a meet
+    /// Returns true if the meet was deleted, false if it wasn't found
+    pub fn delete_meet(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting meet with ID: {}", id);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute("DELETE FROM meets WHERE id = ?1", [&id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Plan or record an attempt, replacing the meet's attempt with the same lift and number
+    pub fn record_attempt(&self, meet_id: String, attempt: MeetAttempt) -> Result<Meet, WeightliftingError> {
+        info!(
+            "🏋️ Recording {:?} attempt {} at {} kg: {:?}",
+            attempt.lift, attempt.attempt_number, attempt.weight_kg, attempt.status
+        );
+
+        let mut conn = self.connection()?;
+        // Take the write lock before reading, so concurrent attempts can't overwrite each other
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut meet = Self::query_meet(&tx, &meet_id)?;
+        meet.attempts.retain(|a| a.lift != attempt.lift || a.attempt_number != attempt.attempt_number);
+        meet.attempts.push(attempt);
+        Self::write_meet(&tx, &meet)?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit attempt for meet '{}': {}", meet_id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to record attempt: {}", e),
+            }
+        })?;
+        Ok(meet)
+    }
+
+    /// Suggest three attempts for `lift` from the best estimated 1RM of the eight weeks before `at`
+    ///
+    /// The lift's exercise comes from the lifter profile. Attempts are rounded
+    /// to 2.5 kg and at least 2.5 kg apart.
+    pub fn suggest_attempts(&self, lift: CompetitionLift, strategy: AttemptStrategy, at: i64) -> Result<AttemptPlan, WeightliftingError> {
+        info!("🎯 Suggesting {:?} {:?} attempts as of {}", strategy, lift, at);
+
+        let conn = self.connection()?;
+        let (exercise_id, estimated_max_kg) = Self::recent_estimated_max(&conn, lift, at)?;
+        let attempts_kg = plan_attempts(estimated_max_kg, strategy);
+
+        info!("✅ Suggested {:?} attempts: {:?}", lift, attempts_kg);
+        Ok(AttemptPlan { lift, exercise_id, estimated_max_kg, attempts_kg })
+    }
+
+    /// Suggest the weight for the next attempt at `lift` in a meet
+    ///
+    /// Opens with the strategy's opener. After a good lift it moves on to the
+    /// strategy's next attempt, or 2.5 kg more if that's no heavier; after a
+    /// miss it repeats the weight. Returns None once all attempts are taken.
+    pub fn suggest_next_attempt(
+        &self,
+        meet_id: String,
+        lift: CompetitionLift,
+        strategy: AttemptStrategy,
+        at: i64,
+    ) -> Result<Option<f64>, WeightliftingError> {
+        info!("🎯 Suggesting next {:?} attempt in meet {}", lift, meet_id);
+
+        let conn = self.connection()?;
+        let meet = Self::query_meet(&conn, &meet_id)?;
+        let last_taken = meet
+            .attempts_for(lift)
+            .into_iter()
+            .rfind(|a| a.status != AttemptStatus::Planned)
+            .cloned();
+        if last_taken.as_ref().is_some_and(|a| a.attempt_number >= ATTEMPTS_PER_LIFT) {
+            debug!("⏹️ All {:?} attempts taken", lift);
+            return Ok(None);
+        }
+
+        let (_, estimated_max_kg) = Self::recent_estimated_max(&conn, lift, at)?;
+        let planned = plan_attempts(estimated_max_kg, strategy);
+        let next_kg = match last_taken {
+            None => planned[0],
+            Some(last) if last.status == AttemptStatus::NoLift => last.weight_kg,
+            Some(last) => planned[last.attempt_number as usize].max(last.weight_kg + MEET_INCREMENT_KG),
+        };
+
+        info!("✅ Next {:?} attempt: {} kg", lift, next_kg);
+        Ok(Some(next_kg))
+    }
+
+    /// The profile's exercise for `lift` and its best estimated 1RM in the window before `at`
+    fn recent_estimated_max(conn: &Connection, lift: CompetitionLift, at: i64) -> Result<(String, f64), WeightliftingError> {
+        let exercise_id = Self::query_lifter_profile(conn)?
+            .and_then(|profile| profile.exercise_for(lift).cloned())
+            .ok_or_else(|| {
+                warn!("❌ No exercise set for {:?} in the lifter profile", lift);
+                WeightliftingError::InsufficientData {
+                    message: format!("Set the {:?} exercise in the lifter profile to plan attempts", lift),
+                }
+            })?;
+
+        let sets = Self::query_sets_between(conn, Some(&exercise_id), at - RECENT_MAX_WINDOW_SECONDS, at)?;
+        let estimated_max_kg = sets.iter().filter_map(estimate_logged_one_rm).reduce(f64::max).ok_or_else(|| {
+            warn!("❌ No recent sets of {} to estimate a max from", exercise_id);
+            WeightliftingError::InsufficientData {
+                message: format!("No recent sets of {} to estimate a max from", exercise_id),
+            }
+        })?;
+
+        debug!("📈 Recent estimated max for {:?}: {} kg", lift, estimated_max_kg);
+        Ok((exercise_id, estimated_max_kg))
+    }
+
+    fn write_meet(conn: &Connection, meet: &Meet) -> Result<(), WeightliftingError> {
+        meet.validate().map_err(|message| {
+            warn!("❌ Invalid meet: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let attempts_json = serde_json::to_string(&meet.attempts).map_err(|e| {
+            error!("❌ Failed to serialize meet attempts: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize meet attempts: {}", e),
+            }
+        })?;
+
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO meets ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                MEET_COLUMNS
+            ),
+            params![
+                meet.id,
+                meet.name,
+                meet.date.to_basic_string(),
+                meet.federation,
+                meet.weight_class,
+                meet.equipment.as_str(),
+                meet.bodyweight_kg,
+                attempts_json,
+                meet.notes
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn query_meet(conn: &Connection, id: &str) -> Result<Meet, WeightliftingError> {
+        conn.query_row(
+            &format!("SELECT {} FROM meets WHERE id = ?1", MEET_COLUMNS),
+            [id],
+            meet_from_row,
+        ).map_err(|e| {
+            warn!("❌ Meet not found with ID '{}': {}", id, e);
+            WeightliftingError::RecordNotFound { id: id.to_string() }
+        })
+    }
+
+    /// Create the meets table if it doesn't exist
+    pub(crate) fn create_meet_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating meets table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS meets (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                date TEXT NOT NULL,
+                federation TEXT NOT NULL,
+                weight_class TEXT NOT NULL,
+                equipment TEXT NOT NULL,
+                bodyweight_kg REAL,
+                attempts TEXT NOT NULL,
+                notes TEXT
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create meets table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Meet table ready");
+        Ok(())
+    }
+}
+
+fn meet_from_row(row: &Row) -> rusqlite::Result<Meet> {
+    let date_text: String = row.get(2)?;
+    let date = CalendarDate::parse_basic(&date_text)
+        .ok_or_else(|| rusqlite::Error::InvalidColumnType(2, "date".to_string(), rusqlite::types::Type::Text))?;
+    let equipment_name: String = row.get(5)?;
+    let equipment = CompetitionEquipment::parse(&equipment_name)
+        .ok_or_else(|| rusqlite::Error::InvalidColumnType(5, "equipment".to_string(), rusqlite::types::Type::Text))?;
+    let attempts_json: String = row.get(7)?;
+    let attempts: Vec<MeetAttempt> = serde_json::from_str(&attempts_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(7, "attempts".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(Meet {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        date,
+        federation: row.get(3)?,
+        weight_class: row.get(4)?,
+        equipment,
+        bodyweight_kg: row.get(6)?,
+        attempts,
+        notes: row.get(8)?,
+    })
+}
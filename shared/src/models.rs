@@ -1,3 +1,4 @@
+use crate::muscles::MuscleInvolvement;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use log::{info, debug, warn};
@@ -9,8 +10,14 @@ pub struct Exercise {
     pub name: String,
     pub description: Option<String>,
     pub muscle_groups: Vec<String>,
-    pub equipment_needed: Option<String>,
+    pub equipment_ids: Vec<String>, // Equipment IDs, empty for bodyweight
     pub difficulty_level: u8, // 1-10 scale
+    #[serde(default)]
+    pub muscle_involvement: Vec<MuscleInvolvement>, // Muscle groups without an entry count as primary
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
 }
 
 impl Exercise {
@@ -20,14 +27,14 @@ impl Exercise {
     /// * `name` - Name of the exercise
     /// * `description` - Optional description
     /// * `muscle_groups` - List of muscle groups targeted
-    /// * `equipment_needed` - Optional equipment required
+    /// * `equipment_ids` - IDs of the equipment required
     /// * `difficulty_level` - Difficulty from 1-10 (will be clamped)
     pub fn new(
         id: String,
         name: String,
         description: Option<String>,
         muscle_groups: Vec<String>,
-        equipment_needed: Option<String>,
+        equipment_ids: Vec<String>,
         difficulty_level: u8,
     ) -> Self {
         debug!("🏗️ Creating new exercise: {}", name);
@@ -47,10 +54,10 @@ impl Exercise {
         }
 
         // Log equipment requirements
-        if let Some(ref equipment) = equipment_needed {
-            debug!("🏋️ Exercise '{}' requires equipment: {}", name, equipment);
-        } else {
+        if equipment_ids.is_empty() {
             debug!("🤸 Exercise '{}' is bodyweight (no equipment)", name);
+        } else {
+            debug!("🏋️ Exercise '{}' requires equipment: {:?}", name, equipment_ids);
         }
 
         let exercise = Self {
@@ -58,8 +65,11 @@ impl Exercise {
             name: name.clone(),
             description,
             muscle_groups,
-            equipment_needed,
+            equipment_ids,
             difficulty_level: clamped_difficulty,
+            muscle_involvement: Vec::new(),
+            tags: Vec::new(),
+            is_favorite: false,
         };
 
         info!("✅ Created exercise: {} (ID: {}, Difficulty: {})",
@@ -74,19 +84,24 @@ impl Exercise {
     /// * `name` - Name of the exercise
     /// * `description` - Optional description
     /// * `muscle_groups` - List of muscle groups targeted
-    /// * `equipment_needed` - Optional equipment required
+    /// * `equipment_ids` - IDs of the equipment required
     /// * `difficulty_level` - Difficulty from 1-10 (will be clamped)
     pub fn new_with_uuid(
         name: String,
         description: Option<String>,
         muscle_groups: Vec<String>,
-        equipment_needed: Option<String>,
+        equipment_ids: Vec<String>,
         difficulty_level: u8,
     ) -> Self {
         let id = Uuid::new_v4().to_string();
         debug!("🆔 Generated UUID for exercise '{}': {}", name, id);
 
-        Self::new(id, name, description, muscle_groups, equipment_needed, difficulty_level)
+        Self::new(id, name, description, muscle_groups, equipment_ids, difficulty_level)
+    }
+
+    /// Describe how much each muscle group is involved
+    pub fn with_muscle_involvement(self, muscle_involvement: Vec<MuscleInvolvement>) -> Self {
+        Self { muscle_involvement, ..self }
     }
 
     /// Validate that the exercise has all required fields
@@ -129,11 +144,250 @@ impl Exercise {
 
     /// Check if this exercise requires equipment
     pub fn requires_equipment(&self) -> bool {
-        self.equipment_needed.is_some()
+        !self.equipment_ids.is_empty()
     }
 
     /// Get the number of muscle groups targeted
     pub fn muscle_group_count(&self) -> usize {
         self.muscle_groups.len()
     }
-}
\ No newline at end of file
+}
+
+/// What kind of set was performed, which decides the fields it uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetType {
+    #[default]
+    Standard,
+    DropSet,            // `drops` lists the lighter weights after the first
+    TimedHold,          // `duration_seconds` is how long the position was held
+    WeightedBodyweight, // `added_load_kg` is on top of bodyweight, `weight_kg` is 0
+    AssistedBodyweight, // `assistance_kg` is taken off bodyweight, `weight_kg` is 0
+}
+
+/// One weight reduction within a drop set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DropSegment {
+    pub weight_kg: f64,
+    pub reps: u32,
+}
+
+/// A single set performed for an exercise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedSet {
+    pub id: String,
+    pub exercise_id: String,
+    pub weight_kg: f64,
+    pub reps: u32,
+    pub rpe: Option<f64>,  // Rate of perceived exertion, 1-10 in half steps; below 6 is off the RPE chart
+    pub rir: Option<u32>,  // Reps in reserve; above 4 is off the RPE chart
+    pub performed_at: i64, // Unix timestamp in seconds
+    pub session_id: Option<String>, // Workout session the set belongs to
+    #[serde(default)]
+    pub set_type: SetType,
+    #[serde(default)]
+    pub is_amrap: bool, // Reps were as many as possible
+    #[serde(default)]
+    pub duration_seconds: Option<u32>,
+    #[serde(default)]
+    pub added_load_kg: Option<f64>,
+    #[serde(default)]
+    pub assistance_kg: Option<f64>,
+    #[serde(default)]
+    pub drops: Vec<DropSegment>,
+}
+
+impl SetType {
+    /// Stable name used in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SetType::Standard => "standard",
+            SetType::DropSet => "drop_set",
+            SetType::TimedHold => "timed_hold",
+            SetType::WeightedBodyweight => "weighted_bodyweight",
+            SetType::AssistedBodyweight => "assisted_bodyweight",
+        }
+    }
+
+    /// Parse a database name back into a set type
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            SetType::Standard,
+            SetType::DropSet,
+            SetType::TimedHold,
+            SetType::WeightedBodyweight,
+            SetType::AssistedBodyweight,
+        ]
+        .into_iter()
+        .find(|set_type| set_type.as_str() == name)
+    }
+
+    /// Whether the lifted load includes the lifter's bodyweight
+    pub fn uses_bodyweight(&self) -> bool {
+        matches!(self, SetType::WeightedBodyweight | SetType::AssistedBodyweight)
+    }
+}
+
+impl LoggedSet {
+    /// Create a new standard logged set with automatic UUID generation
+    ///
+    /// RPE, RIR and session are left empty; set them on the returned value when known.
+    pub fn new(exercise_id: String, weight_kg: f64, reps: u32, performed_at: i64) -> Self {
+        let set = Self {
+            id: Uuid::new_v4().to_string(),
+            exercise_id,
+            weight_kg,
+            reps,
+            rpe: None,
+            rir: None,
+            performed_at,
+            session_id: None,
+            set_type: SetType::Standard,
+            is_amrap: false,
+            duration_seconds: None,
+            added_load_kg: None,
+            assistance_kg: None,
+            drops: Vec::new(),
+        };
+        debug!("🏗️ Created set: {} x {} kg for exercise {}", set.reps, set.weight_kg, set.exercise_id);
+        set
+    }
+
+    /// Validate that the set values are within sensible ranges
+    ///
+    /// RPEs below 6 (or more than 4 reps in reserve) are accepted as a record
+    /// of easy sets, but they are off the RPE chart, so such sets are left out
+    /// of one-rep max estimates and load suggestions.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.exercise_id.trim().is_empty() {
+            return Err("Set must reference an exercise".to_string());
+        }
+
+        if !self.weight_kg.is_finite() || self.weight_kg < 0.0 {
+            return Err(format!("Weight must be a non-negative number, got {}", self.weight_kg));
+        }
+
+        if self.reps == 0 {
+            return Err("Set must have at least one rep".to_string());
+        }
+
+        if let Some(rpe) = self.rpe {
+            if !(1.0..=10.0).contains(&rpe) || (rpe * 2.0).fract() != 0.0 {
+                return Err(format!("RPE must be between 1 and 10 in half steps, got {}", rpe));
+            }
+        }
+
+        if let Some(rir) = self.rir {
+            if rir > 10 {
+                return Err(format!("RIR must be between 0 and 10, got {}", rir));
+            }
+        }
+
+        self.validate_set_type()
+    }
+
+    /// Check that the type-specific fields match the set type
+    fn validate_set_type(&self) -> Result<(), String> {
+        let set_type = self.set_type;
+        let type_fields = [
+            (SetType::DropSet, !self.drops.is_empty(), "drops"),
+            (SetType::TimedHold, self.duration_seconds.is_some(), "a duration"),
+            (SetType::WeightedBodyweight, self.added_load_kg.is_some(), "added load"),
+            (SetType::AssistedBodyweight, self.assistance_kg.is_some(), "assistance"),
+        ];
+        for (owner, present, field) in type_fields {
+            if present && set_type != owner {
+                return Err(format!("{:?} sets cannot have {}", set_type, field));
+            }
+            if !present && set_type == owner {
+                return Err(format!("{:?} sets need {}", set_type, field));
+            }
+        }
+
+        if set_type.uses_bodyweight() && self.weight_kg != 0.0 {
+            return Err(format!("{:?} sets carry their load outside weight_kg, which must be 0", set_type));
+        }
+
+        if self.is_amrap && set_type == SetType::TimedHold {
+            return Err("Timed holds cannot be AMRAP".to_string());
+        }
+
+        if self.duration_seconds == Some(0) {
+            return Err("Hold duration must be at least one second".to_string());
+        }
+
+        for (label, load) in [("Added load", self.added_load_kg), ("Assistance", self.assistance_kg)] {
+            if let Some(load) = load.filter(|l| !l.is_finite() || *l <= 0.0) {
+                return Err(format!("{} must be positive, got {}", label, load));
+            }
+        }
+
+        let mut previous_kg = self.weight_kg;
+        for (i, drop) in self.drops.iter().enumerate() {
+            if !drop.weight_kg.is_finite() || drop.weight_kg < 0.0 || drop.weight_kg >= previous_kg {
+                return Err(format!("Drop {} must be lighter than the weight before it, got {} kg", i + 1, drop.weight_kg));
+            }
+            if drop.reps == 0 {
+                return Err(format!("Drop {} must have at least one rep", i + 1));
+            }
+            previous_kg = drop.weight_kg;
+        }
+
+        Ok(())
+    }
+
+    /// Get the effort of the set on the RPE scale
+    ///
+    /// Uses the logged RPE when present, otherwise derives it from RIR (RPE = 10 - RIR).
+    pub fn effective_rpe(&self) -> Option<f64> {
+        self.rpe.or_else(|| self.rir.map(|rir| 10.0 - rir as f64))
+    }
+
+    /// Get the key identifying the session this set was performed in
+    ///
+    /// Sets logged without a session are grouped by UTC calendar day.
+    pub fn session_key(&self) -> String {
+        match self.session_id {
+            Some(ref session_id) => session_id.clone(),
+            None => format!("day:{}", self.performed_at.div_euclid(86_400)),
+        }
+    }
+
+    /// Get the training volume of the set, without bodyweight
+    ///
+    /// Same as `volume_with_bodyweight(None)`.
+    pub fn volume(&self) -> f64 {
+        self.volume_with_bodyweight(None)
+    }
+
+    /// Get the training volume of the set for a lifter of `bodyweight_kg`
+    ///
+    /// Weight x reps, plus every drop of a drop set. Bodyweight sets move the
+    /// bodyweight plus added load, or minus assistance; when bodyweight is
+    /// unknown only the added load counts. Timed holds add no volume.
+    pub fn volume_with_bodyweight(&self, bodyweight_kg: Option<f64>) -> f64 {
+        let reps = self.reps as f64;
+        match self.set_type {
+            SetType::Standard => self.weight_kg * reps,
+            SetType::DropSet => {
+                self.weight_kg * reps + self.drops.iter().map(|d| d.weight_kg * d.reps as f64).sum::<f64>()
+            }
+            SetType::TimedHold => 0.0,
+            SetType::WeightedBodyweight => {
+                (bodyweight_kg.unwrap_or(0.0) + self.added_load_kg.unwrap_or(0.0)) * reps
+            }
+            SetType::AssistedBodyweight => {
+                (bodyweight_kg.unwrap_or(0.0) - self.assistance_kg.unwrap_or(0.0)).max(0.0) * reps
+            }
+        }
+    }
+
+    /// Get the total reps of the set, including every drop
+    pub fn total_reps(&self) -> u32 {
+        self.reps + self.drops.iter().map(|d| d.reps).sum::<u32>()
+    }
+
+    /// Whether weight and reps describe a lift comparable with other sets for strength estimates
+    pub fn is_strength_comparable(&self) -> bool {
+        matches!(self.set_type, SetType::Standard | SetType::DropSet)
+    }
+}
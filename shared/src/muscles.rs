@@ -0,0 +1,462 @@
+// Canonical muscle group taxonomy and normalization of free-text muscle names
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Settings key recording that stored muscle groups have been normalized
+const MUSCLE_MIGRATION_SETTING: &str = "muscle_groups_normalized";
+
+/// Region of the body a muscle group belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BodyRegion {
+    Chest,
+    Back,
+    Shoulders,
+    Arms,
+    Core,
+    Legs,
+}
+
+/// A muscle group from the controlled vocabulary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MuscleGroup {
+    Chest,
+    Lats,
+    UpperBack,
+    Traps,
+    LowerBack,
+    Shoulders,
+    Biceps,
+    Triceps,
+    Forearms,
+    Abs,
+    Obliques,
+    Quadriceps,
+    Hamstrings,
+    Glutes,
+    Adductors,
+    Abductors,
+    Calves,
+}
+
+/// A muscle group with its display name and body region
+#[derive(Debug, Clone, PartialEq)]
+pub struct MuscleGroupInfo {
+    pub muscle_group: MuscleGroup,
+    pub name: String,
+    pub region: BodyRegion,
+}
+
+/// How a muscle group contributes to an exercise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MuscleRole {
+    Primary,
+    Secondary,
+    Stabilizer,
+}
+
+/// The role and share of the work of one muscle group in an exercise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MuscleInvolvement {
+    pub muscle_group: MuscleGroup,
+    pub role: MuscleRole,
+    pub weight: Option<f64>, // 0-1 fraction of a set credited to the muscle, None for the role's default
+}
+
+/// Training volume credited to a muscle group
+#[derive(Debug, Clone, PartialEq)]
+pub struct MuscleVolume {
+    pub muscle_group: String,
+    pub weighted_sets: f64,
+    pub weighted_volume_kg: f64,
+}
+
+/// Alternative spellings mapped to their canonical group, compared after `simplify`
+const SYNONYMS: [(&str, MuscleGroup); 51] = [
+    ("pecs", MuscleGroup::Chest),
+    ("pec", MuscleGroup::Chest),
+    ("pectorals", MuscleGroup::Chest),
+    ("pectoralis major", MuscleGroup::Chest),
+    ("lat", MuscleGroup::Lats),
+    ("latissimus dorsi", MuscleGroup::Lats),
+    ("back", MuscleGroup::UpperBack),
+    ("mid back", MuscleGroup::UpperBack),
+    ("middle back", MuscleGroup::UpperBack),
+    ("rhomboids", MuscleGroup::UpperBack),
+    ("trap", MuscleGroup::Traps),
+    ("trapezius", MuscleGroup::Traps),
+    ("erectors", MuscleGroup::LowerBack),
+    ("spinal erectors", MuscleGroup::LowerBack),
+    ("erector spinae", MuscleGroup::LowerBack),
+    ("shoulder", MuscleGroup::Shoulders),
+    ("delts", MuscleGroup::Shoulders),
+    ("delt", MuscleGroup::Shoulders),
+    ("deltoids", MuscleGroup::Shoulders),
+    ("front delts", MuscleGroup::Shoulders),
+    ("side delts", MuscleGroup::Shoulders),
+    ("rear delts", MuscleGroup::Shoulders),
+    ("bicep", MuscleGroup::Biceps),
+    ("biceps brachii", MuscleGroup::Biceps),
+    ("tricep", MuscleGroup::Triceps),
+    ("triceps brachii", MuscleGroup::Triceps),
+    ("forearm", MuscleGroup::Forearms),
+    ("grip", MuscleGroup::Forearms),
+    ("ab", MuscleGroup::Abs),
+    ("abdominals", MuscleGroup::Abs),
+    ("rectus abdominis", MuscleGroup::Abs),
+    ("core", MuscleGroup::Abs),
+    ("oblique", MuscleGroup::Obliques),
+    ("quads", MuscleGroup::Quadriceps),
+    ("quad", MuscleGroup::Quadriceps),
+    ("quadricep", MuscleGroup::Quadriceps),
+    ("hams", MuscleGroup::Hamstrings),
+    ("hamstring", MuscleGroup::Hamstrings),
+    ("glute", MuscleGroup::Glutes),
+    ("gluteus", MuscleGroup::Glutes),
+    ("gluteus maximus", MuscleGroup::Glutes),
+    ("adductor", MuscleGroup::Adductors),
+    ("inner thigh", MuscleGroup::Adductors),
+    ("inner thighs", MuscleGroup::Adductors),
+    ("abductor", MuscleGroup::Abductors),
+    ("hip abductors", MuscleGroup::Abductors),
+    ("outer thigh", MuscleGroup::Abductors),
+    ("outer thighs", MuscleGroup::Abductors),
+    ("calf", MuscleGroup::Calves),
+    ("gastrocnemius", MuscleGroup::Calves),
+    ("soleus", MuscleGroup::Calves),
+];
+
+impl MuscleGroup {
+    pub const ALL: [MuscleGroup; 17] = [
+        MuscleGroup::Chest,
+        MuscleGroup::Lats,
+        MuscleGroup::UpperBack,
+        MuscleGroup::Traps,
+        MuscleGroup::LowerBack,
+        MuscleGroup::Shoulders,
+        MuscleGroup::Biceps,
+        MuscleGroup::Triceps,
+        MuscleGroup::Forearms,
+        MuscleGroup::Abs,
+        MuscleGroup::Obliques,
+        MuscleGroup::Quadriceps,
+        MuscleGroup::Hamstrings,
+        MuscleGroup::Glutes,
+        MuscleGroup::Adductors,
+        MuscleGroup::Abductors,
+        MuscleGroup::Calves,
+    ];
+
+    /// Canonical name stored in `Exercise::muscle_groups`
+    pub fn name(&self) -> &'static str {
+        match self {
+            MuscleGroup::Chest => "Chest",
+            MuscleGroup::Lats => "Lats",
+            MuscleGroup::UpperBack => "Upper Back",
+            MuscleGroup::Traps => "Traps",
+            MuscleGroup::LowerBack => "Lower Back",
+            MuscleGroup::Shoulders => "Shoulders",
+            MuscleGroup::Biceps => "Biceps",
+            MuscleGroup::Triceps => "Triceps",
+            MuscleGroup::Forearms => "Forearms",
+            MuscleGroup::Abs => "Abs",
+            MuscleGroup::Obliques => "Obliques",
+            MuscleGroup::Quadriceps => "Quadriceps",
+            MuscleGroup::Hamstrings => "Hamstrings",
+            MuscleGroup::Glutes => "Glutes",
+            MuscleGroup::Adductors => "Adductors",
+            MuscleGroup::Abductors => "Abductors",
+            MuscleGroup::Calves => "Calves",
+        }
+    }
+
+    /// Body region containing the muscle group
+    pub fn region(&self) -> BodyRegion {
+        match self {
+            MuscleGroup::Chest => BodyRegion::Chest,
+            MuscleGroup::Lats | MuscleGroup::UpperBack | MuscleGroup::Traps | MuscleGroup::LowerBack => BodyRegion::Back,
+            MuscleGroup::Shoulders => BodyRegion::Shoulders,
+            MuscleGroup::Biceps | MuscleGroup::Triceps | MuscleGroup::Forearms => BodyRegion::Arms,
+            MuscleGroup::Abs | MuscleGroup::Obliques => BodyRegion::Core,
+            MuscleGroup::Quadriceps
+            | MuscleGroup::Hamstrings
+            | MuscleGroup::Glutes
+            | MuscleGroup::Adductors
+            | MuscleGroup::Abductors
+            | MuscleGroup::Calves => BodyRegion::Legs,
+        }
+    }
+}
+
+impl MuscleRole {
+    /// Fraction of a set credited to a muscle in this role when no weight is given
+    pub fn default_weight(&self) -> f64 {
+        match self {
+            MuscleRole::Primary => 1.0,
+            MuscleRole::Secondary => 0.5,
+            MuscleRole::Stabilizer => 0.25,
+        }
+    }
+}
+
+impl MuscleInvolvement {
+    /// Involvement with the role's default weight
+    pub fn new(muscle_group: MuscleGroup, role: MuscleRole) -> Self {
+        Self { muscle_group, role, weight: None }
+    }
+
+    /// Fraction of a set credited to the muscle
+    pub fn effective_weight(&self) -> f64 {
+        self.weight.unwrap_or_else(|| self.role.default_weight())
+    }
+}
+
+impl Exercise {
+    /// Each targeted muscle group with the fraction of a set credited to it
+    ///
+    /// Groups listed only in `muscle_groups` count fully, as before roles existed.
+    pub fn muscle_weights(&self) -> Vec<(String, f64)> {
+        self.muscle_groups
+            .iter()
+            .map(|name| {
+                let weight = self.muscle_involvement
+                    .iter()
+                    .find(|involvement| involvement.muscle_group.name() == name)
+                    .map_or(1.0, MuscleInvolvement::effective_weight);
+                (name.clone(), weight)
+            })
+            .collect()
+    }
+}
+
+/// Lowercase and collapse punctuation and whitespace ("Upper-Back " -> "upper back")
+fn simplify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Map a muscle name or synonym to its canonical group, None if unrecognized
+pub fn normalize_muscle_group(name: &str) -> Option<MuscleGroup> {
+    let simplified = simplify(name);
+    MuscleGroup::ALL
+        .into_iter()
+        .find(|group| simplify(group.name()) == simplified)
+        .or_else(|| SYNONYMS.iter().find(|(synonym, _)| *synonym == simplified).map(|&(_, group)| group))
+}
+
+/// Every muscle group with its display name and region, grouped by region
+pub fn muscle_taxonomy() -> Vec<MuscleGroupInfo> {
+    MuscleGroup::ALL
+        .into_iter()
+        .map(|muscle_group| MuscleGroupInfo {
+            muscle_group,
+            name: muscle_group.name().to_string(),
+            region: muscle_group.region(),
+        })
+        .collect()
+}
+
+/// Rewrite muscle names to their canonical form, dropping duplicates
+///
+/// Unrecognized names are returned as the error.
+pub(crate) fn canonical_muscle_groups(names: &[String]) -> Result<Vec<String>, Vec<String>> {
+    let mut canonical: Vec<String> = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        match normalize_muscle_group(name) {
+            Some(group) if !canonical.iter().any(|c| c == group.name()) => canonical.push(group.name().to_string()),
+            Some(_) => {}
+            None => unknown.push(name.clone()),
+        }
+    }
+    if unknown.is_empty() { Ok(canonical) } else { Err(unknown) }
+}
+
+/// Canonicalize an exercise's muscle groups and check its involvement entries
+///
+/// Every muscle with an involvement entry is added to `muscle_groups` so
+/// plain-name queries keep seeing it.
+pub(crate) fn normalize_exercise_muscles(exercise: Exercise) -> Result<Exercise, WeightliftingError> {
+    let mut muscle_groups = canonical_muscle_groups(&exercise.muscle_groups).map_err(|unknown| {
+        warn!("❌ Unrecognized muscle groups: {:?}", unknown);
+        WeightliftingError::InvalidInput {
+            message: format!("Unrecognized muscle groups: {}", unknown.join(", ")),
+        }
+    })?;
+
+    for (index, involvement) in exercise.muscle_involvement.iter().enumerate() {
+        let name = involvement.muscle_group.name();
+        if exercise.muscle_involvement[..index].iter().any(|other| other.muscle_group == involvement.muscle_group) {
+            warn!("❌ Duplicate involvement for {}", name);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Muscle group {} is listed more than once", name),
+            });
+        }
+
+        let weight = involvement.effective_weight();
+        if !(weight > 0.0 && weight <= 1.0) {
+            warn!("❌ Invalid involvement weight {} for {}", weight, name);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Involvement weight for {} must be above 0 and at most 1, got {}", name, weight),
+            });
+        }
+
+        if !muscle_groups.iter().any(|group| group == name) {
+            muscle_groups.push(name.to_string());
+        }
+    }
+
+    Ok(Exercise { muscle_groups, ..exercise })
+}
+
+impl ExerciseRepository {
+    /// Credit the sets logged between `start` and `end` (inclusive) to muscle groups, most trained first
+    ///
+    /// Each set counts toward every muscle its exercise targets, scaled by the
+    /// muscle's involvement weight.
+    pub fn get_muscle_volume(&self, start: i64, end: i64) -> Result<Vec<MuscleVolume>, WeightliftingError> {
+        info!("💪 Calculating muscle volume between {} and {}", start, end);
+
+        let conn = self.connection()?;
+        let sets = Self::query_sets_between(&conn, None, start, end)?;
+
+        let mut weights_by_exercise: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+        let mut totals: HashMap<String, (f64, f64)> = HashMap::new();
+        for set in &sets {
+            if !weights_by_exercise.contains_key(&set.exercise_id) {
+                let weights = match Self::query_exercise(&conn, &set.exercise_id) {
+                    Ok(exercise) => exercise.muscle_weights(),
+                    Err(_) => {
+                        warn!("⚠️ Skipping sets of deleted exercise {}", set.exercise_id);
+                        Vec::new()
+                    }
+                };
+                weights_by_exercise.insert(set.exercise_id.clone(), weights);
+            }
+
+            let volume = Self::set_volume(&conn, set)?;
+            for (muscle_group, weight) in &weights_by_exercise[&set.exercise_id] {
+                let (weighted_sets, weighted_volume) = totals.entry(muscle_group.clone()).or_insert((0.0, 0.0));
+                *weighted_sets += weight;
+                *weighted_volume += volume * weight;
+            }
+        }
+
+        let mut volumes: Vec<MuscleVolume> = totals
+            .into_iter()
+            .map(|(muscle_group, (weighted_sets, weighted_volume_kg))| MuscleVolume {
+                muscle_group,
+                weighted_sets,
+                weighted_volume_kg,
+            })
+            .collect();
+        volumes.sort_by(|a, b| {
+            b.weighted_sets
+                .total_cmp(&a.weighted_sets)
+                .then_with(|| a.muscle_group.cmp(&b.muscle_group))
+        });
+
+        info!("✅ Credited {} sets to {} muscle groups", sets.len(), volumes.len());
+        Ok(volumes)
+    }
+
+    /// Get the exercises that train any muscle group in a body region, sorted by name
+    pub fn get_exercises_for_region(&self, region: BodyRegion) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("💪 Retrieving exercises for body region {:?}", region);
+
+        let names: Vec<&str> = MuscleGroup::ALL
+            .into_iter()
+            .filter(|group| group.region() == region)
+            .map(|group| group.name())
+            .collect();
+        let names_json = serde_json::to_string(&names).map_err(|e| WeightliftingError::DatabaseError {
+            message: format!("Failed to serialize muscle groups: {}", e),
+        })?;
+
+        let conn = self.connection()?;
+        let exercises = Self::query_exercises(
+            &conn,
+            "WHERE EXISTS (
+                SELECT 1 FROM json_each(exercises.muscle_groups) muscle
+                WHERE muscle.value IN (SELECT value FROM json_each(?1))
+            ) ORDER BY name",
+            [&names_json],
+        )?;
+
+        info!("✅ Found {} exercises for {:?}", exercises.len(), region);
+        Ok(exercises)
+    }
+
+    /// Rewrite stored muscle groups to canonical names, once per database
+    ///
+    /// Names that can't be recognized are kept as they are so no data is lost.
+    pub(crate) fn migrate_muscle_groups(conn: &Connection) -> Result<(), WeightliftingError> {
+        let migrated: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [MUSCLE_MIGRATION_SETTING],
+            |row| row.get(0),
+        ).optional()?;
+        if migrated.is_some() {
+            return Ok(());
+        }
+
+        info!("🔧 Normalizing stored muscle groups");
+        let rows: Vec<(String, String)> = {
+            let mut stmt = conn.prepare("SELECT id, muscle_groups FROM exercises")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        for (exercise_id, muscle_groups_json) in rows {
+            let Ok(names) = serde_json::from_str::<Vec<String>>(&muscle_groups_json) else {
+                warn!("⚠️ Skipping exercise {} with unreadable muscle groups", exercise_id);
+                continue;
+            };
+
+            let mut normalized: Vec<String> = Vec::new();
+            for name in &names {
+                let name = match normalize_muscle_group(name) {
+                    Some(group) => group.name().to_string(),
+                    None => {
+                        warn!("⚠️ Keeping unrecognized muscle group '{}' on exercise {}", name, exercise_id);
+                        name.clone()
+                    }
+                };
+                if !normalized.contains(&name) {
+                    normalized.push(name);
+                }
+            }
+
+            if normalized != names {
+                debug!("🔧 Exercise {}: {:?} -> {:?}", exercise_id, names, normalized);
+                let normalized_json = serde_json::to_string(&normalized).map_err(|e| WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize muscle groups: {}", e),
+                })?;
+                tx.execute(
+                    "UPDATE exercises SET muscle_groups = ?1 WHERE id = ?2",
+                    params![normalized_json, exercise_id],
+                )?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, '1')",
+            [MUSCLE_MIGRATION_SETTING],
+        )?;
+        tx.commit()?;
+
+        info!("✅ Muscle groups normalized");
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+// Progressive overload rules: declarative per-exercise progression schemes
+// and an evaluator that turns the logged session history into the next prescription
+
+use crate::error::WeightliftingError;
+use crate::models::LoggedSet;
+use crate::repository::ExerciseRepository;
+use crate::rpe::round_to_increment;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Number of recent sets scanned when reconstructing sessions
+const SESSION_HISTORY_SETS: u32 = 500;
+
+/// How the working load moves from one session to the next
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProgressionScheme {
+    /// Add `increment_kg` whenever every set hits the target reps
+    Linear { sets: u32, reps: u32, increment_kg: f64 },
+    /// Add reps within the range, then add `increment_kg` once every set reaches `max_reps`
+    DoubleProgression { sets: u32, min_reps: u32, max_reps: u32, increment_kg: f64 },
+}
+
+/// A progression rule attached to an exercise
+///
+/// Each database belongs to a single user, so rules are keyed by exercise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressionRule {
+    pub exercise_id: String,
+    pub scheme: ProgressionScheme,
+    pub starting_weight_kg: f64,    // Prescribed when nothing has been logged yet
+    pub deload_after_failures: u32, // Consecutive failed sessions before a deload, 0 disables deloads
+    pub deload_percentage: f64,     // How much to take off the bar when deloading
+}
+
+/// Why the evaluator prescribed what it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressionDecision {
+    Start,
+    Increase,
+    Repeat,
+    Deload,
+}
+
+/// The next session's work for an exercise
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prescription {
+    pub exercise_id: String,
+    pub weight_kg: f64,
+    pub sets: u32,
+    pub min_reps: u32,
+    pub max_reps: u32,
+    pub decision: ProgressionDecision,
+    pub explanation: String,
+    pub sessions_evaluated: u32,
+}
+
+/// Outcome of a single past session under a rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SessionOutcome {
+    working_weight_kg: f64,
+    working_sets: u32,
+    lowest_reps: u32,
+    succeeded: bool,
+    reached_top_of_range: bool,
+}
+
+impl ProgressionRule {
+    /// Validate that the rule parameters are usable
+    pub fn validate(&self) -> Result<(), String> {
+        let (sets, min_reps, max_reps, increment_kg) = self.scheme.parameters();
+
+        if sets == 0 || min_reps == 0 {
+            return Err("Progression rule needs at least one set and one rep".to_string());
+        }
+
+        if min_reps > max_reps {
+            return Err(format!("Rep range {}-{} is empty", min_reps, max_reps));
+        }
+
+        if !increment_kg.is_finite() || increment_kg <= 0.0 {
+            return Err(format!("Increment must be positive, got {}", increment_kg));
+        }
+
+        if !self.starting_weight_kg.is_finite() || self.starting_weight_kg < 0.0 {
+            return Err(format!("Starting weight must be non-negative, got {}", self.starting_weight_kg));
+        }
+
+        if !(0.0..100.0).contains(&self.deload_percentage) {
+            return Err(format!("Deload percentage must be between 0 and 100, got {}", self.deload_percentage));
+        }
+
+        Ok(())
+    }
+}
+
+impl ProgressionScheme {
+    /// Get (sets, min reps, max reps, increment) for the scheme
+    fn parameters(&self) -> (u32, u32, u32, f64) {
+        match *self {
+            ProgressionScheme::Linear { sets, reps, increment_kg } => (sets, reps, reps, increment_kg),
+            ProgressionScheme::DoubleProgression { sets, min_reps, max_reps, increment_kg } => {
+                (sets, min_reps, max_reps, increment_kg)
+            }
+        }
+    }
+
+    /// Judge one session's sets (all for the same exercise) against the scheme
+    fn judge(&self, session: &[&LoggedSet]) -> SessionOutcome {
+        let (sets, min_reps, max_reps, _) = self.parameters();
+
+        let working_weight_kg = session.iter().map(|s| s.weight_kg).fold(0.0, f64::max);
+        let working: Vec<u32> = session
+            .iter()
+            .filter(|s| s.weight_kg >= working_weight_kg)
+            .map(|s| s.reps)
+            .collect();
+
+        let sets_at_min = working.iter().filter(|&&reps| reps >= min_reps).count() as u32;
+        let sets_at_max = working.iter().filter(|&&reps| reps >= max_reps).count() as u32;
+
+        SessionOutcome {
+            working_weight_kg,
+            working_sets: working.len() as u32,
+            lowest_reps: working.iter().copied().min().unwrap_or(0),
+            succeeded: sets_at_min >= sets,
+            reached_top_of_range: sets_at_max >= sets,
+        }
+    }
+}
+
+/// Decide the next prescription from a rule and past sessions, newest first
+///
+/// Weights are rounded to the scheme's own increment, so every load it
+/// prescribes can be reached in steps the rule actually takes. Only misses at
+/// the latest working weight count towards a deload, so failures from before a
+/// weight change (including an earlier deload) don't trigger another one.
+fn prescribe(rule: &ProgressionRule, sessions: &[Vec<&LoggedSet>]) -> Prescription {
+    let (sets, min_reps, max_reps, step_kg) = rule.scheme.parameters();
+    let outcomes: Vec<SessionOutcome> = sessions.iter().map(|s| rule.scheme.judge(s)).collect();
+    let round = |weight_kg: f64| round_to_increment(weight_kg, step_kg);
+
+    let prescription = |weight_kg: f64, min_reps: u32, decision, explanation: String| Prescription {
+        exercise_id: rule.exercise_id.clone(),
+        weight_kg,
+        sets,
+        min_reps,
+        max_reps,
+        decision,
+        explanation,
+        sessions_evaluated: outcomes.len() as u32,
+    };
+
+    let last = match outcomes.first() {
+        Some(last) => *last,
+        None => {
+            let weight_kg = round(rule.starting_weight_kg);
+            return prescription(
+                weight_kg,
+                min_reps,
+                ProgressionDecision::Start,
+                format!("No completed sessions yet, starting at {} kg", weight_kg),
+            );
+        }
+    };
+
+    let consecutive_failures = outcomes
+        .iter()
+        .take_while(|o| !o.succeeded && (o.working_weight_kg - last.working_weight_kg).abs() < 1e-6)
+        .count() as u32;
+    if rule.deload_after_failures > 0 && consecutive_failures >= rule.deload_after_failures {
+        let weight_kg = round(last.working_weight_kg * (1.0 - rule.deload_percentage / 100.0));
+        return prescription(
+            weight_kg,
+            min_reps,
+            ProgressionDecision::Deload,
+            format!(
+                "Missed the target in {} consecutive sessions at {} kg, deloading {}% to {} kg",
+                consecutive_failures, last.working_weight_kg, rule.deload_percentage, weight_kg
+            ),
+        );
+    }
+
+    match rule.scheme {
+        ProgressionScheme::Linear { reps, .. } => {
+            if last.succeeded {
+                let weight_kg = round(last.working_weight_kg + step_kg);
+                prescription(
+                    weight_kg,
+                    min_reps,
+                    ProgressionDecision::Increase,
+                    format!("Completed {}x{} at {} kg, moving up to {} kg", sets, reps, last.working_weight_kg, weight_kg),
+                )
+            } else {
+                let weight_kg = round(last.working_weight_kg);
+                prescription(
+                    weight_kg,
+                    min_reps,
+                    ProgressionDecision::Repeat,
+                    format!(
+                        "Missed {}x{} at {} kg ({} sets, lowest {} reps), repeating at {} kg",
+                        sets, reps, last.working_weight_kg, last.working_sets, last.lowest_reps, weight_kg
+                    ),
+                )
+            }
+        }
+        ProgressionScheme::DoubleProgression { .. } => {
+            if last.reached_top_of_range {
+                let weight_kg = round(last.working_weight_kg + step_kg);
+                prescription(
+                    weight_kg,
+                    min_reps,
+                    ProgressionDecision::Increase,
+                    format!(
+                        "Reached {} reps on all {} sets at {} kg, moving up to {} kg and restarting at {} reps",
+                        max_reps, sets, last.working_weight_kg, weight_kg, min_reps
+                    ),
+                )
+            } else if last.succeeded {
+                let weight_kg = round(last.working_weight_kg);
+                let target = (last.lowest_reps + 1).clamp(min_reps, max_reps);
+                prescription(
+                    weight_kg,
+                    target,
+                    ProgressionDecision::Repeat,
+                    format!(
+                        "Within the {}-{} rep range, stay at {} kg and aim for at least {} reps per set",
+                        min_reps, max_reps, weight_kg, target
+                    ),
+                )
+            } else {
+                let weight_kg = round(last.working_weight_kg);
+                prescription(
+                    weight_kg,
+                    min_reps,
+                    ProgressionDecision::Repeat,
+                    format!(
+                        "Fell below {} reps at {} kg, repeating at {} kg",
+                        min_reps, last.working_weight_kg, weight_kg
+                    ),
+                )
+            }
+        }
+    }
+}
+
+/// Group sets (newest first) into sessions, keeping newest-first order
+fn group_into_sessions(sets: &[LoggedSet]) -> Vec<Vec<&LoggedSet>> {
+    let mut sessions: Vec<(String, Vec<&LoggedSet>)> = Vec::new();
+    for set in sets {
+        let key = set.session_key();
+        match sessions.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, session)) => session.push(set),
+            None => sessions.push((key, vec![set])),
+        }
+    }
+    sessions.into_iter().map(|(_, session)| session).collect()
+}
+
+impl ExerciseRepository {
+    /// Attach a progression rule to an exercise, replacing any existing rule
+    pub fn set_progression_rule(&self, rule: ProgressionRule) -> Result<(), WeightliftingError> {
+        info!("📈 Setting progression rule for exercise: {}", rule.exercise_id);
+        debug!("📝 Rule details: {:?}", rule);
+
+        rule.validate().map_err(|message| {
+            warn!("❌ Invalid progression rule: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let scheme_json = serde_json::to_string(&rule.scheme).map_err(|e| {
+            error!("❌ Failed to serialize progression scheme: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize progression scheme: {}", e),
+            }
+        })?;
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &rule.exercise_id)?;
+
+        conn.execute(
+            "INSERT INTO progression_rules (exercise_id, scheme, starting_weight_kg, deload_after_failures, deload_percentage)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(exercise_id) DO UPDATE SET
+                scheme = excluded.scheme,
+                starting_weight_kg = excluded.starting_weight_kg,
+                deload_after_failures = excluded.deload_after_failures,
+                deload_percentage = excluded.deload_percentage",
+            params![
+                rule.exercise_id,
+                scheme_json,
+                rule.starting_weight_kg,
+                rule.deload_after_failures,
+                rule.deload_percentage
+            ],
+        )?;
+
+        info!("✅ Progression rule saved for exercise: {}", rule.exercise_id);
+        Ok(())
+    }
+
+    /// Get the progression rule attached to an exercise, if any
+    pub fn get_progression_rule(&self, exercise_id: String) -> Result<Option<ProgressionRule>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_progression_rule(&conn, &exercise_id)
+    }
+
+    /// Remove the progression rule from an exercise
+    /// Returns true if a rule was removed
+    pub fn delete_progression_rule(&self, exercise_id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting progression rule for exercise: {}", exercise_id);
+
+        let conn = self.connection()?;
+        Self::remove_progression_rule(&conn, &exercise_id)
+    }
+
+    /// Remove an exercise's progression rule on an existing connection
+    pub(crate) fn remove_progression_rule(conn: &Connection, exercise_id: &str) -> Result<bool, WeightliftingError> {
+        let rows_affected = conn.execute("DELETE FROM progression_rules WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Evaluate the exercise's progression rule against its completed sessions
+    pub fn evaluate_progression(&self, exercise_id: String) -> Result<Prescription, WeightliftingError> {
+        info!("🧮 Evaluating progression for exercise: {}", exercise_id);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let rule = Self::query_progression_rule(&conn, &exercise_id)?.ok_or_else(|| {
+            warn!("⚠️ No progression rule for exercise {}", exercise_id);
+            WeightliftingError::InvalidInput {
+                message: format!("No progression rule set for exercise {}", exercise_id),
+            }
+        })?;
+
+        let sets = Self::query_recent_sets(&conn, &exercise_id, SESSION_HISTORY_SETS)?;
+        let sessions = group_into_sessions(&sets);
+
+        let prescription = prescribe(&rule, &sessions);
+        info!("✅ {:?}: {}", prescription.decision, prescription.explanation);
+        Ok(prescription)
+    }
+
+    fn query_progression_rule(conn: &Connection, exercise_id: &str) -> Result<Option<ProgressionRule>, WeightliftingError> {
+        let row = conn.query_row(
+            "SELECT scheme, starting_weight_kg, deload_after_failures, deload_percentage
+             FROM progression_rules WHERE exercise_id = ?1",
+            [exercise_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, f64>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, f64>(3)?,
+                ))
+            },
+        ).optional()?;
+
+        let Some((scheme_json, starting_weight_kg, deload_after_failures, deload_percentage)) = row else {
+            return Ok(None);
+        };
+
+        let scheme = serde_json::from_str(&scheme_json).map_err(|e| {
+            error!("❌ Failed to parse progression scheme for {}: {}", exercise_id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to parse progression scheme: {}", e),
+            }
+        })?;
+
+        Ok(Some(ProgressionRule {
+            exercise_id: exercise_id.to_string(),
+            scheme,
+            starting_weight_kg,
+            deload_after_failures,
+            deload_percentage,
+        }))
+    }
+
+    /// Create the progression rules table if it doesn't exist
+    pub(crate) fn create_progression_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating progression_rules table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS progression_rules (
+                exercise_id TEXT PRIMARY KEY,
+                scheme TEXT NOT NULL,
+                starting_weight_kg REAL NOT NULL,
+                deload_after_failures INTEGER NOT NULL,
+                deload_percentage REAL NOT NULL
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create progression_rules table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Progression rules table ready");
+        Ok(())
+    }
+}
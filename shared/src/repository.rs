@@ -1,17 +1,33 @@
-use crate::models::Exercise;
+use crate::models::{DropSegment, Exercise, LoggedSet, SetType};
+use crate::achievements::{AchievementListener, TrainingEvent};
+use crate::body_measurements::BodyMeasurementRepository;
 use crate::error::WeightliftingError;
-use r2d2::Pool;
+use crate::collections::normalize_tags;
+use crate::muscles::{normalize_exercise_muscles, MuscleInvolvement};
+use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde_json;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use log::{info, debug, warn, error};
 
+/// Default rounding increment for suggested loads (kg)
+pub const DEFAULT_LOAD_INCREMENT_KG: f64 = 2.5;
+
+const LOAD_INCREMENT_SETTING: &str = "load_increment_kg";
+
+const EXERCISE_COLUMNS: &str =
+    "id, name, description, muscle_groups, equipment_ids, difficulty_level, muscle_involvement, tags, is_favorite";
+
+const LOGGED_SET_COLUMNS: &str = "id, exercise_id, weight_kg, reps, rpe, rir, performed_at, session_id, \
+    set_type, is_amrap, duration_seconds, added_load_kg, assistance_kg, drops";
+
 /// Exercise repository that manages SQLite database operations
 /// Uses connection pooling for thread safety and performance
 #[derive(Clone)]
 pub struct ExerciseRepository {
     pool: Arc<Pool<SqliteConnectionManager>>,
+    pub(crate) achievement_listener: Arc<Mutex<Option<Arc<dyn AchievementListener>>>>,
 }
 
 impl ExerciseRepository {
@@ -27,6 +43,9 @@ impl ExerciseRepository {
             }
         })?;
 
+        let exercise = normalize_exercise_muscles(exercise)?;
+        let exercise = Exercise { tags: normalize_tags(&exercise.tags), ..exercise };
+
         let muscle_groups_json = serde_json::to_string(&exercise.muscle_groups)
             .map_err(|e| {
                 error!("❌ Failed to serialize muscle groups: {}", e);
@@ -35,18 +54,45 @@ impl ExerciseRepository {
                 }
             })?;
 
+        Self::ensure_equipment_exists(&conn, &exercise.equipment_ids)?;
+        let equipment_json = serde_json::to_string(&exercise.equipment_ids)
+            .map_err(|e| {
+                error!("❌ Failed to serialize equipment: {}", e);
+                WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize equipment: {}", e),
+                }
+            })?;
+
+        let involvement_json = serde_json::to_string(&exercise.muscle_involvement)
+            .map_err(|e| {
+                error!("❌ Failed to serialize muscle involvement: {}", e);
+                WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize muscle involvement: {}", e),
+                }
+            })?;
+
+        let tags_json = serde_json::to_string(&exercise.tags)
+            .map_err(|e| {
+                error!("❌ Failed to serialize tags: {}", e);
+                WeightliftingError::DatabaseError {
+                    message: format!("Failed to serialize tags: {}", e),
+                }
+            })?;
+
         debug!("💾 Inserting into database with muscle_groups: {}", muscle_groups_json);
 
         conn.execute(
-            "INSERT INTO exercises (id, name, description, muscle_groups, equipment_needed, difficulty_level)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            &format!("INSERT INTO exercises ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", EXERCISE_COLUMNS),
             params![
                 exercise.id,
                 exercise.name,
                 exercise.description,
                 muscle_groups_json,
-                exercise.equipment_needed,
-                exercise.difficulty_level as i32
+                equipment_json,
+                exercise.difficulty_level as i32,
+                involvement_json,
+                tags_json,
+                exercise.is_favorite
             ],
         ).map_err(|e| {
             error!("❌ Failed to insert exercise '{}': {}", exercise.name, e);
@@ -70,39 +116,7 @@ impl ExerciseRepository {
             }
         })?;
 
-        debug!("📊 Executing SELECT query for ID: {}", id);
-
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, muscle_groups, equipment_needed, difficulty_level
-             FROM exercises WHERE id = ?1"
-        ).map_err(|e| {
-            error!("❌ Failed to prepare SELECT statement: {}", e);
-            WeightliftingError::DatabaseError {
-                message: format!("Failed to prepare statement: {}", e),
-            }
-        })?;
-
-        let exercise = stmt.query_row(params![id], |row| {
-            let muscle_groups_json: String = row.get(3)?;
-            let muscle_groups: Vec<String> = serde_json::from_str(&muscle_groups_json)
-                .map_err(|_e| rusqlite::Error::InvalidColumnType(3, "muscle_groups".to_string(), rusqlite::types::Type::Text))?;
-
-            debug!("📋 Found exercise: {}", row.get::<_, String>(1)?);
-
-            Ok(Exercise {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                muscle_groups,
-                equipment_needed: row.get(4)?,
-                difficulty_level: row.get::<_, i32>(5)? as u8,
-            })
-        }).map_err(|e| {
-            warn!("❌ Exercise not found with ID '{}': {}", id, e);
-            WeightliftingError::ExerciseNotFound {
-                id: id.to_string(),
-            }
-        })?;
+        let exercise = Self::query_exercise(&conn, &id)?;
 
         info!("✅ Successfully retrieved exercise: {}", exercise.name);
         Ok(exercise)
@@ -122,8 +136,7 @@ impl ExerciseRepository {
         debug!("📊 Executing SELECT query for all exercises");
 
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, muscle_groups, equipment_needed, difficulty_level
-             FROM exercises ORDER BY name"
+            &format!("SELECT {} FROM exercises ORDER BY name", EXERCISE_COLUMNS)
         ).map_err(|e| {
             error!("❌ Failed to prepare SELECT ALL statement: {}", e);
             WeightliftingError::DatabaseError {
@@ -131,20 +144,7 @@ impl ExerciseRepository {
             }
         })?;
 
-        let exercise_iter = stmt.query_map([], |row| {
-            let muscle_groups_json: String = row.get(3)?;
-            let muscle_groups: Vec<String> = serde_json::from_str(&muscle_groups_json)
-                .map_err(|_e| rusqlite::Error::InvalidColumnType(3, "muscle_groups".to_string(), rusqlite::types::Type::Text))?;
-
-            Ok(Exercise {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                muscle_groups,
-                equipment_needed: row.get(4)?,
-                difficulty_level: row.get::<_, i32>(5)? as u8,
-            })
-        }).map_err(|e| {
+        let exercise_iter = stmt.query_map([], Self::exercise_from_row).map_err(|e| {
             error!("❌ Failed to query all exercises: {}", e);
             WeightliftingError::DatabaseError {
                 message: format!("Failed to query exercises: {}", e),
@@ -176,17 +176,32 @@ impl ExerciseRepository {
     pub fn delete_exercise(&self, id: String) -> Result<bool, WeightliftingError> {
         info!("🗑️ Deleting exercise with ID: {}", id);
 
-        let conn = self.pool.get()
+        let mut conn = self.pool.get()
             .map_err(|e| WeightliftingError::DatabaseError {
                 message: format!("Failed to get connection: {}", e)
             })?;
 
-        let rows_affected = conn.execute(
+        // Related rows and the exercise go together or not at all
+        let tx = conn.transaction()?;
+        Self::detach_variations(&tx, &id)?;
+        Self::delete_translations(&tx, &id)?;
+        Self::delete_instructional_content(&tx, &id)?;
+        Self::delete_training_maxes(&tx, &id)?;
+        Self::remove_progression_rule(&tx, &id)?;
+        Self::delete_canonical_lift(&tx, &id)?;
+
+        let rows_affected = tx.execute(
             "DELETE FROM exercises WHERE id = ?1",
             [&id],
         ).map_err(|e| WeightliftingError::DatabaseError {
             message: format!("Failed to delete exercise: {}", e)
         })?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit exercise deletion: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to delete exercise: {}", e),
+            }
+        })?;
 
         let deleted = rows_affected > 0;
         if deleted {
@@ -198,6 +213,316 @@ impl ExerciseRepository {
         Ok(deleted)
     }
 
+    /// Log a completed set for an exercise
+    pub fn log_set(&self, set: LoggedSet) -> Result<(), WeightliftingError> {
+        info!("📝 Logging set for exercise {}: {} x {} kg", set.exercise_id, set.reps, set.weight_kg);
+
+        set.validate().map_err(|message| {
+            warn!("❌ Invalid set: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let mut conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &set.exercise_id)?;
+        let tx = conn.transaction()?;
+        Self::insert_set(&tx, &set)?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit set '{}': {}", set.id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to insert set: {}", e),
+            }
+        })?;
+        drop(conn);
+
+        info!("✅ Successfully logged set: {}", set.id);
+        self.notify_achievements(TrainingEvent::Sets(std::slice::from_ref(&set)));
+        Ok(())
+    }
+
+    /// Get the most recent sets logged for an exercise, newest first
+    pub fn get_recent_sets(&self, exercise_id: String, limit: u32) -> Result<Vec<LoggedSet>, WeightliftingError> {
+        info!("📚 Retrieving up to {} recent sets for exercise: {}", limit, exercise_id);
+
+        let conn = self.connection()?;
+        let sets = Self::query_recent_sets(&conn, &exercise_id, limit)?;
+
+        info!("✅ Successfully retrieved {} sets", sets.len());
+        Ok(sets)
+    }
+
+    /// Delete a logged set by ID
+    /// Returns true if the set was deleted, false if it wasn't found
+    pub fn delete_set(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting set with ID: {}", id);
+
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        let set = tx.query_row(
+            &format!("SELECT {} FROM logged_sets WHERE id = ?1", LOGGED_SET_COLUMNS),
+            [&id],
+            Self::logged_set_from_row,
+        ).optional()?;
+        let rows_affected = tx.execute("DELETE FROM logged_sets WHERE id = ?1", [&id])
+            .map_err(|e| WeightliftingError::DatabaseError {
+                message: format!("Failed to delete set: {}", e)
+            })?;
+        if let Some(ref set) = set {
+            Self::remove_from_training_totals(&tx, set)?;
+        }
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to commit set deletion '{}': {}", id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to delete set: {}", e),
+            }
+        })?;
+
+        let deleted = rows_affected > 0;
+        if deleted {
+            info!("✅ Successfully deleted set: {}", id);
+        } else {
+            warn!("⚠️ Set not found for deletion: {}", id);
+        }
+
+        Ok(deleted)
+    }
+
+    /// Set the smallest weight increment loads are rounded to (kg)
+    pub fn set_load_increment(&self, increment_kg: f64) -> Result<(), WeightliftingError> {
+        info!("⚙️ Setting load increment to {} kg", increment_kg);
+
+        if !increment_kg.is_finite() || increment_kg <= 0.0 {
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Load increment must be positive, got {}", increment_kg),
+            });
+        }
+
+        let conn = self.connection()?;
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![LOAD_INCREMENT_SETTING, increment_kg.to_string()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the smallest weight increment loads are rounded to (kg)
+    pub fn get_load_increment(&self) -> Result<f64, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::load_increment(&conn)
+    }
+
+    /// Get a pooled connection, mapping pool failures to a database error
+    pub(crate) fn connection(&self) -> Result<PooledConnection<SqliteConnectionManager>, WeightliftingError> {
+        self.pool.get().map_err(|e| {
+            error!("❌ Failed to get connection from pool: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to get database connection: {}", e),
+            }
+        })
+    }
+
+    /// Return an ExerciseNotFound error unless the exercise exists
+    pub(crate) fn ensure_exercise_exists(conn: &Connection, id: &str) -> Result<(), WeightliftingError> {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM exercises WHERE id = ?1)",
+            [id],
+            |row| row.get(0),
+        )?;
+
+        if !exists {
+            warn!("❌ Exercise not found with ID '{}'", id);
+            return Err(WeightliftingError::ExerciseNotFound { id: id.to_string() });
+        }
+        Ok(())
+    }
+
+    /// Load an exercise by ID with an existing connection
+    pub(crate) fn query_exercise(conn: &Connection, id: &str) -> Result<Exercise, WeightliftingError> {
+        debug!("📊 Executing SELECT query for ID: {}", id);
+
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM exercises WHERE id = ?1", EXERCISE_COLUMNS)
+        ).map_err(|e| {
+            error!("❌ Failed to prepare SELECT statement: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to prepare statement: {}", e),
+            }
+        })?;
+
+        let exercise = stmt.query_row(params![id], Self::exercise_from_row).map_err(|e| {
+            warn!("❌ Exercise not found with ID '{}': {}", id, e);
+            WeightliftingError::ExerciseNotFound {
+                id: id.to_string(),
+            }
+        })?;
+
+        debug!("📋 Found exercise: {}", exercise.name);
+        Ok(exercise)
+    }
+
+    /// Load the exercises matching a WHERE/ORDER BY clause with an existing connection
+    pub(crate) fn query_exercises(conn: &Connection, clause: &str, clause_params: impl rusqlite::Params) -> Result<Vec<Exercise>, WeightliftingError> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM exercises {}", EXERCISE_COLUMNS, clause))?;
+        let exercises = stmt
+            .query_map(clause_params, Self::exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
+    }
+
+    /// Map a row selected with EXERCISE_COLUMNS to an Exercise
+    fn exercise_from_row(row: &Row) -> rusqlite::Result<Exercise> {
+        let muscle_groups_json: String = row.get(3)?;
+        let muscle_groups: Vec<String> = serde_json::from_str(&muscle_groups_json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(3, "muscle_groups".to_string(), rusqlite::types::Type::Text))?;
+        let equipment_json: String = row.get(4)?;
+        let equipment_ids: Vec<String> = serde_json::from_str(&equipment_json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(4, "equipment_ids".to_string(), rusqlite::types::Type::Text))?;
+        let involvement_json: String = row.get(6)?;
+        let muscle_involvement: Vec<MuscleInvolvement> = serde_json::from_str(&involvement_json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(6, "muscle_involvement".to_string(), rusqlite::types::Type::Text))?;
+        let tags_json: String = row.get(7)?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(7, "tags".to_string(), rusqlite::types::Type::Text))?;
+
+        Ok(Exercise {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            muscle_groups,
+            equipment_ids,
+            difficulty_level: row.get::<_, i32>(5)? as u8,
+            muscle_involvement,
+            tags,
+            is_favorite: row.get(8)?,
+        })
+    }
+
+    /// Insert a validated set into the training log
+    pub(crate) fn insert_set(conn: &Connection, set: &LoggedSet) -> Result<(), WeightliftingError> {
+        let drops_json = serde_json::to_string(&set.drops).map_err(|e| {
+            error!("❌ Failed to serialize drops: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize drops: {}", e),
+            }
+        })?;
+
+        conn.execute(
+            &format!(
+                "INSERT INTO logged_sets ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                LOGGED_SET_COLUMNS
+            ),
+            params![
+                set.id,
+                set.exercise_id,
+                set.weight_kg,
+                set.reps,
+                set.rpe,
+                set.rir,
+                set.performed_at,
+                set.session_id,
+                set.set_type.as_str(),
+                set.is_amrap,
+                set.duration_seconds,
+                set.added_load_kg,
+                set.assistance_kg,
+                drops_json
+            ],
+        ).map_err(|e| {
+            error!("❌ Failed to insert set '{}': {}", set.id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to insert set: {}", e),
+            }
+        })?;
+        Self::add_to_training_totals(conn, set)?;
+        Ok(())
+    }
+
+    /// Query the most recent sets for an exercise, newest first
+    pub(crate) fn query_recent_sets(conn: &Connection, exercise_id: &str, limit: u32) -> Result<Vec<LoggedSet>, WeightliftingError> {
+        debug!("📊 Executing SELECT query for recent sets of: {}", exercise_id);
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM logged_sets WHERE exercise_id = ?1
+             ORDER BY performed_at DESC, rowid DESC LIMIT ?2",
+            LOGGED_SET_COLUMNS
+        ))?;
+
+        let sets = stmt
+            .query_map(params![exercise_id, limit], Self::logged_set_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sets)
+    }
+
+    /// Query the sets performed between `start` and `end` (inclusive), oldest first
+    ///
+    /// Covers every exercise when `exercise_id` is None.
+    pub(crate) fn query_sets_between(conn: &Connection, exercise_id: Option<&str>, start: i64, end: i64) -> Result<Vec<LoggedSet>, WeightliftingError> {
+        debug!("📊 Executing SELECT query for sets between {} and {}", start, end);
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM logged_sets
+             WHERE (?1 IS NULL OR exercise_id = ?1) AND performed_at BETWEEN ?2 AND ?3
+             ORDER BY performed_at, rowid",
+            LOGGED_SET_COLUMNS
+        ))?;
+
+        let sets = stmt
+            .query_map(params![exercise_id, start, end], Self::logged_set_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sets)
+    }
+
+    /// Map a row selected with LOGGED_SET_COLUMNS to a LoggedSet
+    pub(crate) fn logged_set_from_row(row: &Row) -> rusqlite::Result<LoggedSet> {
+        let set_type_name: String = row.get(8)?;
+        let set_type = SetType::parse(&set_type_name)
+            .ok_or_else(|| rusqlite::Error::InvalidColumnType(8, "set_type".to_string(), rusqlite::types::Type::Text))?;
+        let drops_json: String = row.get(13)?;
+        let drops: Vec<DropSegment> = serde_json::from_str(&drops_json)
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(13, "drops".to_string(), rusqlite::types::Type::Text))?;
+
+        Ok(LoggedSet {
+            id: row.get(0)?,
+            exercise_id: row.get(1)?,
+            weight_kg: row.get(2)?,
+            reps: row.get(3)?,
+            rpe: row.get(4)?,
+            rir: row.get(5)?,
+            performed_at: row.get(6)?,
+            session_id: row.get(7)?,
+            set_type,
+            is_amrap: row.get(9)?,
+            duration_seconds: row.get(10)?,
+            added_load_kg: row.get(11)?,
+            assistance_kg: row.get(12)?,
+            drops,
+        })
+    }
+
+    /// Volume of a set, counting the bodyweight recorded before it for bodyweight set types
+    pub(crate) fn set_volume(conn: &Connection, set: &LoggedSet) -> Result<f64, WeightliftingError> {
+        let bodyweight_kg = if set.set_type.uses_bodyweight() {
+            BodyMeasurementRepository::bodyweight_at(conn, set.performed_at)?
+        } else {
+            None
+        };
+        Ok(set.volume_with_bodyweight(bodyweight_kg))
+    }
+
+    /// Read the configured load increment, falling back to the default
+    pub(crate) fn load_increment(conn: &Connection) -> Result<f64, WeightliftingError> {
+        let value: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [LOAD_INCREMENT_SETTING],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(value
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(DEFAULT_LOAD_INCREMENT_KG))
+    }
+
     /// Create a new repository with SQLite backend
     pub fn new(db_path: &str) -> Result<Arc<Self>, WeightliftingError> {
         info!("🏗️ Initializing ExerciseRepository with database: {}", db_path);
@@ -208,7 +533,10 @@ impl ExerciseRepository {
                 message: format!("Failed to create connection pool: {}", e)
             })?;
 
-        let repo = Arc::new(ExerciseRepository { pool: Arc::new(pool) });
+        let repo = Arc::new(ExerciseRepository {
+            pool: Arc::new(pool),
+            achievement_listener: Arc::new(Mutex::new(None)),
+        });
         repo.initialize_database()?;
 
         info!("✅ ExerciseRepository initialized successfully");
@@ -231,8 +559,11 @@ impl ExerciseRepository {
                 name TEXT NOT NULL,
                 description TEXT,
                 muscle_groups TEXT NOT NULL,
-                equipment_needed TEXT,
-                difficulty_level INTEGER NOT NULL
+                equipment_ids TEXT NOT NULL DEFAULT '[]',
+                difficulty_level INTEGER NOT NULL,
+                muscle_involvement TEXT NOT NULL DEFAULT '[]',
+                tags TEXT NOT NULL DEFAULT '[]',
+                is_favorite INTEGER NOT NULL DEFAULT 0
             )",
             [],
         ).map_err(|e| {
@@ -242,10 +573,88 @@ impl ExerciseRepository {
             }
         })?;
 
+        // Databases created before muscle groups had roles list plain names only
+        Self::add_column_if_missing(conn, "exercises", "muscle_involvement", "TEXT NOT NULL DEFAULT '[]'")?;
+
+        // Databases created before exercises could be tagged or starred
+        Self::add_column_if_missing(conn, "exercises", "tags", "TEXT NOT NULL DEFAULT '[]'")?;
+        Self::add_column_if_missing(conn, "exercises", "is_favorite", "INTEGER NOT NULL DEFAULT 0")?;
+
         debug!("✅ Exercises table ready");
         Ok(())
     }
 
+    /// Create the set log and settings tables if they don't exist
+    fn create_log_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating logged_sets and settings tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS logged_sets (
+                id TEXT PRIMARY KEY,
+                exercise_id TEXT NOT NULL,
+                weight_kg REAL NOT NULL,
+                reps INTEGER NOT NULL,
+                rpe REAL,
+                rir INTEGER,
+                performed_at INTEGER NOT NULL,
+                session_id TEXT,
+                set_type TEXT NOT NULL DEFAULT 'standard',
+                is_amrap INTEGER NOT NULL DEFAULT 0,
+                duration_seconds INTEGER,
+                added_load_kg REAL,
+                assistance_kg REAL,
+                drops TEXT NOT NULL DEFAULT '[]'
+            );
+            CREATE INDEX IF NOT EXISTS idx_logged_sets_exercise
+                ON logged_sets (exercise_id, performed_at);
+            CREATE INDEX IF NOT EXISTS idx_logged_sets_performed
+                ON logged_sets (performed_at);
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create log tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        // Databases created before sets were grouped into sessions
+        Self::add_column_if_missing(conn, "logged_sets", "session_id", "TEXT")?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_logged_sets_session ON logged_sets (session_id)", [])?;
+        // ...and before sets had types
+        Self::add_column_if_missing(conn, "logged_sets", "set_type", "TEXT NOT NULL DEFAULT 'standard'")?;
+        Self::add_column_if_missing(conn, "logged_sets", "is_amrap", "INTEGER NOT NULL DEFAULT 0")?;
+        Self::add_column_if_missing(conn, "logged_sets", "duration_seconds", "INTEGER")?;
+        Self::add_column_if_missing(conn, "logged_sets", "added_load_kg", "REAL")?;
+        Self::add_column_if_missing(conn, "logged_sets", "assistance_kg", "REAL")?;
+        Self::add_column_if_missing(conn, "logged_sets", "drops", "TEXT NOT NULL DEFAULT '[]'")?;
+
+        debug!("✅ Log tables ready");
+        Ok(())
+    }
+
+    /// Add a column to an existing table unless it is already present
+    pub(crate) fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), WeightliftingError> {
+        if !Self::has_column(conn, table, column)? {
+            info!("🔧 Adding column {}.{}", table, column);
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+        }
+        Ok(())
+    }
+
+    /// Whether a table has a column, used when migrating older databases
+    pub(crate) fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool, WeightliftingError> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .any(|name| name == column);
+        Ok(exists)
+    }
+
     fn initialize_database(&self) -> Result<(), WeightliftingError> {
         let conn = self.pool.get().map_err(|e| {
             error!("❌ Failed to get connection from pool: {}", e);
@@ -255,6 +664,23 @@ impl ExerciseRepository {
         })?;
 
         Self::create_table(&conn)?;
+        Self::create_log_tables(&conn)?;
+        Self::create_progression_tables(&conn)?;
+        Self::create_workout_tables(&conn)?;
+        Self::create_body_measurement_tables(&conn)?;
+        Self::create_goal_tables(&conn)?;
+        Self::create_schedule_tables(&conn)?;
+        Self::create_variation_tables(&conn)?;
+        Self::create_equipment_tables(&conn)?;
+        Self::migrate_muscle_groups(&conn)?;
+        Self::create_translation_tables(&conn)?;
+        Self::create_instruction_tables(&conn)?;
+        Self::create_collection_tables(&conn)?;
+        Self::create_training_max_tables(&conn)?;
+        Self::create_cardio_tables(&conn)?;
+        Self::create_meet_tables(&conn)?;
+        Self::create_standards_tables(&conn)?;
+        Self::create_achievement_tables(&conn)?;
         Ok(())
     }
 }
\ No newline at end of file
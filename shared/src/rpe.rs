@@ -0,0 +1,166 @@
+// RPE-based autoregulation: the reps x RPE chart and load suggestions
+// built on top of the logged set history
+
+use crate::error::WeightliftingError;
+use crate::models::LoggedSet;
+use crate::repository::ExerciseRepository;
+use log::{debug, info, warn};
+
+/// Percentage of 1RM for a set taken to RPE 10, indexed by reps - 1.
+/// Lower RPEs are read from this curve by adding the reps left in reserve.
+const RPE_10_PERCENTAGES: [f64; 16] = [
+    100.0, 95.5, 92.2, 89.2, 86.3, 83.7, 81.1, 78.6, 76.2, 73.9, 70.7, 68.0, 65.3, 62.6, 59.9, 57.4,
+];
+
+/// Rep and RPE range covered by the chart
+pub const MIN_CHART_REPS: u32 = 1;
+pub const MAX_CHART_REPS: u32 = 12;
+pub const MIN_CHART_RPE: f64 = 6.0;
+pub const MAX_CHART_RPE: f64 = 10.0;
+
+/// Number of recent sets scanned when estimating current strength
+const RECENT_SET_WINDOW: u32 = 20;
+/// Number of effort-rated sets averaged into the estimated 1RM
+const ESTIMATES_PER_SUGGESTION: usize = 5;
+
+/// One cell of the RPE chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpeChartEntry {
+    pub reps: u32,
+    pub rpe: f64,
+    pub percentage: f64, // Percentage of 1RM
+}
+
+/// A recommended load for the next set of an exercise
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadSuggestion {
+    pub exercise_id: String,
+    pub reps: u32,
+    pub target_rpe: f64,
+    pub estimated_one_rm_kg: f64,
+    pub suggested_weight_kg: f64,
+    pub sets_considered: u32,
+}
+
+/// Get the full RPE chart, ordered by reps then RPE
+pub fn rpe_chart() -> Vec<RpeChartEntry> {
+    let mut entries = Vec::new();
+    for reps in MIN_CHART_REPS..=MAX_CHART_REPS {
+        let mut rpe = MIN_CHART_RPE;
+        while rpe <= MAX_CHART_RPE {
+            if let Some(percentage) = rpe_percentage(reps, rpe) {
+                entries.push(RpeChartEntry { reps, rpe, percentage });
+            }
+            rpe += 0.5;
+        }
+    }
+    entries
+}
+
+/// Look up the percentage of 1RM that `reps` at `rpe` corresponds to
+///
+/// Returns None when the combination is outside the chart
+/// (1-12 reps, RPE 6-10 in half steps).
+pub fn rpe_percentage(reps: u32, rpe: f64) -> Option<f64> {
+    if !(MIN_CHART_REPS..=MAX_CHART_REPS).contains(&reps)
+        || !(MIN_CHART_RPE..=MAX_CHART_RPE).contains(&rpe)
+        || (rpe * 2.0).fract() != 0.0
+    {
+        return None;
+    }
+
+    // Each RPE point below 10 is one more rep that could have been done
+    let effective_reps = reps as f64 + (MAX_CHART_RPE - rpe);
+    let lower = effective_reps.floor() as usize - 1;
+    let upper = effective_reps.ceil() as usize - 1;
+    let percentage = (RPE_10_PERCENTAGES[lower] + RPE_10_PERCENTAGES[upper]) / 2.0;
+
+    Some((percentage * 10.0).round() / 10.0)
+}
+
+/// Estimate a one-rep max from a weight lifted for `reps` at `rpe`
+pub fn estimate_one_rm(weight_kg: f64, reps: u32, rpe: f64) -> Option<f64> {
+    rpe_percentage(reps, rpe).map(|percentage| weight_kg * 100.0 / percentage)
+}
+
+/// Round a load to the nearest multiple of the available increment
+pub fn round_to_increment(weight_kg: f64, increment_kg: f64) -> f64 {
+    if increment_kg <= 0.0 {
+        return weight_kg;
+    }
+    (weight_kg / increment_kg).round() * increment_kg
+}
+
+/// Estimate a one-rep max from a logged set, if it has an on-chart effort rating
+///
+/// Sets rated below RPE 6 are off the chart and give no estimate.
+pub(crate) fn estimate_set_one_rm(set: &LoggedSet) -> Option<f64> {
+    if !set.is_strength_comparable() {
+        return None;
+    }
+    set.effective_rpe()
+        .and_then(|rpe| estimate_one_rm(set.weight_kg, set.reps, rpe))
+}
+
+/// Estimate a one-rep max from a logged set for analytics
+///
+/// Sets logged without RPE or RIR are treated as taken to failure (RPE 10);
+/// sets rated below RPE 6 are off the chart and give no estimate.
+pub(crate) fn estimate_logged_one_rm(set: &LoggedSet) -> Option<f64> {
+    if !set.is_strength_comparable() {
+        return None;
+    }
+    estimate_one_rm(set.weight_kg, set.reps, set.effective_rpe().unwrap_or(MAX_CHART_RPE))
+}
+
+impl ExerciseRepository {
+    /// Suggest a load for `reps` at `target_rpe` based on recent performance
+    ///
+    /// The estimated 1RM is the mean of the most recent sets that were logged
+    /// with an RPE or RIR, and the suggestion is rounded to the configured
+    /// load increment.
+    pub fn suggest_load(&self, exercise_id: String, reps: u32, target_rpe: f64) -> Result<LoadSuggestion, WeightliftingError> {
+        info!("🎯 Suggesting load for exercise {}: {} reps @ RPE {}", exercise_id, reps, target_rpe);
+
+        let percentage = rpe_percentage(reps, target_rpe).ok_or_else(|| {
+            warn!("❌ Target outside RPE chart: {} reps @ RPE {}", reps, target_rpe);
+            WeightliftingError::InvalidInput {
+                message: format!(
+                    "Target must be {}-{} reps at RPE {}-{} in half steps, got {} reps @ RPE {}",
+                    MIN_CHART_REPS, MAX_CHART_REPS, MIN_CHART_RPE, MAX_CHART_RPE, reps, target_rpe
+                ),
+            }
+        })?;
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let estimates: Vec<f64> = Self::query_recent_sets(&conn, &exercise_id, RECENT_SET_WINDOW)?
+            .iter()
+            .filter_map(estimate_set_one_rm)
+            .take(ESTIMATES_PER_SUGGESTION)
+            .collect();
+
+        if estimates.is_empty() {
+            warn!("⚠️ No recent effort-rated sets for exercise {}", exercise_id);
+            return Err(WeightliftingError::InsufficientData {
+                message: format!("No recent sets with RPE or RIR logged for exercise {}", exercise_id),
+            });
+        }
+
+        let estimated_one_rm_kg = estimates.iter().sum::<f64>() / estimates.len() as f64;
+        let increment = Self::load_increment(&conn)?;
+        let suggested_weight_kg = round_to_increment(estimated_one_rm_kg * percentage / 100.0, increment);
+        debug!("📈 e1RM {:.1} kg from {} sets, {}% -> {} kg", estimated_one_rm_kg, estimates.len(), percentage, suggested_weight_kg);
+
+        info!("✅ Suggested {} kg for exercise {}", suggested_weight_kg, exercise_id);
+        Ok(LoadSuggestion {
+            exercise_id,
+            reps,
+            target_rpe,
+            estimated_one_rm_kg,
+            suggested_weight_kg,
+            sets_considered: estimates.len() as u32,
+        })
+    }
+}
@@ -0,0 +1,573 @@
+// Planned workouts with RFC 5545-style recurrence, exceptions and rescheduling
+
+use crate::calendar::{CalendarDate, Weekday};
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Upper bound on occurrences expanded for a single series, guarding against runaway rules
+const MAX_EXPANDED_OCCURRENCES: usize = 10_000;
+
+/// How often a recurring workout repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Subset of an RFC 5545 RRULE: FREQ, INTERVAL, BYDAY (weekly only), COUNT and UNTIL
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub by_weekday: Vec<Weekday>, // Weekly only; empty means the start date's weekday
+    pub count: Option<u32>,
+    pub until: Option<CalendarDate>, // Inclusive
+}
+
+/// A single occurrence that was cancelled (no new date) or moved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleException {
+    pub original_date: CalendarDate,
+    pub new_date: Option<CalendarDate>,
+}
+
+/// A workout planned on one date or repeating on a schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedWorkout {
+    pub id: String,
+    pub name: String,
+    pub exercise_ids: Vec<String>,
+    pub start_date: CalendarDate,
+    pub recurrence: Option<RecurrenceRule>,
+    pub exceptions: Vec<ScheduleException>,
+}
+
+/// A concrete date on which a planned workout takes place
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledOccurrence {
+    pub planned_workout_id: String,
+    pub name: String,
+    pub date: CalendarDate,
+    pub original_date: CalendarDate,
+    pub is_rescheduled: bool,
+}
+
+/// Parse an RRULE value such as `FREQ=WEEKLY;BYDAY=MO,WE,FR`
+pub fn parse_recurrence_rule(rrule: String) -> Result<RecurrenceRule, WeightliftingError> {
+    RecurrenceRule::parse(&rrule).map_err(|message| {
+        warn!("❌ Invalid recurrence rule '{}': {}", rrule, message);
+        WeightliftingError::InvalidInput { message }
+    })
+}
+
+/// Format a recurrence rule as an RRULE value
+pub fn format_recurrence_rule(rule: RecurrenceRule) -> String {
+    rule.to_rrule_string()
+}
+
+impl RecurrenceRule {
+    /// Parse an RRULE value (with or without the `RRULE:` prefix)
+    pub fn parse(rrule: &str) -> Result<Self, String> {
+        let body = rrule.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_weekday = Vec::new();
+        let mut count = None;
+        let mut until = None;
+
+        for part in body.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed rule part '{}'", part))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => RecurrenceFrequency::Daily,
+                        "WEEKLY" => RecurrenceFrequency::Weekly,
+                        "MONTHLY" => RecurrenceFrequency::Monthly,
+                        other => return Err(format!("Unsupported frequency '{}'", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value.parse().map_err(|_| format!("Invalid interval '{}'", value))?;
+                }
+                "BYDAY" => {
+                    for code in value.split(',') {
+                        let day = Weekday::from_code(&code.to_ascii_uppercase())
+                            .ok_or_else(|| format!("Invalid weekday '{}'", code))?;
+                        by_weekday.push(day);
+                    }
+                }
+                "COUNT" => {
+                    count = Some(value.parse().map_err(|_| format!("Invalid count '{}'", value))?);
+                }
+                "UNTIL" => {
+                    // Date-times are accepted but only their date is used
+                    let date = value.get(..8).and_then(CalendarDate::parse_basic)
+                        .ok_or_else(|| format!("Invalid until date '{}'", value))?;
+                    until = Some(date);
+                }
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(format!("Unsupported rule part '{}'", other)),
+            }
+        }
+
+        let rule = Self {
+            frequency: frequency.ok_or_else(|| "Rule is missing FREQ".to_string())?,
+            interval,
+            by_weekday,
+            count,
+            until,
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    /// Format as an RRULE value (without the `RRULE:` prefix)
+    pub fn to_rrule_string(&self) -> String {
+        let frequency = match self.frequency {
+            RecurrenceFrequency::Daily => "DAILY",
+            RecurrenceFrequency::Weekly => "WEEKLY",
+            RecurrenceFrequency::Monthly => "MONTHLY",
+        };
+
+        let mut parts = vec![format!("FREQ={}", frequency)];
+        if self.interval != 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if !self.by_weekday.is_empty() {
+            let days: Vec<&str> = self.by_weekday.iter().map(Weekday::code).collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("UNTIL={}", until.to_basic_string()));
+        }
+        parts.join(";")
+    }
+
+    /// Validate the rule's parameters
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("Interval must be at least 1".to_string());
+        }
+        if self.count == Some(0) {
+            return Err("Count must be at least 1".to_string());
+        }
+        if self.count.is_some() && self.until.is_some() {
+            return Err("COUNT and UNTIL cannot both be set".to_string());
+        }
+        if !self.by_weekday.is_empty() && self.frequency != RecurrenceFrequency::Weekly {
+            return Err("BYDAY is only supported for weekly rules".to_string());
+        }
+        if let Some(until) = self.until {
+            if !until.is_valid() {
+                return Err(format!("Invalid until date {:?}", until));
+            }
+        }
+        Ok(())
+    }
+
+    /// Expand the series starting at `start` into dates up to `last` (inclusive)
+    fn expand(&self, start: CalendarDate, last: CalendarDate) -> Vec<CalendarDate> {
+        let last = match self.until {
+            Some(until) => until.min(last),
+            None => last,
+        };
+        let limit = self.count.map_or(MAX_EXPANDED_OCCURRENCES, |c| (c as usize).min(MAX_EXPANDED_OCCURRENCES));
+        let interval = self.interval as i64;
+        let mut dates = Vec::new();
+
+        let mut push = |date: CalendarDate| -> bool {
+            if date > last || dates.len() >= limit {
+                return false;
+            }
+            dates.push(date);
+            true
+        };
+
+        match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let mut date = start;
+                while push(date) {
+                    date = date.add_days(interval);
+                }
+            }
+            RecurrenceFrequency::Weekly => {
+                let mut weekdays = if self.by_weekday.is_empty() { vec![start.weekday()] } else { self.by_weekday.clone() };
+                weekdays.sort();
+                weekdays.dedup();
+
+                let mut week = start.start_of_week();
+                'weeks: loop {
+                    for day in &weekdays {
+                        let date = week.add_days(day.index());
+                        if date >= start && !push(date) {
+                            break 'weeks;
+                        }
+                    }
+                    week = week.add_days(7 * interval);
+                }
+            }
+            RecurrenceFrequency::Monthly => {
+                // Months without the start's day of month are skipped, as in RFC 5545
+                let mut months = 0;
+                while start.start_of_month().add_months(months).is_some_and(|month| month <= last) {
+                    if let Some(date) = start.add_months(months) {
+                        if !push(date) {
+                            break;
+                        }
+                    }
+                    months += interval;
+                }
+            }
+        }
+        dates
+    }
+}
+
+impl PlannedWorkout {
+    /// Create a new planned workout with automatic UUID generation
+    pub fn new(name: String, exercise_ids: Vec<String>, start_date: CalendarDate, recurrence: Option<RecurrenceRule>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            exercise_ids,
+            start_date,
+            recurrence,
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// Validate the plan's name, dates and recurrence
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Planned workout name cannot be empty".to_string());
+        }
+        if !self.start_date.is_valid() {
+            return Err(format!("Invalid start date {:?}", self.start_date));
+        }
+        if let Some(ref rule) = self.recurrence {
+            rule.validate()?;
+        }
+        for exception in &self.exceptions {
+            if !exception.original_date.is_valid() || !exception.new_date.is_none_or(|d| d.is_valid()) {
+                return Err(format!("Invalid exception date {:?}", exception));
+            }
+        }
+        Ok(())
+    }
+
+    /// Dates of the unmodified series up to `last` (inclusive)
+    fn series_dates(&self, last: CalendarDate) -> Vec<CalendarDate> {
+        match self.recurrence {
+            Some(ref rule) => rule.expand(self.start_date, last),
+            None if self.start_date <= last => vec![self.start_date],
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether `date` is part of the unmodified series
+    fn is_series_date(&self, date: CalendarDate) -> bool {
+        self.series_dates(date).last() == Some(&date)
+    }
+
+    /// Occurrences falling between `start` and `end` (inclusive) after exceptions are applied
+    pub fn occurrences_between(&self, start: CalendarDate, end: CalendarDate) -> Vec<ScheduledOccurrence> {
+        let exceptions: HashMap<CalendarDate, Option<CalendarDate>> = self.exceptions
+            .iter()
+            .map(|e| (e.original_date, e.new_date))
+            .collect();
+
+        // Occurrences moved into the range may originate after it
+        let last_original = self.exceptions
+            .iter()
+            .filter(|e| e.new_date.is_some())
+            .map(|e| e.original_date)
+            .fold(end, CalendarDate::max);
+
+        let mut occurrences: Vec<ScheduledOccurrence> = self
+            .series_dates(last_original)
+            .into_iter()
+            .filter_map(|original_date| {
+                let date = match exceptions.get(&original_date) {
+                    Some(None) => return None,
+                    Some(Some(new_date)) => *new_date,
+                    None => original_date,
+                };
+                (date >= start && date <= end).then(|| ScheduledOccurrence {
+                    planned_workout_id: self.id.clone(),
+                    name: self.name.clone(),
+                    date,
+                    original_date,
+                    is_rescheduled: date != original_date,
+                })
+            })
+            .collect();
+
+        occurrences.sort_by_key(|o| o.date);
+        occurrences
+    }
+}
+
+/// Repository for planned workouts, sharing the exercise database
+pub struct ScheduleRepository {
+    repository: ExerciseRepository,
+}
+
+impl ScheduleRepository {
+    /// Add a planned workout
+    pub fn add_planned_workout(&self, workout: PlannedWorkout) -> Result<(), WeightliftingError> {
+        info!("📅 Adding planned workout: {} (ID: {})", workout.name, workout.id);
+        debug!("📝 Planned workout details: {:?}", workout);
+
+        workout.validate().map_err(|message| {
+            warn!("❌ Invalid planned workout: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+        let exercise_ids_json = serde_json::to_string(&workout.exercise_ids).map_err(|e| {
+            error!("❌ Failed to serialize exercise IDs: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize exercise IDs: {}", e),
+            }
+        })?;
+
+        let mut conn = self.repository.connection()?;
+        for exercise_id in &workout.exercise_ids {
+            ExerciseRepository::ensure_exercise_exists(&conn, exercise_id)?;
+        }
+
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO planned_workouts (id, name, exercise_ids, start_date, rrule) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                workout.id,
+                workout.name,
+                exercise_ids_json,
+                workout.start_date.to_basic_string(),
+                workout.recurrence.as_ref().map(RecurrenceRule::to_rrule_string)
+            ],
+        )?;
+        for exception in &workout.exceptions {
+            save_exception(&tx, &workout.id, exception)?;
+        }
+        tx.commit()?;
+
+        info!("✅ Successfully added planned workout: {}", workout.name);
+        Ok(())
+    }
+
+    /// Get a planned workout by ID
+    pub fn get_planned_workout(&self, id: String) -> Result<PlannedWorkout, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        Self::query_planned_workout(&conn, &id)
+    }
+
+    /// Get all planned workouts, sorted by name
+    pub fn get_all_planned_workouts(&self) -> Result<Vec<PlannedWorkout>, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        Self::query_planned_workouts(&conn, "ORDER BY name", [])
+    }
+
+    /// Delete a planned workout and its exceptions
+    /// Returns true if the planned workout was deleted, false if it wasn't found
+    pub fn delete_planned_workout(&self, id: String) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting planned workout with ID: {}", id);
+
+        let mut conn = self.repository.connection()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM schedule_exceptions WHERE planned_workout_id = ?1", [&id])?;
+        let rows_affected = tx.execute("DELETE FROM planned_workouts WHERE id = ?1", [&id])?;
+        tx.commit().map_err(|e| {
+            error!("❌ Failed to delete planned workout '{}': {}", id, e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to delete planned workout: {}", e),
+            }
+        })?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Cancel a single occurrence of a planned workout
+    pub fn cancel_occurrence(&self, id: String, original_date: CalendarDate) -> Result<(), WeightliftingError> {
+        info!("🚫 Cancelling {} occurrence on {:?}", id, original_date);
+        self.set_exception(&id, ScheduleException { original_date, new_date: None })
+    }
+
+    /// Move a single occurrence of a planned workout to another date
+    pub fn reschedule_occurrence(&self, id: String, original_date: CalendarDate, new_date: CalendarDate) -> Result<(), WeightliftingError> {
+        info!("🔀 Rescheduling {} occurrence from {:?} to {:?}", id, original_date, new_date);
+
+        if !new_date.is_valid() {
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Invalid date {:?}", new_date),
+            });
+        }
+        self.set_exception(&id, ScheduleException { original_date, new_date: Some(new_date) })
+    }
+
+    /// Undo a cancellation or reschedule, restoring the original occurrence
+    /// Returns true if an exception was removed
+    pub fn restore_occurrence(&self, id: String, original_date: CalendarDate) -> Result<bool, WeightliftingError> {
+        let conn = self.repository.connection()?;
+        let rows_affected = conn.execute(
+            "DELETE FROM schedule_exceptions WHERE planned_workout_id = ?1 AND original_date = ?2",
+            params![id, original_date.to_basic_string()],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get every planned occurrence between `start` and `end` (inclusive), in date order
+    pub fn get_occurrences_between(&self, start: CalendarDate, end: CalendarDate) -> Result<Vec<ScheduledOccurrence>, WeightliftingError> {
+        info!("📆 Expanding schedule between {:?} and {:?}", start, end);
+
+        if !start.is_valid() || !end.is_valid() {
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Invalid date range {:?} - {:?}", start, end),
+            });
+        }
+
+        let conn = self.repository.connection()?;
+        let mut occurrences: Vec<ScheduledOccurrence> = Self::query_planned_workouts(&conn, "ORDER BY name", [])?
+            .iter()
+            .flat_map(|workout| workout.occurrences_between(start, end))
+            .collect();
+        occurrences.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+
+        info!("✅ Found {} occurrences", occurrences.len());
+        Ok(occurrences)
+    }
+
+    fn set_exception(&self, id: &str, exception: ScheduleException) -> Result<(), WeightliftingError> {
+        let conn = self.repository.connection()?;
+        let workout = Self::query_planned_workout(&conn, id)?;
+
+        if !exception.original_date.is_valid() || !workout.is_series_date(exception.original_date) {
+            warn!("❌ {:?} is not an occurrence of planned workout {}", exception.original_date, id);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("{:?} is not an occurrence of planned workout {}", exception.original_date, id),
+            });
+        }
+
+        save_exception(&conn, id, &exception)
+    }
+
+    fn query_planned_workout(conn: &Connection, id: &str) -> Result<PlannedWorkout, WeightliftingError> {
+        Self::query_planned_workouts(conn, "WHERE id = ?1", [id])?
+            .pop()
+            .ok_or_else(|| {
+                warn!("❌ Planned workout not found with ID '{}'", id);
+                WeightliftingError::RecordNotFound { id: id.to_string() }
+            })
+    }
+
+    fn query_planned_workouts(conn: &Connection, clause: &str, clause_params: impl rusqlite::Params) -> Result<Vec<PlannedWorkout>, WeightliftingError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, name, exercise_ids, start_date, rrule FROM planned_workouts {}",
+            clause
+        ))?;
+        let mut workouts = stmt.query_map(clause_params, planned_workout_from_row)?.collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT original_date, new_date FROM schedule_exceptions WHERE planned_workout_id = ?1 ORDER BY original_date",
+        )?;
+        for workout in &mut workouts {
+            workout.exceptions = stmt
+                .query_map([&workout.id], |row| {
+                    Ok(ScheduleException {
+                        original_date: date_column(row, 0)?,
+                        new_date: row.get::<_, Option<String>>(1)?
+                            .map(|_| date_column(row, 1))
+                            .transpose()?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+        Ok(workouts)
+    }
+}
+
+fn save_exception(conn: &Connection, planned_workout_id: &str, exception: &ScheduleException) -> Result<(), WeightliftingError> {
+    conn.execute(
+        "INSERT INTO schedule_exceptions (planned_workout_id, original_date, new_date) VALUES (?1, ?2, ?3)
+         ON CONFLICT(planned_workout_id, original_date) DO UPDATE SET new_date = excluded.new_date",
+        params![
+            planned_workout_id,
+            exception.original_date.to_basic_string(),
+            exception.new_date.map(|d| d.to_basic_string())
+        ],
+    )?;
+    Ok(())
+}
+
+fn date_column(row: &Row, index: usize) -> rusqlite::Result<CalendarDate> {
+    let text: String = row.get(index)?;
+    CalendarDate::parse_basic(&text)
+        .ok_or_else(|| rusqlite::Error::InvalidColumnType(index, "date".to_string(), rusqlite::types::Type::Text))
+}
+
+fn planned_workout_from_row(row: &Row) -> rusqlite::Result<PlannedWorkout> {
+    let exercise_ids_json: String = row.get(2)?;
+    let exercise_ids: Vec<String> = serde_json::from_str(&exercise_ids_json)
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(2, "exercise_ids".to_string(), rusqlite::types::Type::Text))?;
+    let recurrence = row.get::<_, Option<String>>(4)?
+        .map(|rrule| RecurrenceRule::parse(&rrule))
+        .transpose()
+        .map_err(|_e| rusqlite::Error::InvalidColumnType(4, "rrule".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(PlannedWorkout {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        exercise_ids,
+        start_date: date_column(row, 3)?,
+        recurrence,
+        exceptions: Vec::new(),
+    })
+}
+
+impl ExerciseRepository {
+    /// Get the schedule repository backed by the same database
+    pub fn schedule(&self) -> Arc<ScheduleRepository> {
+        Arc::new(ScheduleRepository { repository: self.clone() })
+    }
+
+    /// Create the scheduling tables if they don't exist
+    pub(crate) fn create_schedule_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating planned_workouts and schedule_exceptions tables if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS planned_workouts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                exercise_ids TEXT NOT NULL,
+                start_date TEXT NOT NULL,
+                rrule TEXT
+            );
+            CREATE TABLE IF NOT EXISTS schedule_exceptions (
+                planned_workout_id TEXT NOT NULL,
+                original_date TEXT NOT NULL,
+                new_date TEXT,
+                PRIMARY KEY (planned_workout_id, original_date)
+            );",
+        ).map_err(|e| {
+            error!("❌ Failed to create schedule tables: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Schedule tables ready");
+        Ok(())
+    }
+}
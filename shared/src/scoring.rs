@@ -0,0 +1,326 @@
+// Powerlifting scores that compare totals across bodyweights, and the lifter's best total
+
+use crate::body_measurements::BodyMeasurementRepository;
+use crate::error::WeightliftingError;
+use crate::models::LoggedSet;
+use crate::repository::ExerciseRepository;
+use crate::rpe::estimate_logged_one_rm;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+const LIFTER_PROFILE_SETTING: &str = "lifter_profile";
+
+/// Wilks (1994) polynomial coefficients a-f and the bodyweight range they were fitted on
+const WILKS_MALE: ([f64; 6], (f64, f64)) = (
+    [-216.0475144, 16.2606339, -0.002388645, -0.00113732, 7.01863e-6, -1.291e-8],
+    (40.0, 201.9),
+);
+const WILKS_FEMALE: ([f64; 6], (f64, f64)) = (
+    [594.31747775582, -27.23842536447, 0.82112226871, -0.00930733913, 4.731582e-5, -9.054e-8],
+    (26.51, 154.53),
+);
+
+/// Wilks 2020 polynomial coefficients a-f and bodyweight range
+const WILKS_2020_MALE: ([f64; 6], (f64, f64)) = (
+    [47.46178854, 8.472061379, 0.07369410346, -0.001395833811, 7.07665973070743e-6, -1.20804336482315e-8],
+    (40.0, 200.95),
+);
+const WILKS_2020_FEMALE: ([f64; 6], (f64, f64)) = (
+    [-125.4255398, 13.71219419, -0.03307250631, -0.001050400051, 9.38773881462799e-6, -2.3334613884954e-8],
+    (40.0, 150.95),
+);
+
+/// DOTS polynomial coefficients a-e and bodyweight range
+const DOTS_MALE: ([f64; 5], (f64, f64)) = (
+    [-307.75076, 24.0900756, -0.1918759221, 0.0007391293, -0.000001093],
+    (40.0, 210.0),
+);
+const DOTS_FEMALE: ([f64; 5], (f64, f64)) = (
+    [-57.96288, 13.6175032, -0.1126655495, 0.0005158568, -0.0000010706],
+    (40.0, 150.0),
+);
+
+/// IPF GL points are not defined below this bodyweight
+const IPF_GL_MIN_BODYWEIGHT_KG: f64 = 35.0;
+
+/// Sex category used by the scoring formulas and strength standards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+/// Whether supportive equipment (suits, shirts) was worn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompetitionEquipment {
+    Classic,
+    Equipped,
+}
+
+/// The lifts a total is made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompetitionEvent {
+    FullPower, // Squat, bench press and deadlift
+    BenchOnly,
+}
+
+/// Every score for one total
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerliftingScores {
+    pub total_kg: f64,
+    pub bodyweight_kg: f64,
+    pub wilks: f64,
+    pub wilks_2020: f64,
+    pub dots: f64,
+    pub ipf_gl: f64,
+}
+
+/// Who the lifter is and which exercises count as their competition lifts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LifterProfile {
+    pub sex: Sex,
+    pub equipment: CompetitionEquipment,
+    pub squat_exercise_id: Option<String>,
+    pub bench_exercise_id: Option<String>,
+    pub deadlift_exercise_id: Option<String>,
+}
+
+/// The heaviest set logged for a lift
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiftRecord {
+    pub exercise_id: String,
+    pub weight_kg: f64,
+    pub reps: u32,
+    pub performed_at: i64,
+    pub estimated_one_rm_kg: Option<f64>, // Best estimate over all sets, not just this one
+}
+
+/// The lifter's best squat, bench and deadlift and the total they add up to
+#[derive(Debug, Clone, PartialEq)]
+pub struct BestTotal {
+    pub squat: Option<LiftRecord>,
+    pub bench: Option<LiftRecord>,
+    pub deadlift: Option<LiftRecord>,
+    pub total_kg: Option<f64>,           // Heaviest weights lifted, once all three lifts have a record
+    pub estimated_total_kg: Option<f64>, // Sum of the best estimated 1RMs
+    pub bodyweight_kg: Option<f64>,      // Latest bodyweight on record at the time
+    pub scores: Option<PowerliftingScores>, // Needs both a total and a bodyweight
+}
+
+impl CompetitionEquipment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompetitionEquipment::Classic => "classic",
+            CompetitionEquipment::Equipped => "equipped",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        [CompetitionEquipment::Classic, CompetitionEquipment::Equipped]
+            .into_iter()
+            .find(|equipment| equipment.as_str() == name)
+    }
+}
+
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+fn validate_score_inputs(total_kg: f64, bodyweight_kg: f64) -> Result<(), WeightliftingError> {
+    for (label, value) in [("Total", total_kg), ("Bodyweight", bodyweight_kg)] {
+        if !value.is_finite() || value <= 0.0 {
+            warn!("❌ Invalid {} for scoring: {}", label.to_lowercase(), value);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("{} must be positive, got {}", label, value),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Score a total with a polynomial formula, clamping bodyweight to the fitted range
+fn polynomial_points(total_kg: f64, bodyweight_kg: f64, (coefficients, (min, max)): (&[f64], (f64, f64)), numerator: f64) -> f64 {
+    total_kg * numerator / polynomial(coefficients, bodyweight_kg.clamp(min, max))
+}
+
+/// Original Wilks points
+pub fn wilks_points(total_kg: f64, bodyweight_kg: f64, sex: Sex) -> Result<f64, WeightliftingError> {
+    validate_score_inputs(total_kg, bodyweight_kg)?;
+    let (coefficients, range) = if sex == Sex::Male { WILKS_MALE } else { WILKS_FEMALE };
+    Ok(polynomial_points(total_kg, bodyweight_kg, (&coefficients, range), 500.0))
+}
+
+/// Wilks 2020 points
+pub fn wilks_2020_points(total_kg: f64, bodyweight_kg: f64, sex: Sex) -> Result<f64, WeightliftingError> {
+    validate_score_inputs(total_kg, bodyweight_kg)?;
+    let (coefficients, range) = if sex == Sex::Male { WILKS_2020_MALE } else { WILKS_2020_FEMALE };
+    Ok(polynomial_points(total_kg, bodyweight_kg, (&coefficients, range), 600.0))
+}
+
+/// DOTS points
+pub fn dots_points(total_kg: f64, bodyweight_kg: f64, sex: Sex) -> Result<f64, WeightliftingError> {
+    validate_score_inputs(total_kg, bodyweight_kg)?;
+    let (coefficients, range) = if sex == Sex::Male { DOTS_MALE } else { DOTS_FEMALE };
+    Ok(polynomial_points(total_kg, bodyweight_kg, (&coefficients, range), 500.0))
+}
+
+/// IPF GL points (2020 formula); 0 below 35 kg bodyweight where the formula isn't defined
+pub fn ipf_gl_points(
+    total_kg: f64,
+    bodyweight_kg: f64,
+    sex: Sex,
+    equipment: CompetitionEquipment,
+    event: CompetitionEvent,
+) -> Result<f64, WeightliftingError> {
+    validate_score_inputs(total_kg, bodyweight_kg)?;
+    if bodyweight_kg < IPF_GL_MIN_BODYWEIGHT_KG {
+        return Ok(0.0);
+    }
+
+    use CompetitionEquipment::*;
+    use CompetitionEvent::*;
+    let (a, b, c) = match (sex, equipment, event) {
+        (Sex::Male, Classic, FullPower) => (1199.72839, 1025.18162, 0.00921),
+        (Sex::Male, Equipped, FullPower) => (1236.25115, 1449.21864, 0.01644),
+        (Sex::Male, Classic, BenchOnly) => (320.98041, 281.40258, 0.01008),
+        (Sex::Male, Equipped, BenchOnly) => (381.22073, 733.79378, 0.02398),
+        (Sex::Female, Classic, FullPower) => (610.32796, 1045.59282, 0.03048),
+        (Sex::Female, Equipped, FullPower) => (758.63878, 949.31382, 0.02435),
+        (Sex::Female, Classic, BenchOnly) => (142.40398, 442.52671, 0.04724),
+        (Sex::Female, Equipped, BenchOnly) => (221.82209, 357.00377, 0.02937),
+    };
+    Ok(total_kg * 100.0 / (a - b * (-c * bodyweight_kg).exp()))
+}
+
+/// Every score for a full-power total
+pub fn powerlifting_scores(
+    total_kg: f64,
+    bodyweight_kg: f64,
+    sex: Sex,
+    equipment: CompetitionEquipment,
+) -> Result<PowerliftingScores, WeightliftingError> {
+    Ok(PowerliftingScores {
+        total_kg,
+        bodyweight_kg,
+        wilks: wilks_points(total_kg, bodyweight_kg, sex)?,
+        wilks_2020: wilks_2020_points(total_kg, bodyweight_kg, sex)?,
+        dots: dots_points(total_kg, bodyweight_kg, sex)?,
+        ipf_gl: ipf_gl_points(total_kg, bodyweight_kg, sex, equipment, CompetitionEvent::FullPower)?,
+    })
+}
+
+/// Heaviest strength-comparable set of a lift, earliest first on ties
+fn lift_record(exercise_id: &str, sets: &[LoggedSet]) -> Option<LiftRecord> {
+    let comparable: Vec<&LoggedSet> = sets.iter().filter(|s| s.is_strength_comparable()).collect();
+    let heaviest = comparable
+        .iter()
+        .copied()
+        .reduce(|best, set| if set.weight_kg > best.weight_kg { set } else { best })?;
+
+    Some(LiftRecord {
+        exercise_id: exercise_id.to_string(),
+        weight_kg: heaviest.weight_kg,
+        reps: heaviest.reps,
+        performed_at: heaviest.performed_at,
+        estimated_one_rm_kg: comparable.iter().copied().filter_map(estimate_logged_one_rm).reduce(f64::max),
+    })
+}
+
+impl ExerciseRepository {
+    /// Save the lifter's profile, replacing any existing one
+    pub fn set_lifter_profile(&self, profile: LifterProfile) -> Result<(), WeightliftingError> {
+        info!("🏆 Saving lifter profile");
+        debug!("📝 Profile: {:?}", profile);
+
+        let conn = self.connection()?;
+        let lifts = [&profile.squat_exercise_id, &profile.bench_exercise_id, &profile.deadlift_exercise_id];
+        for exercise_id in lifts.into_iter().flatten() {
+            Self::ensure_exercise_exists(&conn, exercise_id)?;
+        }
+
+        let profile_json = serde_json::to_string(&profile).map_err(|e| {
+            error!("❌ Failed to serialize lifter profile: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to serialize lifter profile: {}", e),
+            }
+        })?;
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![LIFTER_PROFILE_SETTING, profile_json],
+        )?;
+        Ok(())
+    }
+
+    /// Get the lifter's profile, if one has been saved
+    pub fn get_lifter_profile(&self) -> Result<Option<LifterProfile>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::query_lifter_profile(&conn)
+    }
+
+    /// Get the best squat, bench and deadlift logged up to `at`, with the total and its scores
+    ///
+    /// Fails with InsufficientData until a lifter profile has been saved.
+    pub fn get_best_total(&self, at: i64) -> Result<BestTotal, WeightliftingError> {
+        info!("🏆 Computing best total as of {}", at);
+
+        let conn = self.connection()?;
+        let profile = Self::query_lifter_profile(&conn)?.ok_or_else(|| {
+            warn!("❌ No lifter profile for best total");
+            WeightliftingError::InsufficientData {
+                message: "Set a lifter profile to track the best total".to_string(),
+            }
+        })?;
+
+        let record_for = |exercise_id: &Option<String>| -> Result<Option<LiftRecord>, WeightliftingError> {
+            match exercise_id {
+                Some(id) => Ok(lift_record(id, &Self::query_sets_between(&conn, Some(id), i64::MIN, at)?)),
+                None => Ok(None),
+            }
+        };
+        let squat = record_for(&profile.squat_exercise_id)?;
+        let bench = record_for(&profile.bench_exercise_id)?;
+        let deadlift = record_for(&profile.deadlift_exercise_id)?;
+
+        let lifts = [&squat, &bench, &deadlift];
+        let total_kg = lifts
+            .iter()
+            .map(|record| record.as_ref().map(|r| r.weight_kg))
+            .sum::<Option<f64>>();
+        let estimated_total_kg = lifts
+            .iter()
+            .map(|record| record.as_ref().and_then(|r| r.estimated_one_rm_kg))
+            .sum::<Option<f64>>();
+        let bodyweight_kg = BodyMeasurementRepository::bodyweight_at(&conn, at)?;
+
+        let scores = match (total_kg, bodyweight_kg) {
+            (Some(total), Some(bodyweight)) if total > 0.0 => {
+                Some(powerlifting_scores(total, bodyweight, profile.sex, profile.equipment)?)
+            }
+            _ => None,
+        };
+
+        info!("✅ Best total: {:?} kg", total_kg);
+        Ok(BestTotal { squat, bench, deadlift, total_kg, estimated_total_kg, bodyweight_kg, scores })
+    }
+
+    pub(crate) fn query_lifter_profile(conn: &Connection) -> Result<Option<LifterProfile>, WeightliftingError> {
+        let profile_json: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [LIFTER_PROFILE_SETTING],
+            |row| row.get(0),
+        ).optional()?;
+
+        profile_json
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    error!("❌ Failed to parse lifter profile: {}", e);
+                    WeightliftingError::DatabaseError {
+                        message: format!("Failed to parse lifter profile: {}", e),
+                    }
+                })
+            })
+            .transpose()
+    }
+}
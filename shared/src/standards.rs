@@ -0,0 +1,333 @@
+// Strength standards: where a lift ranks for the lifter's bodyweight and sex
+
+use crate::body_measurements::BodyMeasurementRepository;
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::rpe::estimate_logged_one_rm;
+use crate::scoring::Sex;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+// Standards come from one ratio model rather than per-lift tables:
+//
+//   1RM = LEVEL_MULTIPLES[level] * lift factor * reference bodyweight * (bodyweight / reference)^(2/3)
+//
+// The level ladder is evenly spaced in multiples of bodyweight for a male
+// squat at the reference bodyweight. Each lift and sex scales that ladder by
+// one factor, so every lift keeps the same spacing between levels. The 2/3
+// power is allometric scaling: strength grows with muscle cross-section, so
+// heavier lifters need more weight but less per kilogram of bodyweight.
+// The multiples and factors are the app's own calibration, not a published dataset.
+
+/// Bodyweight multiples for each level, Beginner to Elite, for the reference lifter's squat
+const LEVEL_MULTIPLES: [f64; 5] = [0.5, 1.0, 1.5, 2.0, 2.5];
+
+/// Bodyweight the level multiples are stated at, by sex
+const MALE_REFERENCE_BODYWEIGHT_KG: f64 = 80.0;
+const FEMALE_REFERENCE_BODYWEIGHT_KG: f64 = 60.0;
+
+/// Exponent of the allometric bodyweight scaling
+const ALLOMETRIC_EXPONENT: f64 = 2.0 / 3.0;
+
+/// A lift the strength standards cover
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CanonicalLift {
+    Squat,
+    BenchPress,
+    Deadlift,
+    OverheadPress,
+    BarbellRow,
+}
+
+/// Strength level, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StrengthLevel {
+    Beginner,
+    Novice,
+    Intermediate,
+    Advanced,
+    Elite,
+}
+
+/// The 1RM needed to reach a level
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrengthThreshold {
+    pub level: StrengthLevel,
+    pub one_rm_kg: f64,
+}
+
+/// Where a lift ranks among the standards
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrengthClassification {
+    pub lift: CanonicalLift,
+    pub exercise_id: Option<String>, // Set when classified from an exercise's logged sets
+    pub one_rm_kg: f64,
+    pub bodyweight_kg: f64,
+    pub bodyweight_ratio: f64,
+    pub level: StrengthLevel, // Beginner also covers lifts below the Beginner standard
+    pub next_level: Option<StrengthLevel>,
+    pub kg_to_next_level: Option<f64>,
+    pub thresholds: Vec<StrengthThreshold>,
+}
+
+impl CanonicalLift {
+    pub const ALL: [CanonicalLift; 5] = [
+        CanonicalLift::Squat,
+        CanonicalLift::BenchPress,
+        CanonicalLift::Deadlift,
+        CanonicalLift::OverheadPress,
+        CanonicalLift::BarbellRow,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CanonicalLift::Squat => "squat",
+            CanonicalLift::BenchPress => "bench_press",
+            CanonicalLift::Deadlift => "deadlift",
+            CanonicalLift::OverheadPress => "overhead_press",
+            CanonicalLift::BarbellRow => "barbell_row",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lift| lift.as_str() == name)
+    }
+
+    /// How the lift compares with the male squat ladder, for each sex
+    fn factor(&self, sex: Sex) -> f64 {
+        match (self, sex) {
+            (CanonicalLift::Squat, Sex::Male) => 1.0,
+            (CanonicalLift::BenchPress, Sex::Male) => 0.75,
+            (CanonicalLift::Deadlift, Sex::Male) => 1.2,
+            (CanonicalLift::OverheadPress, Sex::Male) => 0.5,
+            (CanonicalLift::BarbellRow, Sex::Male) => 0.6,
+            (CanonicalLift::Squat, Sex::Female) => 0.8,
+            (CanonicalLift::BenchPress, Sex::Female) => 0.5,
+            (CanonicalLift::Deadlift, Sex::Female) => 0.95,
+            (CanonicalLift::OverheadPress, Sex::Female) => 0.35,
+            (CanonicalLift::BarbellRow, Sex::Female) => 0.45,
+        }
+    }
+}
+
+impl StrengthLevel {
+    pub const ALL: [StrengthLevel; 5] = [
+        StrengthLevel::Beginner,
+        StrengthLevel::Novice,
+        StrengthLevel::Intermediate,
+        StrengthLevel::Advanced,
+        StrengthLevel::Elite,
+    ];
+}
+
+fn validate_positive(label: &str, value: f64) -> Result<(), WeightliftingError> {
+    if !value.is_finite() || value <= 0.0 {
+        warn!("❌ Invalid {} for strength standards: {}", label.to_lowercase(), value);
+        return Err(WeightliftingError::InvalidInput {
+            message: format!("{} must be positive, got {}", label, value),
+        });
+    }
+    Ok(())
+}
+
+/// The 1RM needed for each level at `bodyweight_kg`
+pub fn strength_standards(lift: CanonicalLift, bodyweight_kg: f64, sex: Sex) -> Result<Vec<StrengthThreshold>, WeightliftingError> {
+    validate_positive("Bodyweight", bodyweight_kg)?;
+
+    let reference_kg = match sex {
+        Sex::Male => MALE_REFERENCE_BODYWEIGHT_KG,
+        Sex::Female => FEMALE_REFERENCE_BODYWEIGHT_KG,
+    };
+    let scaled_bodyweight_kg = reference_kg * (bodyweight_kg / reference_kg).powf(ALLOMETRIC_EXPONENT);
+    let factor = lift.factor(sex);
+
+    Ok(StrengthLevel::ALL
+        .into_iter()
+        .zip(LEVEL_MULTIPLES)
+        .map(|(level, multiple)| StrengthThreshold { level, one_rm_kg: multiple * factor * scaled_bodyweight_kg })
+        .collect())
+}
+
+/// Classify a 1RM against the standards for the lifter's bodyweight and sex
+pub fn classify_strength(lift: CanonicalLift, one_rm_kg: f64, bodyweight_kg: f64, sex: Sex) -> Result<StrengthClassification, WeightliftingError> {
+    validate_positive("1RM", one_rm_kg)?;
+    let thresholds = strength_standards(lift, bodyweight_kg, sex)?;
+
+    let level = thresholds
+        .iter()
+        .rev()
+        .find(|t| one_rm_kg >= t.one_rm_kg)
+        .map_or(StrengthLevel::Beginner, |t| t.level);
+    let next = thresholds.iter().find(|t| t.level > level && one_rm_kg < t.one_rm_kg);
+    debug!("📏 {:?} {} kg at {} kg bodyweight: {:?}", lift, one_rm_kg, bodyweight_kg, level);
+
+    Ok(StrengthClassification {
+        lift,
+        exercise_id: None,
+        one_rm_kg,
+        bodyweight_kg,
+        bodyweight_ratio: one_rm_kg / bodyweight_kg,
+        level,
+        next_level: next.map(|t| t.level),
+        kg_to_next_level: next.map(|t| t.one_rm_kg - one_rm_kg),
+        thresholds,
+    })
+}
+
+impl ExerciseRepository {
+    /// Set or clear the canonical lift an exercise is compared against
+    pub fn set_canonical_lift(&self, exercise_id: String, lift: Option<CanonicalLift>) -> Result<(), WeightliftingError> {
+        info!("📏 Mapping exercise {} to {:?}", exercise_id, lift);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        match lift {
+            Some(lift) => {
+                conn.execute(
+                    "INSERT INTO exercise_canonical_lifts (exercise_id, lift) VALUES (?1, ?2)
+                     ON CONFLICT(exercise_id) DO UPDATE SET lift = excluded.lift",
+                    params![exercise_id, lift.as_str()],
+                )?;
+            }
+            None => Self::delete_canonical_lift(&conn, &exercise_id)?,
+        }
+        Ok(())
+    }
+
+    /// Get the canonical lift an exercise is compared against, if any
+    pub fn get_canonical_lift(&self, exercise_id: String) -> Result<Option<CanonicalLift>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        Self::query_canonical_lift(&conn, &exercise_id)
+    }
+
+    /// Classify an exercise's best estimated 1RM up to `at`
+    ///
+    /// Uses the sex from the lifter profile and the latest bodyweight on record.
+    pub fn classify_exercise_strength(&self, exercise_id: String, at: i64) -> Result<StrengthClassification, WeightliftingError> {
+        info!("📏 Classifying strength for exercise {} as of {}", exercise_id, at);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        let lift = Self::query_canonical_lift(&conn, &exercise_id)?.ok_or_else(|| {
+            warn!("❌ Exercise {} has no canonical lift", exercise_id);
+            WeightliftingError::InsufficientData {
+                message: format!("Exercise {} isn't mapped to a lift the strength standards cover", exercise_id),
+            }
+        })?;
+        let (sex, bodyweight_kg) = Self::standards_lifter(&conn, at)?;
+
+        Self::classify_logged_strength(&conn, &exercise_id, lift, sex, bodyweight_kg, at)?.ok_or_else(|| {
+            warn!("❌ No sets of {} to estimate a 1RM from", exercise_id);
+            WeightliftingError::InsufficientData {
+                message: format!("No sets of {} to estimate a 1RM from", exercise_id),
+            }
+        })
+    }
+
+    /// Classify every mapped exercise with logged sets up to `at`, strongest level first
+    pub fn get_strength_overview(&self, at: i64) -> Result<Vec<StrengthClassification>, WeightliftingError> {
+        info!("📏 Building strength overview as of {}", at);
+
+        let conn = self.connection()?;
+        let (sex, bodyweight_kg) = Self::standards_lifter(&conn, at)?;
+
+        let mut stmt = conn.prepare("SELECT exercise_id, lift FROM exercise_canonical_lifts ORDER BY exercise_id")?;
+        let mappings = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut overview = Vec::new();
+        for (exercise_id, lift_name) in mappings {
+            let Some(lift) = CanonicalLift::parse(&lift_name) else {
+                warn!("⚠️ Skipping unknown canonical lift '{}'", lift_name);
+                continue;
+            };
+            if let Some(classification) = Self::classify_logged_strength(&conn, &exercise_id, lift, sex, bodyweight_kg, at)? {
+                overview.push(classification);
+            }
+        }
+        overview.sort_by_key(|c| std::cmp::Reverse(c.level));
+
+        info!("✅ Classified {} exercises", overview.len());
+        Ok(overview)
+    }
+
+    /// Sex from the lifter profile and bodyweight at `at`, both needed for the standards
+    fn standards_lifter(conn: &Connection, at: i64) -> Result<(Sex, f64), WeightliftingError> {
+        let sex = Self::query_lifter_profile(conn)?.map(|profile| profile.sex).ok_or_else(|| {
+            warn!("❌ No lifter profile for strength standards");
+            WeightliftingError::InsufficientData {
+                message: "Set a lifter profile to compare against strength standards".to_string(),
+            }
+        })?;
+        let bodyweight_kg = BodyMeasurementRepository::bodyweight_at(conn, at)?.ok_or_else(|| {
+            warn!("❌ No bodyweight on record for strength standards");
+            WeightliftingError::InsufficientData {
+                message: "Log a bodyweight to compare against strength standards".to_string(),
+            }
+        })?;
+        Ok((sex, bodyweight_kg))
+    }
+
+    fn classify_logged_strength(
+        conn: &Connection,
+        exercise_id: &str,
+        lift: CanonicalLift,
+        sex: Sex,
+        bodyweight_kg: f64,
+        at: i64,
+    ) -> Result<Option<StrengthClassification>, WeightliftingError> {
+        let sets = Self::query_sets_between(conn, Some(exercise_id), i64::MIN, at)?;
+        let Some(one_rm_kg) = sets.iter().filter_map(estimate_logged_one_rm).reduce(f64::max) else {
+            return Ok(None);
+        };
+
+        let classification = classify_strength(lift, one_rm_kg, bodyweight_kg, sex)?;
+        Ok(Some(StrengthClassification { exercise_id: Some(exercise_id.to_string()), ..classification }))
+    }
+
+    fn query_canonical_lift(conn: &Connection, exercise_id: &str) -> Result<Option<CanonicalLift>, WeightliftingError> {
+        let lift_name: Option<String> = conn.query_row(
+            "SELECT lift FROM exercise_canonical_lifts WHERE exercise_id = ?1",
+            [exercise_id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(lift_name.as_deref().and_then(CanonicalLift::parse))
+    }
+
+    /// IDs of the exercises mapped to `lift`
+    pub(crate) fn query_exercises_for_lift(conn: &Connection, lift: CanonicalLift) -> Result<Vec<String>, WeightliftingError> {
+        let mut stmt = conn.prepare("SELECT exercise_id FROM exercise_canonical_lifts WHERE lift = ?1")?;
+        let exercise_ids = stmt.query_map([lift.as_str()], |row| row.get(0))?.collect::<Result<Vec<String>, _>>()?;
+        Ok(exercise_ids)
+    }
+
+    /// Remove an exercise's canonical lift mapping
+    pub(crate) fn delete_canonical_lift(conn: &Connection, exercise_id: &str) -> Result<(), WeightliftingError> {
+        conn.execute("DELETE FROM exercise_canonical_lifts WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(())
+    }
+
+    /// Create the canonical lift mapping table if it doesn't exist
+    pub(crate) fn create_standards_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating exercise_canonical_lifts table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS exercise_canonical_lifts (
+                exercise_id TEXT PRIMARY KEY,
+                lift TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create exercise_canonical_lifts table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Strength standards table ready");
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+// Small numeric helpers shared by the time-series features
+
+/// Seconds in one week, used to express slopes per week
+pub const SECONDS_PER_WEEK: f64 = 604_800.0;
+
+/// A single value at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeriesPoint {
+    pub timestamp: i64, // Unix timestamp in seconds
+    pub value: f64,
+}
+
+/// Trailing moving average over the last `window` points
+///
+/// The first points average over however many points are available, so the
+/// output has the same length and timestamps as the input.
+pub fn moving_average(points: &[TimeSeriesPoint], window: usize) -> Vec<TimeSeriesPoint> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            sum += point.value;
+            if i >= window {
+                sum -= points[i - window].value;
+            }
+            TimeSeriesPoint {
+                timestamp: point.timestamp,
+                value: sum / (i + 1).min(window) as f64,
+            }
+        })
+        .collect()
+}
+
+/// Least-squares slope of the values, in value units per week
+///
+/// Returns None with fewer than two points or when all points share a timestamp.
+pub fn slope_per_week(points: &[TimeSeriesPoint]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    // Center on the first timestamp to keep the sums well conditioned
+    let origin = points[0].timestamp;
+    let n = points.len() as f64;
+    let xs: Vec<f64> = points.iter().map(|p| (p.timestamp - origin) as f64 / SECONDS_PER_WEEK).collect();
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.value).sum::<f64>() / n;
+
+    let (covariance, variance) = xs.iter().zip(points).fold((0.0, 0.0), |(cov, var), (x, p)| {
+        (cov + (x - mean_x) * (p.value - mean_y), var + (x - mean_x).powi(2))
+    });
+
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// Indices of the points Largest-Triangle-Three-Buckets keeps when reducing to `max_points`
+///
+/// Keeps the first and last point and, from each bucket in between, the point
+/// forming the largest triangle with the previously kept point and the next
+/// bucket's average. Returns every index when there are no more than `max_points`.
+pub fn lttb_indices(points: &[TimeSeriesPoint], max_points: usize) -> Vec<usize> {
+    let n = points.len();
+    if n <= max_points || n <= 2 {
+        return (0..n).collect();
+    }
+    if max_points < 3 {
+        // Too few points for any buckets; keep the endpoints that fit
+        return [0, n - 1].into_iter().take(max_points).collect();
+    }
+
+    let x = |i: usize| points[i].timestamp as f64;
+    let y = |i: usize| points[i].value;
+    // Buckets over the points between the fixed first and last ones
+    let bucket_size = (n - 2) as f64 / (max_points - 2) as f64;
+    let bucket_bounds = |b: usize| {
+        let start = (b as f64 * bucket_size) as usize + 1;
+        let end = (((b + 1) as f64 * bucket_size) as usize + 1).min(n - 1);
+        (start, end)
+    };
+
+    let mut kept = Vec::with_capacity(max_points);
+    kept.push(0);
+    let mut previous = 0;
+    for b in 0..max_points - 2 {
+        let (start, end) = bucket_bounds(b);
+        let (next_start, next_end) = if b + 1 < max_points - 2 { bucket_bounds(b + 1) } else { (n - 1, n) };
+        let count = (next_end - next_start) as f64;
+        let (avg_x, avg_y) = (next_start..next_end).fold((0.0, 0.0), |(sx, sy), i| (sx + x(i) / count, sy + y(i) / count));
+
+        let area = |i: usize| ((x(previous) - avg_x) * (y(i) - y(previous)) - (x(previous) - x(i)) * (avg_y - y(previous))).abs();
+        let chosen = (start..end).max_by(|&a, &b| area(a).total_cmp(&area(b))).unwrap_or(start);
+        kept.push(chosen);
+        previous = chosen;
+    }
+    kept.push(n - 1);
+    kept
+}
+
+/// Reduce a series to at most `max_points` points with Largest-Triangle-Three-Buckets
+pub fn downsample_series(points: Vec<TimeSeriesPoint>, max_points: u32) -> Vec<TimeSeriesPoint> {
+    lttb_indices(&points, max_points as usize)
+        .into_iter()
+        .map(|i| points[i].clone())
+        .collect()
+}
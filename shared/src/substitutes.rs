@@ -0,0 +1,101 @@
+// Ranked substitutes for an exercise when it can't be performed
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, info};
+use std::collections::HashSet;
+
+/// Contribution of each component to the overall substitute score
+const MUSCLE_OVERLAP_WEIGHT: f64 = 0.6;
+const EQUIPMENT_WEIGHT: f64 = 0.25;
+const DIFFICULTY_WEIGHT: f64 = 0.15;
+/// Largest possible gap on the 1-10 difficulty scale
+const MAX_DIFFICULTY_GAP: f64 = 9.0;
+
+/// A candidate replacement for an exercise, with its score breakdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubstituteCandidate {
+    pub exercise: Exercise,
+    pub score: f64,                // Weighted total, 0-1
+    pub muscle_overlap_score: f64, // Jaccard similarity of muscle groups, 0-1
+    pub equipment_score: f64,      // 1 when all required equipment is available, else 0
+    pub difficulty_score: f64,     // 1 for the same difficulty, 0 for the largest gap
+}
+
+impl SubstituteCandidate {
+    /// Score `candidate` as a replacement for `original`
+    fn score(original: &Exercise, candidate: Exercise, available_equipment: &HashSet<String>) -> Self {
+        let original_muscles = normalized_set(&original.muscle_groups);
+        let candidate_muscles = normalized_set(&candidate.muscle_groups);
+        let union = original_muscles.union(&candidate_muscles).count();
+        let muscle_overlap_score = if union == 0 {
+            0.0
+        } else {
+            original_muscles.intersection(&candidate_muscles).count() as f64 / union as f64
+        };
+
+        let performable = candidate.equipment_ids.iter().all(|id| available_equipment.contains(id));
+        let equipment_score = if performable { 1.0 } else { 0.0 };
+
+        let gap = (original.difficulty_level as f64 - candidate.difficulty_level as f64).abs();
+        let difficulty_score = (1.0 - gap / MAX_DIFFICULTY_GAP).max(0.0);
+
+        let score = MUSCLE_OVERLAP_WEIGHT * muscle_overlap_score
+            + EQUIPMENT_WEIGHT * equipment_score
+            + DIFFICULTY_WEIGHT * difficulty_score;
+
+        Self {
+            exercise: candidate,
+            score,
+            muscle_overlap_score,
+            equipment_score,
+            difficulty_score,
+        }
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+fn normalized_set(values: &[String]) -> HashSet<String> {
+    values.iter().map(|value| normalize(value)).collect()
+}
+
+impl ExerciseRepository {
+    /// Suggest exercises that can replace `exercise_id`, best match first
+    ///
+    /// Candidates must share at least one muscle group with the original.
+    /// Exercises needing equipment outside `available_equipment` are still
+    /// returned, but rank below equally similar ones that can be performed.
+    pub fn suggest_substitutes(
+        &self,
+        exercise_id: String,
+        available_equipment: Vec<String>,
+        limit: u32,
+    ) -> Result<Vec<SubstituteCandidate>, WeightliftingError> {
+        info!("🔄 Suggesting up to {} substitutes for exercise {}", limit, exercise_id);
+
+        let conn = self.connection()?;
+        let original = Self::query_exercise(&conn, &exercise_id)?;
+        let available_equipment: HashSet<String> = available_equipment.into_iter().collect();
+
+        let mut candidates: Vec<SubstituteCandidate> = Self::query_exercises(&conn, "WHERE id != ?1", [&exercise_id])?
+            .into_iter()
+            .map(|candidate| SubstituteCandidate::score(&original, candidate, &available_equipment))
+            .filter(|candidate| candidate.muscle_overlap_score > 0.0)
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.exercise.name.cmp(&b.exercise.name))
+        });
+        candidates.truncate(limit as usize);
+
+        debug!("📋 Substitutes: {:?}", candidates.iter().map(|c| (&c.exercise.name, c.score)).collect::<Vec<_>>());
+        info!("✅ Found {} substitutes for {}", candidates.len(), original.name);
+        Ok(candidates)
+    }
+}
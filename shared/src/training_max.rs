@@ -0,0 +1,218 @@
+// Training maxes with history and percentage-based prescriptions resolved against them
+
+use crate::error::WeightliftingError;
+use crate::repository::ExerciseRepository;
+use crate::rpe::round_to_increment;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+/// The training max used to compute an exercise's loads from `effective_at` on
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingMax {
+    pub exercise_id: String,
+    pub weight_kg: f64,
+    pub effective_at: i64, // Unix timestamp in seconds
+}
+
+/// Work expressed relative to a training max, e.g. "85% x 5+"
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercentagePrescription {
+    pub exercise_id: String,
+    pub sets: u32,
+    pub reps: u32,
+    pub percentage: f64, // Of the training max
+    pub is_amrap: bool,  // Reps are a minimum; go for as many as possible
+}
+
+/// A percentage prescription turned into a load
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedPrescription {
+    pub prescription: PercentagePrescription,
+    pub training_max_kg: f64,
+    pub weight_kg: f64,    // Rounded to `increment_kg`
+    pub increment_kg: f64, // Smallest increment the load was rounded to
+    pub label: String,
+}
+
+impl PercentagePrescription {
+    /// Validate the prescription's sets, reps and percentage
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sets == 0 || self.reps == 0 {
+            return Err(format!("Prescription needs at least one set and rep, got {} x {}", self.sets, self.reps));
+        }
+
+        if !self.percentage.is_finite() || self.percentage <= 0.0 {
+            return Err(format!("Percentage must be positive, got {}", self.percentage));
+        }
+
+        Ok(())
+    }
+
+    /// Short description such as "85% x 5+" or "3 x 5 @ 75%"
+    pub fn label(&self) -> String {
+        let reps = format!("{}{}", self.reps, if self.is_amrap { "+" } else { "" });
+        if self.sets == 1 {
+            format!("{}% x {}", self.percentage, reps)
+        } else {
+            format!("{} x {} @ {}%", self.sets, reps, self.percentage)
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Record an exercise's training max from `effective_at` on
+    ///
+    /// Recording another max at the same time replaces it; earlier maxes are kept as history.
+    pub fn set_training_max(&self, exercise_id: String, weight_kg: f64, effective_at: i64) -> Result<(), WeightliftingError> {
+        info!("🎯 Setting training max for {}: {} kg", exercise_id, weight_kg);
+
+        if !weight_kg.is_finite() || weight_kg <= 0.0 {
+            warn!("❌ Invalid training max: {}", weight_kg);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Training max must be positive, got {}", weight_kg),
+            });
+        }
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        conn.execute(
+            "INSERT INTO training_maxes (exercise_id, weight_kg, effective_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(exercise_id, effective_at) DO UPDATE SET weight_kg = excluded.weight_kg",
+            params![exercise_id, weight_kg, effective_at],
+        )?;
+
+        info!("✅ Training max saved");
+        Ok(())
+    }
+
+    /// Get the training max in effect for an exercise at `at`, if any
+    pub fn get_training_max(&self, exercise_id: String, at: i64) -> Result<Option<TrainingMax>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        Self::query_training_max(&conn, &exercise_id, at)
+    }
+
+    /// Get every training max recorded for an exercise, oldest first
+    pub fn get_training_max_history(&self, exercise_id: String) -> Result<Vec<TrainingMax>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT exercise_id, weight_kg, effective_at FROM training_maxes
+             WHERE exercise_id = ?1 ORDER BY effective_at",
+        )?;
+        let history = stmt.query_map([&exercise_id], training_max_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(history)
+    }
+
+    /// Delete the training max recorded for an exercise at `effective_at`
+    /// Returns true if the training max was deleted, false if it wasn't found
+    pub fn delete_training_max(&self, exercise_id: String, effective_at: i64) -> Result<bool, WeightliftingError> {
+        info!("🗑️ Deleting training max for {} at {}", exercise_id, effective_at);
+
+        let conn = self.connection()?;
+        let rows_affected = conn.execute(
+            "DELETE FROM training_maxes WHERE exercise_id = ?1 AND effective_at = ?2",
+            params![exercise_id, effective_at],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Turn percentage prescriptions into loads from the training maxes in effect at `at`
+    ///
+    /// Loads are rounded to the gym's smallest increment, or to the configured
+    /// load increment when no gym is given or the gym doesn't set one.
+    pub fn resolve_prescriptions(
+        &self,
+        prescriptions: Vec<PercentagePrescription>,
+        gym_id: Option<String>,
+        at: i64,
+    ) -> Result<Vec<ResolvedPrescription>, WeightliftingError> {
+        info!("🧮 Resolving {} percentage prescriptions", prescriptions.len());
+
+        if let Some(message) = prescriptions.iter().find_map(|p| p.validate().err()) {
+            warn!("❌ Invalid prescription: {}", message);
+            return Err(WeightliftingError::InvalidInput { message });
+        }
+
+        let conn = self.connection()?;
+        let gym_increment = match gym_id {
+            Some(ref id) => Self::query_gym_profile(&conn, id)?.smallest_increment_kg,
+            None => None,
+        };
+        let increment_kg = match gym_increment {
+            Some(increment) => increment,
+            None => Self::load_increment(&conn)?,
+        };
+        debug!("📏 Rounding loads to {} kg", increment_kg);
+
+        let mut resolved = Vec::with_capacity(prescriptions.len());
+        for prescription in prescriptions {
+            Self::ensure_exercise_exists(&conn, &prescription.exercise_id)?;
+            let training_max = Self::query_training_max(&conn, &prescription.exercise_id, at)?.ok_or_else(|| {
+                warn!("❌ No training max for exercise {}", prescription.exercise_id);
+                WeightliftingError::InsufficientData {
+                    message: format!("No training max set for exercise {}", prescription.exercise_id),
+                }
+            })?;
+
+            resolved.push(ResolvedPrescription {
+                training_max_kg: training_max.weight_kg,
+                weight_kg: round_to_increment(training_max.weight_kg * prescription.percentage / 100.0, increment_kg),
+                increment_kg,
+                label: prescription.label(),
+                prescription,
+            });
+        }
+
+        info!("✅ Resolved {} prescriptions", resolved.len());
+        Ok(resolved)
+    }
+
+    fn query_training_max(conn: &Connection, exercise_id: &str, at: i64) -> Result<Option<TrainingMax>, WeightliftingError> {
+        let training_max = conn.query_row(
+            "SELECT exercise_id, weight_kg, effective_at FROM training_maxes
+             WHERE exercise_id = ?1 AND effective_at <= ?2 ORDER BY effective_at DESC LIMIT 1",
+            params![exercise_id, at],
+            training_max_from_row,
+        ).optional()?;
+        Ok(training_max)
+    }
+
+    /// Remove all training maxes of an exercise
+    pub(crate) fn delete_training_maxes(conn: &Connection, exercise_id: &str) -> Result<(), WeightliftingError> {
+        conn.execute("DELETE FROM training_maxes WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(())
+    }
+
+    /// Create the training max table if it doesn't exist
+    pub(crate) fn create_training_max_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating training_maxes table if not exists");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS training_maxes (
+                exercise_id TEXT NOT NULL,
+                weight_kg REAL NOT NULL,
+                effective_at INTEGER NOT NULL,
+                PRIMARY KEY (exercise_id, effective_at)
+            )",
+            [],
+        ).map_err(|e| {
+            error!("❌ Failed to create training_maxes table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Training max table ready");
+        Ok(())
+    }
+}
+
+fn training_max_from_row(row: &Row) -> rusqlite::Result<TrainingMax> {
+    Ok(TrainingMax {
+        exercise_id: row.get(0)?,
+        weight_kg: row.get(1)?,
+        effective_at: row.get(2)?,
+    })
+}
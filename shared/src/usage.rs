@@ -0,0 +1,135 @@
+// Per-exercise usage statistics and recency/frequency-ranked exercise lists
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use crate::repository::ExerciseRepository;
+use log::{debug, info};
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Days after which a use counts half as much towards the frecency score
+const FRECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// How often and how recently an exercise has been trained
+///
+/// A use is one workout session; sets logged without a session count as one
+/// use per UTC day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseUsage {
+    pub exercise_id: String,
+    pub use_count: u32,
+    pub set_count: u32,
+    pub last_used_at: Option<i64>, // Unix timestamp of the latest set, None if never used
+    pub frecency_score: f64,       // Sum of uses, each decayed by its age
+}
+
+/// Order for exercise listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExerciseSortOrder {
+    Name,
+    RecentlyUsed,
+    MostUsed,
+    Frecency,
+}
+
+/// Weight of a single use `age_seconds` old; future timestamps count as now
+fn decayed_use_weight(age_seconds: i64) -> f64 {
+    let age_days = age_seconds.max(0) as f64 / SECONDS_PER_DAY;
+    0.5_f64.powf(age_days / FRECENCY_HALF_LIFE_DAYS)
+}
+
+impl ExerciseUsage {
+    fn unused(exercise_id: String) -> Self {
+        Self {
+            exercise_id,
+            use_count: 0,
+            set_count: 0,
+            last_used_at: None,
+            frecency_score: 0.0,
+        }
+    }
+}
+
+impl ExerciseRepository {
+    /// Get usage statistics for one exercise as of `now`
+    pub fn get_exercise_usage(&self, exercise_id: String, now: i64) -> Result<ExerciseUsage, WeightliftingError> {
+        info!("📈 Computing usage for exercise {}", exercise_id);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+        let mut usage = Self::query_usage(&conn, now)?;
+        Ok(usage.remove(&exercise_id).unwrap_or_else(|| ExerciseUsage::unused(exercise_id)))
+    }
+
+    /// Get usage statistics for every exercise as of `now`, highest frecency first
+    pub fn get_all_exercise_usage(&self, now: i64) -> Result<Vec<ExerciseUsage>, WeightliftingError> {
+        info!("📈 Computing usage for all exercises");
+
+        let conn = self.connection()?;
+        let mut usage = Self::query_usage(&conn, now)?;
+        let mut all_usage: Vec<ExerciseUsage> = Self::query_exercises(&conn, "ORDER BY name", [])?
+            .into_iter()
+            .map(|exercise| usage.remove(&exercise.id).unwrap_or_else(|| ExerciseUsage::unused(exercise.id)))
+            .collect();
+        // Stable sort keeps never-used exercises in name order
+        all_usage.sort_by(|a, b| b.frecency_score.total_cmp(&a.frecency_score));
+
+        Ok(all_usage)
+    }
+
+    /// Get all exercises in the given order as of `now`
+    ///
+    /// Usage-based orders fall back to name for ties and never-used exercises.
+    pub fn get_exercises_sorted(&self, order: ExerciseSortOrder, now: i64) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("📚 Retrieving exercises sorted by {:?}", order);
+
+        let conn = self.connection()?;
+        let mut exercises = Self::query_exercises(&conn, "ORDER BY name", [])?;
+        if order == ExerciseSortOrder::Name {
+            return Ok(exercises);
+        }
+
+        let usage = Self::query_usage(&conn, now)?;
+        let unused = ExerciseUsage::unused(String::new());
+        let usage_of = |exercise: &Exercise| usage.get(&exercise.id).unwrap_or(&unused);
+        exercises.sort_by(|a, b| {
+            let (a, b) = (usage_of(a), usage_of(b));
+            match order {
+                ExerciseSortOrder::RecentlyUsed => b.last_used_at.cmp(&a.last_used_at),
+                ExerciseSortOrder::MostUsed => b.use_count.cmp(&a.use_count).then(b.last_used_at.cmp(&a.last_used_at)),
+                _ => b.frecency_score.total_cmp(&a.frecency_score),
+            }
+        });
+
+        info!("✅ Sorted {} exercises", exercises.len());
+        Ok(exercises)
+    }
+
+    /// Aggregate logged sets into usage statistics, keyed by exercise
+    fn query_usage(conn: &Connection, now: i64) -> Result<HashMap<String, ExerciseUsage>, WeightliftingError> {
+        let mut stmt = conn.prepare(
+            "SELECT exercise_id, COUNT(*), MAX(performed_at) FROM logged_sets
+             GROUP BY exercise_id, COALESCE(session_id, 'day:' || (performed_at / 86400))",
+        )?;
+        let uses = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, i64>(2)?))
+        })?;
+
+        let mut usage: HashMap<String, ExerciseUsage> = HashMap::new();
+        for entry in uses {
+            let (exercise_id, set_count, used_at) = entry?;
+            let stats = usage
+                .entry(exercise_id.clone())
+                .or_insert_with(|| ExerciseUsage::unused(exercise_id));
+            stats.use_count += 1;
+            stats.set_count += set_count;
+            stats.last_used_at = stats.last_used_at.max(Some(used_at));
+            stats.frecency_score += decayed_use_weight(now - used_at);
+        }
+
+        debug!("📊 Usage computed for {} exercises", usage.len());
+        Ok(usage)
+    }
+}
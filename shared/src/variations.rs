@@ -0,0 +1,223 @@
+// Parent/variation relationships between exercises and roll-up analytics
+
+use crate::error::WeightliftingError;
+use crate::models::{Exercise, LoggedSet};
+use crate::repository::ExerciseRepository;
+use crate::rpe::estimate_logged_one_rm;
+use log::{debug, error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Training totals for an exercise, optionally including its variations
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseSummary {
+    pub exercise_id: String,
+    pub included_exercise_ids: Vec<String>,
+    pub set_count: u32,
+    pub total_reps: u32,
+    pub total_volume_kg: f64,
+    pub best_estimated_one_rm_kg: Option<f64>,
+    pub last_performed_at: Option<i64>,
+}
+
+impl ExerciseRepository {
+    /// Make `exercise_id` a variation of `parent_id`, or a base movement when None
+    ///
+    /// Fails with InvalidInput if the change would make an exercise its own ancestor.
+    pub fn set_exercise_parent(&self, exercise_id: String, parent_id: Option<String>) -> Result<(), WeightliftingError> {
+        info!("🌳 Setting parent of {} to {:?}", exercise_id, parent_id);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let Some(parent_id) = parent_id else {
+            conn.execute("DELETE FROM exercise_variations WHERE exercise_id = ?1", [&exercise_id])?;
+            info!("✅ {} is now a base movement", exercise_id);
+            return Ok(());
+        };
+
+        Self::ensure_exercise_exists(&conn, &parent_id)?;
+        if Self::query_ancestor_ids(&conn, &parent_id)?.contains(&exercise_id) || parent_id == exercise_id {
+            warn!("❌ Making {} a variation of {} would create a cycle", exercise_id, parent_id);
+            return Err(WeightliftingError::InvalidInput {
+                message: format!("Making {} a variation of {} would create a cycle", exercise_id, parent_id),
+            });
+        }
+
+        conn.execute(
+            "INSERT INTO exercise_variations (exercise_id, parent_id) VALUES (?1, ?2)
+             ON CONFLICT(exercise_id) DO UPDATE SET parent_id = excluded.parent_id",
+            params![exercise_id, parent_id],
+        )?;
+
+        info!("✅ {} is now a variation of {}", exercise_id, parent_id);
+        Ok(())
+    }
+
+    /// Get the exercise this one is a variation of, if any
+    pub fn get_exercise_parent(&self, exercise_id: String) -> Result<Option<Exercise>, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        match Self::query_parent_id(&conn, &exercise_id)? {
+            Some(parent_id) => Ok(Some(Self::query_exercise(&conn, &parent_id)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the base movement at the top of an exercise's hierarchy (itself if it has no parent)
+    pub fn get_base_exercise(&self, exercise_id: String) -> Result<Exercise, WeightliftingError> {
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let base_id = Self::query_ancestor_ids(&conn, &exercise_id)?
+            .pop()
+            .unwrap_or(exercise_id);
+        Self::query_exercise(&conn, &base_id)
+    }
+
+    /// Get the variations of an exercise, sorted by name
+    ///
+    /// With `recursive`, variations of variations are included as well.
+    pub fn get_variations(&self, exercise_id: String, recursive: bool) -> Result<Vec<Exercise>, WeightliftingError> {
+        info!("🌳 Retrieving {} variations of {}", if recursive { "all" } else { "direct" }, exercise_id);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let variations = if recursive {
+            Self::query_exercises(
+                &conn,
+                "WHERE id IN (
+                    WITH RECURSIVE descendants(id) AS (
+                        SELECT exercise_id FROM exercise_variations WHERE parent_id = ?1
+                        UNION
+                        SELECT v.exercise_id FROM exercise_variations v JOIN descendants d ON v.parent_id = d.id
+                    )
+                    SELECT id FROM descendants
+                ) ORDER BY name",
+                [&exercise_id],
+            )?
+        } else {
+            Self::query_exercises(
+                &conn,
+                "WHERE id IN (SELECT exercise_id FROM exercise_variations WHERE parent_id = ?1) ORDER BY name",
+                [&exercise_id],
+            )?
+        };
+
+        info!("✅ Found {} variations", variations.len());
+        Ok(variations)
+    }
+
+    /// Summarize the sets logged for an exercise, rolling up all of its variations when asked
+    pub fn get_exercise_summary(&self, exercise_id: String, include_variations: bool) -> Result<ExerciseSummary, WeightliftingError> {
+        info!("📊 Summarizing exercise {} (variations: {})", exercise_id, include_variations);
+
+        let conn = self.connection()?;
+        Self::ensure_exercise_exists(&conn, &exercise_id)?;
+
+        let mut included_exercise_ids = vec![exercise_id.clone()];
+        if include_variations {
+            included_exercise_ids.extend(Self::query_descendant_ids(&conn, &exercise_id)?);
+        }
+
+        let sets = Self::query_sets_for_exercises(&conn, &included_exercise_ids, i64::MIN, i64::MAX)?;
+        let summary = ExerciseSummary {
+            exercise_id,
+            included_exercise_ids,
+            set_count: sets.len() as u32,
+            total_reps: sets.iter().map(LoggedSet::total_reps).sum(),
+            total_volume_kg: sets.iter().map(|set| Self::set_volume(&conn, set)).sum::<Result<f64, _>>()?,
+            best_estimated_one_rm_kg: sets.iter().filter_map(estimate_logged_one_rm).reduce(f64::max),
+            last_performed_at: sets.iter().map(|s| s.performed_at).max(),
+        };
+
+        debug!("📋 Summary: {:?}", summary);
+        Ok(summary)
+    }
+
+    /// Query the sets of several exercises between `start` and `end` (inclusive), oldest first
+    pub(crate) fn query_sets_for_exercises(conn: &Connection, exercise_ids: &[String], start: i64, end: i64) -> Result<Vec<LoggedSet>, WeightliftingError> {
+        let mut sets = Vec::new();
+        for exercise_id in exercise_ids {
+            sets.extend(Self::query_sets_between(conn, Some(exercise_id), start, end)?);
+        }
+        sets.sort_by_key(|s| s.performed_at);
+        Ok(sets)
+    }
+
+    /// IDs of every variation below an exercise
+    pub(crate) fn query_descendant_ids(conn: &Connection, exercise_id: &str) -> Result<Vec<String>, WeightliftingError> {
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE descendants(id) AS (
+                SELECT exercise_id FROM exercise_variations WHERE parent_id = ?1
+                UNION
+                SELECT v.exercise_id FROM exercise_variations v JOIN descendants d ON v.parent_id = d.id
+            )
+            SELECT id FROM descendants",
+        )?;
+        let ids = stmt.query_map([exercise_id], |row| row.get(0))?.collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+
+    /// IDs of an exercise's ancestors, nearest parent first
+    fn query_ancestor_ids(conn: &Connection, exercise_id: &str) -> Result<Vec<String>, WeightliftingError> {
+        let mut ancestors: Vec<String> = Vec::new();
+        let mut current = exercise_id.to_string();
+
+        while let Some(parent_id) = Self::query_parent_id(conn, &current)? {
+            if parent_id == exercise_id || ancestors.contains(&parent_id) {
+                // Stored data should never contain a cycle, but never loop forever on one
+                error!("❌ Variation cycle detected at {}", parent_id);
+                break;
+            }
+            ancestors.push(parent_id.clone());
+            current = parent_id;
+        }
+        Ok(ancestors)
+    }
+
+    fn query_parent_id(conn: &Connection, exercise_id: &str) -> Result<Option<String>, WeightliftingError> {
+        let parent_id = conn.query_row(
+            "SELECT parent_id FROM exercise_variations WHERE exercise_id = ?1",
+            [exercise_id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(parent_id)
+    }
+
+    /// Remove an exercise from the hierarchy, moving its variations up to its parent
+    pub(crate) fn detach_variations(conn: &Connection, exercise_id: &str) -> Result<(), WeightliftingError> {
+        match Self::query_parent_id(conn, exercise_id)? {
+            Some(parent_id) => conn.execute(
+                "UPDATE exercise_variations SET parent_id = ?1 WHERE parent_id = ?2",
+                params![parent_id, exercise_id],
+            )?,
+            None => conn.execute("DELETE FROM exercise_variations WHERE parent_id = ?1", [exercise_id])?,
+        };
+        conn.execute("DELETE FROM exercise_variations WHERE exercise_id = ?1", [exercise_id])?;
+        Ok(())
+    }
+
+    /// Create the exercise variations table if it doesn't exist
+    pub(crate) fn create_variation_tables(conn: &Connection) -> Result<(), WeightliftingError> {
+        info!("🏗️ Creating exercise_variations table if not exists");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS exercise_variations (
+                exercise_id TEXT PRIMARY KEY,
+                parent_id TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_exercise_variations_parent
+                ON exercise_variations (parent_id);",
+        ).map_err(|e| {
+            error!("❌ Failed to create exercise_variations table: {}", e);
+            WeightliftingError::DatabaseError {
+                message: format!("Failed to create table: {}", e),
+            }
+        })?;
+
+        debug!("✅ Exercise variations table ready");
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+// Warm-up ramps to a working weight, loaded from the plates on hand
+
+use crate::error::WeightliftingError;
+use crate::models::Exercise;
+use log::{debug, info, warn};
+use std::collections::BTreeMap;
+
+/// Tolerance when comparing loads built from fractional plates
+const LOAD_EPSILON_KG: f64 = 1e-6;
+
+/// One step of a warm-up scheme
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmupStep {
+    pub percentage: f64, // Of the working weight; 0 means the empty bar or bodyweight alone
+    pub reps: u32,
+}
+
+/// How to ramp up to the working weight
+#[derive(Debug, Clone, PartialEq)]
+pub enum WarmupScheme {
+    /// Empty bar, then 40/60/80%
+    Standard,
+    /// Longer ramp with a final single, for heavy top sets
+    Extended,
+    /// Two quick sets at 50/75%
+    Minimal,
+    Custom { steps: Vec<WarmupStep> },
+}
+
+/// Plates of one weight available in the gym
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateCount {
+    pub weight_kg: f64,
+    pub count: u32, // Total plates, loaded in pairs on a bar
+}
+
+/// A generated warm-up set
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmupSet {
+    pub percentage: f64, // Share of the working weight actually loaded
+    pub reps: u32,
+    pub weight_kg: f64,
+    pub plates: Vec<f64>, // Per side of the bar, or the whole added load for bodyweight exercises
+}
+
+fn step(percentage: f64, reps: u32) -> WarmupStep {
+    WarmupStep { percentage, reps }
+}
+
+impl WarmupScheme {
+    /// The scheme's steps, lightest first
+    pub fn steps(&self) -> Vec<WarmupStep> {
+        match self {
+            WarmupScheme::Standard => vec![step(0.0, 10), step(40.0, 5), step(60.0, 3), step(80.0, 2)],
+            WarmupScheme::Extended => vec![
+                step(0.0, 10),
+                step(30.0, 8),
+                step(45.0, 5),
+                step(60.0, 4),
+                step(75.0, 3),
+                step(85.0, 2),
+                step(92.0, 1),
+            ],
+            WarmupScheme::Minimal => vec![step(50.0, 5), step(75.0, 2)],
+            WarmupScheme::Custom { steps } => steps.clone(),
+        }
+    }
+}
+
+/// Heaviest combination of plates not above `target_kg`, largest plates first
+///
+/// With `paired` each plate type can be used `count / 2` times, one per side.
+/// Searches every reachable total rather than loading greedily, so 30 kg from
+/// 25/15/10 kg plates is 15 + 15 rather than 25; ties go to fewer plates.
+fn load_plates(target_kg: f64, plates: &[PlateCount], paired: bool) -> Vec<f64> {
+    let mut available: Vec<f64> = plates
+        .iter()
+        .flat_map(|p| std::iter::repeat_n(p.weight_kg, (if paired { p.count / 2 } else { p.count }) as usize))
+        .collect();
+    available.sort_by(|a, b| b.total_cmp(a));
+
+    // Work in grams so equal totals built from fractional plates compare equal
+    let grams = |kg: f64| (kg * 1000.0).round() as i64;
+    let target_g = ((target_kg + LOAD_EPSILON_KG) * 1000.0).floor() as i64;
+
+    // Fewest plates reaching each total, bounded by the target
+    let mut reachable: BTreeMap<i64, Vec<f64>> = BTreeMap::from([(0, Vec::new())]);
+    for weight_kg in available {
+        let weight_g = grams(weight_kg);
+        let extended: Vec<(i64, Vec<f64>)> = reachable
+            .iter()
+            .filter(|(&total, _)| total + weight_g <= target_g)
+            .map(|(&total, loaded)| (total + weight_g, [loaded.as_slice(), &[weight_kg]].concat()))
+            .collect();
+        for (total, loaded) in extended {
+            match reachable.get(&total) {
+                Some(existing) if existing.len() <= loaded.len() => {}
+                _ => {
+                    reachable.insert(total, loaded);
+                }
+            }
+        }
+    }
+    reachable.pop_last().map(|(_, loaded)| loaded).unwrap_or_default()
+}
+
+/// Total weight of some plates; an empty list is +0.0 rather than the -0.0 `sum` gives
+fn total_kg(plates: &[f64]) -> f64 {
+    plates.iter().fold(0.0, |total, weight_kg| total + weight_kg)
+}
+
+fn validate_inputs(working_weight_kg: f64, bar_weight_kg: f64, plates: &[PlateCount], steps: &[WarmupStep]) -> Result<(), String> {
+    if !working_weight_kg.is_finite() || working_weight_kg < 0.0 {
+        return Err(format!("Working weight must be a non-negative number, got {}", working_weight_kg));
+    }
+
+    if !bar_weight_kg.is_finite() || bar_weight_kg < 0.0 {
+        return Err(format!("Bar weight must be a non-negative number, got {}", bar_weight_kg));
+    }
+
+    if let Some(plate) = plates.iter().find(|p| !p.weight_kg.is_finite() || p.weight_kg <= 0.0) {
+        return Err(format!("Plate weights must be positive, got {}", plate.weight_kg));
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        if !(0.0..100.0).contains(&step.percentage) {
+            return Err(format!("Warm-up percentages must be at least 0 and below 100, got {}", step.percentage));
+        }
+        if step.reps == 0 {
+            return Err("Warm-up steps need at least one rep".to_string());
+        }
+        if i > 0 && step.percentage <= steps[i - 1].percentage {
+            return Err("Warm-up percentages must increase from step to step".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a warm-up ramp to `working_weight_kg` using the plates on hand
+///
+/// Exercises that require equipment are loaded on a bar of `bar_weight_kg`
+/// with plates in pairs. Bodyweight exercises ignore the bar and ramp the
+/// added load instead, so without added load they get no warm-up sets.
+/// Loads are rounded down to what the plates allow; steps that land on the
+/// previous step's load are merged, keeping the later reps, and steps that
+/// would reach the working weight are dropped.
+pub fn generate_warmup(
+    exercise: Exercise,
+    working_weight_kg: f64,
+    bar_weight_kg: f64,
+    plates: Vec<PlateCount>,
+    scheme: WarmupScheme,
+) -> Result<Vec<WarmupSet>, WeightliftingError> {
+    info!("🔥 Generating {:?} warm-up for {} to {} kg", scheme, exercise.name, working_weight_kg);
+
+    let steps = scheme.steps();
+    let uses_bar = exercise.requires_equipment();
+    validate_inputs(working_weight_kg, bar_weight_kg, &plates, &steps)
+        .and_then(|_| {
+            if uses_bar && working_weight_kg < bar_weight_kg {
+                Err(format!("Working weight {} kg is lighter than the {} kg bar", working_weight_kg, bar_weight_kg))
+            } else {
+                Ok(())
+            }
+        })
+        .map_err(|message| {
+            warn!("❌ Invalid warm-up request: {}", message);
+            WeightliftingError::InvalidInput { message }
+        })?;
+
+    let mut sets: Vec<WarmupSet> = Vec::new();
+    for step in steps {
+        let target_kg = working_weight_kg * step.percentage / 100.0;
+        let (weight_kg, plates) = if uses_bar {
+            let per_side = load_plates((target_kg - bar_weight_kg).max(0.0) / 2.0, &plates, true);
+            (bar_weight_kg + 2.0 * total_kg(&per_side), per_side)
+        } else {
+            let added = load_plates(target_kg, &plates, false);
+            (total_kg(&added), added)
+        };
+
+        if weight_kg >= working_weight_kg - LOAD_EPSILON_KG {
+            debug!("⏭️ Dropping {}% step, it reaches the working weight", step.percentage);
+            continue;
+        }
+
+        let set = WarmupSet {
+            percentage: weight_kg / working_weight_kg * 100.0,
+            reps: step.reps,
+            weight_kg,
+            plates,
+        };
+        match sets.last_mut() {
+            Some(previous) if weight_kg <= previous.weight_kg + LOAD_EPSILON_KG => *previous = set,
+            _ => sets.push(set),
+        }
+    }
+
+    info!("✅ Generated {} warm-up sets", sets.len());
+    Ok(sets)
+}